@@ -3,15 +3,12 @@
 
 set -e
 
-if [ -d "src/wasm/pkg" ]; then
-  echo "✅ Skipping WASM build (pkg/ already exists)"
-else
-  echo "🦀 Building Rust WASM module..."
-  cd src/wasm
-  npx wasm-pack build --target web --out-dir pkg --release
-  cd ../..
-  echo "✅ WASM build complete!"
-fi
+# Always rebuild: a stale pkg/ is missing whatever the core exported since
+echo "🦀 Building Rust WASM module..."
+cd src/wasm
+npx wasm-pack build --target web --out-dir pkg --release
+cd ../..
+echo "✅ WASM build complete!"
 
 echo "🏗️ Building Vite frontend..."
 npm run build-only
@@ -14,6 +14,7 @@ async function initWasm() {
     wasmCore = new wasm.MiraCore()
     wasmReady = true
     console.log('✅ WASM Core initialized')
+    restoreSessionState()
     return wasmCore
   } catch (error) {
     console.warn('⚠️ WASM failed, fallback to JS:', error)
@@ -21,6 +22,33 @@ async function initWasm() {
   }
 }
 
+// ========== SESSION STATE PERSISTENCE ==========
+// Snapshot state core (mood, cache, affect, rng, mirror) di localStorage,
+// supaya reload halaman lanjut sesi yang sama
+const SESSION_STATE_KEY = 'mira-session-state'
+
+function restoreSessionState() {
+  const saved = localStorage.getItem(SESSION_STATE_KEY)
+  if (!saved) return
+  try {
+    wasmCore.import_state(saved)
+    console.log('💾 Session state restored')
+  } catch (error) {
+    // Snapshot rusak atau dari versi lebih baru: mulai sesi baru
+    console.warn('⚠️ Could not restore session state:', error.message)
+    localStorage.removeItem(SESSION_STATE_KEY)
+  }
+}
+
+function persistSessionState() {
+  if (!wasmReady || !wasmCore) return
+  try {
+    localStorage.setItem(SESSION_STATE_KEY, wasmCore.export_state())
+  } catch (error) {
+    console.warn('⚠️ Could not persist session state:', error.message)
+  }
+}
+
 // ========== CUSTOM ERROR HANDLING ==========
 class WasmError extends Error {
   constructor(operation, originalError) {
@@ -141,8 +169,9 @@ Sekarang chat naturally as MIRA!`,
       return this.wasmEnabled
     },
 
-    addAnswer(role, content) {
-      this.displayAnswers.push({ role, content })
+    // `bubbles`: potongan balasan yang sudah tampil; content tetap utuh
+    addAnswer(role, content, bubbles = null) {
+      this.displayAnswers.push(bubbles ? { role, content, bubbles } : { role, content })
     },
 
     clearAnswers() {
@@ -170,6 +199,18 @@ Sekarang chat naturally as MIRA!`,
     analyzeUserEmotionWasm(userInput) {
       if (!wasmCore) return null
       const result = wasmCore.calculate_sentiment_advanced(userInput)
+      // Observer di bawah cuma update state core; kalau gagal, hasil
+      // sentiment di atas tetap dipakai (jangan jatuh ke JS path)
+      try {
+        // Channel user: dipakai resolve_display_expression buat empathy
+        wasmCore.detect_user_emotion(userInput)
+        // Affect MIRA sendiri (energy, warmth, rapport) ikut berubah per pesan
+        wasmCore.observe_user_message(userInput, Date.now())
+        // Register & sapaan user (aku/kamu, gue/lo, kak) buat di-mirror di balasan
+        wasmCore.observe_user_register(userInput)
+      } catch (error) {
+        console.warn('Failed to observe user message:', error.message)
+      }
 
       this.currentEmotionIntensity = result.intensity
       this.emotionHistory.push({
@@ -339,7 +380,7 @@ Sekarang chat naturally as MIRA!`,
         }
       }
 
-      return [...messages, ...this.displayAnswers]
+      return [...messages, ...this.displayAnswers.map(({ role, content }) => ({ role, content }))]
     },
 
     buildDynamicSystemPrompt() {
@@ -369,6 +410,15 @@ Sekarang chat naturally as MIRA!`,
         ? `Overall trend: ${this.moodTrendline.trend} (avg intensity: ${(this.moodTrendline.avgIntensity * 100).toFixed(0)}%)`
         : ''
 
+      let affectContext = ''
+      if (this.wasmEnabled && wasmCore) {
+        try {
+          affectContext = wasmCore.affect_prompt_context(Date.now())
+        } catch (error) {
+          console.warn('Failed to get affect context:', error)
+        }
+      }
+
       const systemPrompt = `# MIRA Personality Core (Dynamic v2.0)
 
 Kamu adalah MIRA, teman curhat 23 tahun yang asik, empatik, dan tahu kapan serious kapan jail.
@@ -378,6 +428,7 @@ Bukan AI assistant—kamu temen ngobrol yang beneran peduli.
 **Mood Sekarang**: ${this.currentMood} → ${moodTone[this.currentMood]}
 **Emotion Level**: ${(this.currentEmotionIntensity * 100).toFixed(0)}% intensity
 **User State**: ${emotionalContext}
+${affectContext}
 
 ## Recent Emotion Pattern (Last 3 Messages)
 ${recentContext || 'Fresh conversation, jadi relatable aja dulu'}
@@ -418,7 +469,8 @@ Respond as MIRA—be genuine, be there, be real.`
           role: 'system',
           content: this.buildDynamicSystemPrompt(),
         },
-        ...this.displayAnswers,
+        // Cuma role & content yang dikirim ke LLM (tanpa daftar bubbles)
+        ...this.displayAnswers.map(({ role, content }) => ({ role, content })),
       ]
 
       return messages
@@ -435,7 +487,10 @@ Respond as MIRA—be genuine, be there, be real.`
           'expression_detection',
           () => {
             if (!wasmCore) throw new Error('WASM not ready')
-            return wasmCore.detect_expression_with_intensity(text)
+            // Text di sini reply MIRA: baca pakai bot-speech lexicon,
+            // lalu gabung dengan emosi user lewat empathy table
+            wasmCore.detect_reply_emotion(text)
+            return wasmCore.resolve_display_expression()
           },
           null,
         )
@@ -444,12 +499,10 @@ Respond as MIRA—be genuine, be there, be real.`
           return this.detectExpressionFallback(text)
         }
 
-        const primaryExpr = this.mapEmotionToExpression(detection.primary)
-        const secondaryExpr = this.mapEmotionToExpression(detection.secondary)
-
+        // expression/secondary sudah ID dari registry model, pakai langsung
         this.expressionBlend = {
-          primary: primaryExpr,
-          secondary: secondaryExpr,
+          primary: detection.expression,
+          secondary: detection.secondary,
           intensity: detection.intensity,
           confidence: detection.confidence,
           blendStrength: detection.intensity * detection.confidence,
@@ -463,6 +516,20 @@ Respond as MIRA—be genuine, be there, be real.`
       }
     },
 
+    /**
+     * Pilih motion (group + index) untuk expression via WASM core.
+     * Returns null kalau WASM off atau semua motion lagi cooldown.
+     */
+    selectMotion(expressionKey) {
+      if (!this.wasmEnabled || !wasmCore) return null
+      try {
+        return wasmCore.select_motion(expressionKey, Date.now()) ?? null
+      } catch (error) {
+        console.warn('Motion selection error:', error.message)
+        return null
+      }
+    },
+
     /**
      * Legacy expression detection (untuk backward compatibility)
      */
@@ -501,39 +568,6 @@ Respond as MIRA—be genuine, be there, be real.`
       }
     },
 
-    /**
-     * Map emotion category to actual Live2D expression (f01-f04)
-     */
-    mapEmotionToExpression(emotionKey) {
-      const emotionMap = {
-        // Primary expressions
-        f01: 'f01',
-        f02: 'f02',
-        f03: 'f03',
-        f04: 'f04',
-
-        // Category to expression mapping
-        positive_high: 'f02',
-        positive_medium: 'f02',
-        negative_high: 'f03',
-        negative_medium: 'f03',
-        curious: 'f04',
-        confused: 'f04',
-
-        // Mood to expression
-        playful: 'f02',
-        reflective: 'f03',
-        chill: 'f01',
-        default: 'f01',
-        happy: 'f02',
-        sad: 'f03',
-        surprised: 'f04',
-        neutral: 'f01',
-      }
-
-      return emotionMap[emotionKey] || 'f01'
-    },
-
     /**
      * Get expression blend info untuk smooth transitions
      */
@@ -730,6 +764,7 @@ Respond as MIRA—be genuine, be there, be real.`
             console.log(`🦀 Mood transition: ${this.currentMood} → ${newMood}`)
             this.currentMood = newMood
           }
+          persistSessionState()
           return { mood: this.currentMood, emotion: analysis }
         } catch (error) {
           console.warn('Mood detection failed:', error)
@@ -742,11 +777,37 @@ Respond as MIRA—be genuine, be there, be real.`
 })
 
 // ===== RESPONSE HUMANIZER (WASM-powered) =====
+// Bersihkan output LLM (think block, heading, frasa "sebagai AI") dan cek persona
+export function enforcePersona(response) {
+  if (wasmReady && wasmCore) {
+    try {
+      return wasmCore.enforce_persona(response)
+    } catch (error) {
+      console.warn('WASM persona check failed', error)
+    }
+  }
+  return { text: response, compliant: true, regenerate: false, violations: [] }
+}
+
+// Pecah balasan jadi beberapa bubble chat + jeda mengetik per bubble
+export function planBubbles(text) {
+  if (wasmReady && wasmCore) {
+    try {
+      return wasmCore.split_bubbles(text)
+    } catch (error) {
+      console.warn('WASM bubble split failed', error)
+    }
+  }
+  return { bubbles: [{ text, typing_ms: 1000, at_ms: 1000 }], total_ms: 1000 }
+}
+
 export async function humanizeResponse(response) {
   if (wasmReady && wasmCore) {
     try {
       const result = wasmCore.humanize_response(response)
       console.log('🦀 WASM humanize used')
+      // Humanize ikut geser rng, emoji terakhir dan jeda sapaan
+      persistSessionState()
       return result
     } catch (error) {
       console.warn('WASM humanize failed, using JS fallback', error)
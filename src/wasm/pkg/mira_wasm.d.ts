@@ -1,69 +1,649 @@
 /* tslint:disable */
 /* eslint-disable */
+export function seal_state(state: string, passphrase: string): string;
 export function quick_mood_check(input: string): string;
+/**
+ * Re-encrypt a sealed snapshot under a new passphrase (fresh salt and nonce).
+ */
+export function rotate_sealed_state(sealed: string, old_passphrase: string, new_passphrase: string): string;
+export function unseal_state(sealed: string, passphrase: string): string;
 export function polish_text(text: string): string;
+/**
+ * Crossfades between expressions. Time is always passed in (`now_ms`), so
+ * the same inputs give the same frames in tests, replays and the browser.
+ */
+export class ExpressionInterpolator {
+  free(): void;
+  [Symbol.dispose](): void;
+  is_animating(): boolean;
+  /**
+   * Start a transition now, replacing anything running or chained. The
+   * new one starts from whichever expression dominates at `now_ms`.
+   * `easing` is anything `Easing::parse` accepts.
+   */
+  transition_to(to: string, duration_ms: number, easing: string, now_ms: number): void;
+  current_expression(): string;
+  constructor(initial_expression: string);
+  /**
+   * Queue a transition to run after the last one, or right away when idle.
+   */
+  then(to: string, duration_ms: number, easing: string, now_ms: number): void;
+  /**
+   * Drop the running and chained transitions and settle on the expression
+   * that dominates at `now_ms`. Returns that expression.
+   */
+  cancel(now_ms: number): string;
+  sample(now_ms: number): any;
+}
+/**
+ * Turns a pointer (or any target) position on the canvas into smoothed
+ * head, eye and body angles. Springs are stepped by the time between
+ * `update` calls, so results depend only on the inputs and `now_ms`.
+ */
+export class GazeSolver {
+  free(): void;
+  [Symbol.dispose](): void;
+  /**
+   * Canvas size and where the face is on it, all in the same units as the
+   * target (usually CSS pixels).
+   */
+  set_canvas(width: number, height: number, face_x: number, face_y: number): void;
+  /**
+   * Maximum head angles and body turn, in degrees.
+   */
+  set_limits(angle_x: number, angle_y: number, angle_z: number, body_x: number): void;
+  set_target(x: number, y: number): void;
+  /**
+   * Pointer left the canvas: ease back to looking straight ahead.
+   */
+  clear_target(): void;
+  update_frame(now_ms: number): any;
+  static parameter_ids(): string[];
+  /**
+   * Look away after this much unbroken eye contact. 0 or less goes back
+   * to the mood's default; `Infinity` never looks away.
+   */
+  set_look_away_after(ms: number): void;
+  /**
+   * The face anchor defaults to the horizontal centre, a third of the way
+   * down, which suits a bust-up model.
+   */
+  constructor(canvas_width: number, canvas_height: number);
+  /**
+   * Step the springs to `now_ms` and get the values, laid out like
+   * `parameter_ids()`.
+   */
+  update(now_ms: number): Float32Array;
+  set_mood(mood: string): void;
+}
+/**
+ * Procedural idle motion between messages: blinks with jitter and the odd
+ * double blink, breathing, small brow/mouth/head drift and glances.
+ * Everything is derived from `seed` and the time passed to `sample`, so the
+ * same seed and calls give the same curves.
+ */
+export class IdleAnimator {
+  free(): void;
+  [Symbol.dispose](): void;
+  /**
+   * 0 (drowsy) to 1 (energetic); clamped. Applied like `set_mood`.
+   */
+  set_energy(energy: number): void;
+  /**
+   * `{time_ms, mood, energy, blinking, glancing, parameters: {ParamId: value}}`
+   */
+  sample_frame(now_ms: number): any;
+  static parameter_ids(): string[];
+  constructor(seed: number, now_ms: number);
+  /**
+   * Parameter values at `now_ms`, laid out like `parameter_ids()`.
+   */
+  sample(now_ms: number): Float32Array;
+  /**
+   * Blinks and glances pick up the new pace from the next one on;
+   * breathing keeps its phase and movement eases over, so nothing jumps.
+   */
+  set_mood(mood: string): void;
+}
+/**
+ * Timed `ParamMouthOpenY` / `ParamMouthForm` keyframes for a piece of text,
+ * for lip-sync when there's no TTS audio to analyse.
+ */
+export class LipSyncTrack {
+  free(): void;
+  [Symbol.dispose](): void;
+  duration_ms(): number;
+  /**
+   * `syllables_per_second` of 0 or less uses the default rate.
+   */
+  constructor(text: string, syllables_per_second: number);
+  /**
+   * `[ParamMouthOpenY, ParamMouthForm]` at `time_ms` into the track.
+   */
+  sample(time_ms: number): Float32Array;
+  segments(): any;
+  keyframes(): any;
+}
+/**
+ * One conversation. Cheap to create from a shared lexicon; handles returned
+ * by `SessionManager` share their state with the manager.
+ */
 export class MiraCore {
   free(): void;
   [Symbol.dispose](): void;
-  constructor();
-  preprocess_input(input: string): any;
-  calculate_sentiment_advanced(text: string): any;
   /**
-   * Legacy method for backward compatibility
+   * `{energy, warmth, rapport, ...}` as of `now_ms`, including the drift
+   * since the last event. Doesn't change the state.
    */
-  calculate_sentiment(text: string): number;
+  get_affect(now_ms: number): any;
+  /**
+   * Force the crisis profile on or off. It is also set (and cleared) by
+   * every `detect_mood` / `detect_user_emotion` call.
+   */
+  set_crisis(active: boolean): void;
   detect_mood(user_input: string): string;
-  get_dominant_mood(): string;
-  calculate_mood_transition(current_mood: string, sentiment_score: number): string;
-  detect_expression(text: string): string;
   /**
-   * Advanced expression detection dengan secondary emotion
+   * Dry run of `detect_mood`: the full decision path for `user_input`
+   * against the current session, without recording anything.
    */
-  detect_expression_with_intensity(text: string): any;
+  explain_mood(user_input: string): any;
+  /**
+   * Serialize the conversation state (moods, cache, history) to a compact,
+   * versioned JSON snapshot that can be persisted and restored later.
+   */
+  export_state(): string;
+  /**
+   * Learned sentiment baseline of this user (samples, mean, std dev, warm-up).
+   */
+  get_baseline(): any;
+  get_register(): string;
+  /**
+   * Restore a snapshot produced by `export_state`. Older versions are
+   * migrated; invalid snapshots are rejected and leave the state untouched.
+   */
+  import_state(json: string): void;
+  /**
+   * Run only the emoji stage of `humanize_response` on `text`: `{text,
+   * placed: [{emoji, kaomoji, placement, keyword}], skipped}`. Replies
+   * that read sad or concerned, crisis replies and serious profiles get
+   * none.
+   */
+  inject_emoji(text: string): any;
+  /**
+   * Register `humanize_response` writes in: "formal", "casual" (the
+   * default) or "gaul". While mirroring, the user's register wins once
+   * they have shown one.
+   */
+  set_register(register: string): void;
+  /**
+   * `{seed, draws}`; exported snapshots carry the same and resume from
+   * the exact draw.
+   */
+  get_rng_state(): any;
+  /**
+   * Pick a gesture for `expression_id` and the current mood: weighted
+   * random among motions that are off cooldown and weren't played last.
+   * Returns `{group, index, expression, mood}`, or null when none fit.
+   */
+  select_motion(expression_id: string, now_ms: number): any;
+  /**
+   * "neutral" rests on the neutral expression, "mood" on the one mapped
+   * to the current mood.
+   */
+  set_idle_mode(mode: string): void;
+  /**
+   * Split a (humanized) reply into chat bubbles with typing delays:
+   * `{bubbles: [{text, typing_ms, at_ms}], total_ms, mood, pace}`. Play
+   * each bubble at its `at_ms`, with the typing indicator on before it.
+   */
+  split_bubbles(text: string): any;
+  static state_version(): number;
+  extract_topics(messages: string[]): string[];
+  has_expression(id: string): boolean;
+  reset_baseline(): void;
+  /**
+   * How formal `text` is: `{register, formality, hits, scores}`, with
+   * formality from 0 (gaul) to 1 (formal).
+   */
+  detect_register(text: string): any;
+  /**
+   * Clean a raw model reply before humanizing it: strips `<think>`
+   * blocks, markdown headings and "Sebagai AI" style disclaimers, fixes
+   * pronouns and length. Returns `{text, compliant, regenerate,
+   * violations: [{kind, detail, fixed}]}`; `regenerate` is set when a
+   * problem couldn't be fixed (English reply, nothing left).
+   */
+  enforce_persona(response: string): any;
+  generate_riddle(): any;
+  get_emoji_rules(): any;
+  get_style_rules(): any;
+  /**
+   * Rewrite `text` into another register (pronouns, verbs, particles and
+   * spelling), in either direction.
+   */
+  convert_register(text: string, register: string): string;
+  get_motion_table(): any;
+  list_expressions(): string[];
+  /**
+   * Replace the emoji rules (`{emotions: {"<emotion>": {emoji, kaomoji}},
+   * keywords: [{words, emoji}], moods: {"<mood>": {chance,
+   * kaomoji_chance, max_per_reply}}, serious_emotions, chars_per_emoji,
+   * repeat_window}`). "neutral" and "chill" are required as fallbacks.
+   */
+  load_emoji_rules(json: string): void;
+  /**
+   * Replace the style rules (`{"rules": [{id, from, to, priority, regex,
+   * whole_word, case_sensitive}]}`). They are compiled here, once; a bad
+   * rule set leaves the current one in place.
+   */
+  load_style_rules(rules_json: string): void;
+  preprocess_input(input: string): any;
+  /**
+   * Schedule an expression to be held for `hold_ms`. It starts right away
+   * if the face is idle, or if the current expression is interruptible and
+   * has a lower priority; otherwise it waits in priority order. Returns
+   * whether it started immediately.
+   */
+  queue_expression(id: string, priority: number, hold_ms: number, interruptible: boolean, now_ms: number): boolean;
   /**
    * Map emotion to Live2D expression with blending
    */
   blend_expressions(emotion_score: number, context: string): any;
+  detect_expression(text: string): string;
+  /**
+   * Decision path of the most recent `detect_mood` call, or null.
+   */
+  explain_last_mood(): any;
+  get_dominant_mood(): string;
+  get_persona_rules(): any;
   humanize_response(response: string): string;
-  build_conversation_context(messages: string[]): string;
-  extract_topics(messages: string[]): string[];
-  generate_riddle(): any;
+  /**
+   * Replace the gesture table (`{"expressions": {...}, "moods": {...}}`,
+   * each a list of `{group, index, weight, cooldown_ms}`). Shared with
+   * other sessions of the same `SessionManager`.
+   */
+  load_motion_table(table_json: string): void;
+  /**
+   * Override the persona rules with a (partial) JSON object:
+   * `{banned_phrases, max_chars, english_threshold,
+   * min_words_for_language}`.
+   */
+  set_persona_rules(rules_json: string): void;
+  /**
+   * Shorten `text` to the given limits (zero = no limit) without cutting
+   * inside code, links or list items: `{text, truncated, units_kept,
+   * units_total}`.
+   */
+  truncate_response(text: string, max_sentences: number, max_chars: number, max_tokens: number): any;
+  /**
+   * Report an event directly: "message", "compliment" or "insult".
+   */
+  apply_affect_event(event: string, now_ms: number): any;
+  /**
+   * Gaze solver already tuned to the session's current mood.
+   */
+  create_gaze_solver(canvas_width: number, canvas_height: number): GazeSolver;
+  get_bubble_options(): any;
+  /**
+   * Override the bubble options with a (partial) JSON object:
+   * `{min_chars, max_chars, max_bubbles, words_per_minute, mood_pace,
+   * jitter, min_delay_ms, max_delay_ms, pause_ms}`.
+   */
+  set_bubble_options(options_json: string): void;
+  /**
+   * Legacy method for backward compatibility
+   */
+  calculate_sentiment(text: string): number;
+  /**
+   * Read what the user wrote and remember it as the user channel for
+   * `resolve_display_expression`.
+   */
+  detect_user_emotion(text: string): any;
+  /**
+   * Same as `export_state`, but encrypted under `passphrase`
+   * (Argon2id + ChaCha20-Poly1305).
+   */
+  export_sealed_state(passphrase: string): string;
+  expression_for_mood(mood: string): string;
+  get_register_mirror(): any;
+  /**
+   * Decrypt and restore a sealed snapshot. Throws `WrongPassphraseError`
+   * or `TamperedError` (see `error.name`) without touching the state.
+   */
+  import_sealed_state(sealed: string, passphrase: string): void;
+  /**
+   * Override the affect dynamics with a (partial) JSON object; missing
+   * fields keep their defaults.
+   */
+  set_affect_dynamics(dynamics_json: string): void;
+  /**
+   * Idle animator already tuned to the session's current mood and
+   * MIRA's energy.
+   */
+  create_idle_animator(seed: number, now_ms: number): IdleAnimator;
+  /**
+   * Read MIRA's own reply with the bot-speech lexicon and remember it as
+   * the reply channel.
+   */
+  detect_reply_emotion(text: string): any;
   get_offline_response(): string;
-  interpolate_expression(_current: string, target: string, _progress: number): string;
-  create_expression_interpolator(from: string, to: string, duration_ms: bigint): any;
+  /**
+   * Update MIRA's affect from a user message: every message counts, and
+   * compliments or insults aimed at her move warmth and rapport. Returns
+   * `{events, affect}`.
+   */
+  observe_user_message(text: string, now_ms: number): any;
+  /**
+   * Turn per-user calibration on or off. The learned stats are kept either way.
+   */
+  set_baseline_enabled(enabled: boolean): void;
+  /**
+   * One line describing MIRA's state, for the LLM system prompt.
+   */
+  affect_prompt_context(now_ms: number): string;
+  get_humanize_profiles(): any;
+  /**
+   * Learn how the user talks from one of their messages: register,
+   * pronouns ("aku/kamu", "gue/lo", "saya/anda") and the honorific they
+   * call MIRA ("kak", "min", "sayang"). Returns the tracked state.
+   */
+  observe_user_register(text: string): any;
+  /**
+   * Limits for `humanize_response`: sentences (list items and code
+   * blocks count as one each), characters and approximate tokens. Zero
+   * means no limit. A tired MIRA keeps one sentence fewer.
+   */
+  set_truncation_budget(max_sentences: number, max_chars: number, max_tokens: number): void;
+  clear_expression_queue(): void;
+  expression_for_emotion(emotion: string): string;
+  /**
+   * `[[expressionId, startedAtMs], ...]`, oldest first.
+   */
+  get_expression_history(): any;
+  /**
+   * Dominant expression of a crossfade. Use
+   * `interpolate_expression_parameters` for the actual blended values.
+   */
+  interpolate_expression(current: string, target: string, progress: number): string;
+  /**
+   * Replace the humanization profiles (`{"profiles": {"<mood>" or
+   * "crisis": [{min_intensity, playful, fillers, filler_chance, particles,
+   * particle_chance, laughter, laughter_chance, emoji_chance,
+   * max_sentences}]}}`). A "chill" profile is required as the fallback.
+   * Emoji themselves come from the emoji rules; unknown fields, such as
+   * an `emoji` list on a tier, are an error.
+   */
+  load_humanize_profiles(json: string): void;
+  reset_motion_cooldowns(): void;
+  /**
+   * Turn mirroring off to always answer in the `set_register` register.
+   */
+  set_register_mirroring(enabled: boolean): void;
+  get_expression_registry(): any;
+  expression_parameter_ids(): string[];
+  /**
+   * Same as `humanize_response`, plus which style rules fired, whether it
+   * was shortened, the profile used and what it added: `{text, fired,
+   * truncated, profile, filler, particle, laughter, emoji: [{emoji,
+   * kaomoji, placement, keyword}], emoji_skipped}`.
+   */
+  humanize_response_report(response: string): any;
+  /**
+   * Replace the expression set with the one from a Live2D `model3.json`,
+   * mapped through `mapping_json` (`{"emotions": {...}, "moods": {...}}`).
+   * Every mapped ID must exist in the model. Shared with other sessions of
+   * the same `SessionManager`.
+   */
+  load_expression_registry(model3_json: string, mapping_json: string): void;
+  calculate_mood_transition(current_mood: string, sentiment_score: number): string;
+  /**
+   * Resting values of the model's parameters (`{ParamId: value}`); the
+   * starting point for Overwrite blending. Unset parameters rest at 0.
+   */
+  set_parameter_base_values(base_values: any): void;
+  build_conversation_context(messages: string[]): string;
+  /**
+   * Attach a Live2D `exp3.json` to a registered expression ID.
+   */
+  load_expression_parameters(id: string, exp3_json: string): void;
+  /**
+   * The expression to show, from both channels: the reply's emotion when
+   * it has a clear one, otherwise MIRA's empathic answer to the user's.
+   * MIRA's affect scales the intensity.
+   */
+  resolve_display_expression(): any;
+  /**
+   * Blend `{expressionId: weight}` (weights 0-1) into `{ParamId: value}`
+   * using each parameter's Add / Multiply / Overwrite mode.
+   */
+  blend_expression_parameters(weights: any): any;
+  calculate_sentiment_advanced(text: string): any;
   interpolate_expression_smooth(_from: string, _to: string, progress: number): number;
+  create_expression_interpolator(from: string, to: string, duration_ms: number, now_ms: number): ExpressionInterpolator;
+  /**
+   * Advanced expression detection dengan secondary emotion. `confidence`
+   * comes from the lexicon evidence (hits, agreement, margin, negation,
+   * sarcasm), which is returned under `evidence`.
+   */
+  detect_expression_with_intensity(text: string): any;
+  /**
+   * Same as `blend_expression_parameters`, as a Float32Array laid out like
+   * `expression_parameter_ids()`. Untouched parameters hold their base value.
+   */
+  blend_expression_parameters_array(weights: any): Float32Array;
+  /**
+   * Blended parameters `progress` (0-1) of the way from `current` to `target`.
+   */
+  interpolate_expression_parameters(current: string, target: string, progress: number): any;
+  constructor();
+  /**
+   * Ease `progress` (0.0-1.0) with a preset name, `"cubic-bezier(...)"`
+   * or a JSON spring/elastic spec (see `Easing::parse`).
+   */
+  ease(easing: string, progress: number): number;
+  /**
+   * Advance the expression timeline and get what to render: the current
+   * expression, crossfade weights and, when exp3 parameters are loaded,
+   * the blended parameter values.
+   */
+  tick(now_ms: number): any;
+  get_seed(): number;
+  /**
+   * Reseed the session RNG. Every random choice (humanization, motions,
+   * riddles, offline replies) goes through it, so the same seed and the
+   * same calls give the same output. Sessions start from a random seed.
+   */
+  set_seed(seed: number): void;
+  is_crisis(): boolean;
+}
+/**
+ * Keeps many conversations on top of a single lexicon. `session(id)` and
+ * `active()` return `MiraCore` handles that read and write the managed state
+ * directly, so nothing needs to be synced back.
+ */
+export class SessionManager {
+  free(): void;
+  [Symbol.dispose](): void;
+  has_session(id: string): boolean;
+  list_sessions(): string[];
+  /**
+   * Create an empty session. Fails if the ID is already taken. The first
+   * session created becomes the active one.
+   */
+  create_session(id: string): MiraCore;
+  /**
+   * Remove a session. Returns false if it didn't exist. Deleting the active
+   * session leaves no session active.
+   */
+  delete_session(id: string): boolean;
+  switch_session(id: string): MiraCore;
+  aggregate_stats(): any;
+  /**
+   * Load humanization style rules for every session (see
+   * `MiraCore::load_style_rules`).
+   */
+  load_style_rules(rules_json: string): void;
+  active_session_id(): string | undefined;
+  /**
+   * Load the Live2D expression registry for every session (see
+   * `MiraCore::load_expression_registry`).
+   */
+  load_expression_registry(model3_json: string, mapping_json: string): void;
+  constructor();
+  active(): MiraCore | undefined;
+  session(id: string): MiraCore;
 }
 
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
   readonly memory: WebAssembly.Memory;
+  readonly __wbg_expressioninterpolator_free: (a: number, b: number) => void;
+  readonly __wbg_gazesolver_free: (a: number, b: number) => void;
+  readonly __wbg_idleanimator_free: (a: number, b: number) => void;
+  readonly __wbg_lipsynctrack_free: (a: number, b: number) => void;
   readonly __wbg_miracore_free: (a: number, b: number) => void;
-  readonly miracore_new: () => number;
-  readonly miracore_preprocess_input: (a: number, b: number, c: number) => any;
-  readonly miracore_calculate_sentiment_advanced: (a: number, b: number, c: number) => any;
-  readonly miracore_calculate_sentiment: (a: number, b: number, c: number) => number;
-  readonly miracore_detect_mood: (a: number, b: number, c: number) => [number, number];
-  readonly miracore_get_dominant_mood: (a: number) => [number, number];
+  readonly __wbg_sessionmanager_free: (a: number, b: number) => void;
+  readonly expressioninterpolator_cancel: (a: number, b: number) => [number, number];
+  readonly expressioninterpolator_current_expression: (a: number) => [number, number];
+  readonly expressioninterpolator_is_animating: (a: number) => number;
+  readonly expressioninterpolator_new: (a: number, b: number) => number;
+  readonly expressioninterpolator_sample: (a: number, b: number) => any;
+  readonly expressioninterpolator_then: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number];
+  readonly expressioninterpolator_transition_to: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number];
+  readonly gazesolver_clear_target: (a: number) => void;
+  readonly gazesolver_new: (a: number, b: number) => number;
+  readonly gazesolver_parameter_ids: () => [number, number];
+  readonly gazesolver_set_canvas: (a: number, b: number, c: number, d: number, e: number) => void;
+  readonly gazesolver_set_limits: (a: number, b: number, c: number, d: number, e: number) => void;
+  readonly gazesolver_set_look_away_after: (a: number, b: number) => void;
+  readonly gazesolver_set_mood: (a: number, b: number, c: number) => [number, number];
+  readonly gazesolver_set_target: (a: number, b: number, c: number) => void;
+  readonly gazesolver_update: (a: number, b: number) => [number, number];
+  readonly gazesolver_update_frame: (a: number, b: number) => any;
+  readonly idleanimator_new: (a: number, b: number) => number;
+  readonly idleanimator_parameter_ids: () => [number, number];
+  readonly idleanimator_sample: (a: number, b: number) => [number, number];
+  readonly idleanimator_sample_frame: (a: number, b: number) => any;
+  readonly idleanimator_set_energy: (a: number, b: number) => void;
+  readonly idleanimator_set_mood: (a: number, b: number, c: number) => [number, number];
+  readonly lipsynctrack_duration_ms: (a: number) => number;
+  readonly lipsynctrack_keyframes: (a: number) => any;
+  readonly lipsynctrack_new: (a: number, b: number, c: number) => number;
+  readonly lipsynctrack_sample: (a: number, b: number) => [number, number];
+  readonly lipsynctrack_segments: (a: number) => any;
+  readonly miracore_affect_prompt_context: (a: number, b: number) => [number, number];
+  readonly miracore_apply_affect_event: (a: number, b: number, c: number, d: number) => [number, number, number];
+  readonly miracore_blend_expression_parameters: (a: number, b: any) => [number, number, number];
+  readonly miracore_blend_expression_parameters_array: (a: number, b: any) => [number, number, number, number];
+  readonly miracore_blend_expressions: (a: number, b: number, c: number, d: number) => any;
+  readonly miracore_build_conversation_context: (a: number, b: number, c: number) => [number, number];
   readonly miracore_calculate_mood_transition: (a: number, b: number, c: number, d: number) => [number, number];
+  readonly miracore_calculate_sentiment: (a: number, b: number, c: number) => number;
+  readonly miracore_calculate_sentiment_advanced: (a: number, b: number, c: number) => any;
+  readonly miracore_clear_expression_queue: (a: number) => void;
+  readonly miracore_convert_register: (a: number, b: number, c: number, d: number, e: number) => [number, number, number, number];
+  readonly miracore_create_expression_interpolator: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+  readonly miracore_create_gaze_solver: (a: number, b: number, c: number) => number;
+  readonly miracore_create_idle_animator: (a: number, b: number, c: number) => number;
   readonly miracore_detect_expression: (a: number, b: number, c: number) => [number, number];
   readonly miracore_detect_expression_with_intensity: (a: number, b: number, c: number) => any;
-  readonly miracore_blend_expressions: (a: number, b: number, c: number, d: number) => any;
-  readonly miracore_humanize_response: (a: number, b: number, c: number) => [number, number];
-  readonly miracore_build_conversation_context: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_detect_mood: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_detect_register: (a: number, b: number, c: number) => any;
+  readonly miracore_detect_reply_emotion: (a: number, b: number, c: number) => any;
+  readonly miracore_detect_user_emotion: (a: number, b: number, c: number) => any;
+  readonly miracore_ease: (a: number, b: number, c: number, d: number) => [number, number, number];
+  readonly miracore_enforce_persona: (a: number, b: number, c: number) => any;
+  readonly miracore_explain_last_mood: (a: number) => any;
+  readonly miracore_explain_mood: (a: number, b: number, c: number) => any;
+  readonly miracore_export_sealed_state: (a: number, b: number, c: number) => [number, number, number, number];
+  readonly miracore_export_state: (a: number) => [number, number];
+  readonly miracore_expression_for_emotion: (a: number, b: number, c: number) => [number, number, number, number];
+  readonly miracore_expression_for_mood: (a: number, b: number, c: number) => [number, number, number, number];
+  readonly miracore_expression_parameter_ids: (a: number) => [number, number];
   readonly miracore_extract_topics: (a: number, b: number, c: number) => [number, number];
   readonly miracore_generate_riddle: (a: number) => any;
+  readonly miracore_get_affect: (a: number, b: number) => any;
+  readonly miracore_get_baseline: (a: number) => any;
+  readonly miracore_get_bubble_options: (a: number) => any;
+  readonly miracore_get_dominant_mood: (a: number) => [number, number];
+  readonly miracore_get_emoji_rules: (a: number) => any;
+  readonly miracore_get_expression_history: (a: number) => any;
+  readonly miracore_get_expression_registry: (a: number) => any;
+  readonly miracore_get_humanize_profiles: (a: number) => any;
+  readonly miracore_get_motion_table: (a: number) => any;
   readonly miracore_get_offline_response: (a: number) => [number, number];
+  readonly miracore_get_persona_rules: (a: number) => any;
+  readonly miracore_get_register: (a: number) => [number, number];
+  readonly miracore_get_register_mirror: (a: number) => any;
+  readonly miracore_get_rng_state: (a: number) => any;
+  readonly miracore_get_seed: (a: number) => number;
+  readonly miracore_get_style_rules: (a: number) => any;
+  readonly miracore_has_expression: (a: number, b: number, c: number) => number;
+  readonly miracore_humanize_response: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_humanize_response_report: (a: number, b: number, c: number) => any;
+  readonly miracore_import_sealed_state: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+  readonly miracore_import_state: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_inject_emoji: (a: number, b: number, c: number) => any;
   readonly miracore_interpolate_expression: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
-  readonly miracore_create_expression_interpolator: (a: number, b: number, c: number, d: number, e: number, f: bigint) => any;
+  readonly miracore_interpolate_expression_parameters: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
   readonly miracore_interpolate_expression_smooth: (a: number, b: number, c: number, d: number, e: number, f: number) => number;
-  readonly quick_mood_check: (a: number, b: number) => [number, number];
+  readonly miracore_is_crisis: (a: number) => number;
+  readonly miracore_list_expressions: (a: number) => [number, number];
+  readonly miracore_load_emoji_rules: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_load_expression_parameters: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+  readonly miracore_load_expression_registry: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+  readonly miracore_load_humanize_profiles: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_load_motion_table: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_load_style_rules: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_new: () => number;
+  readonly miracore_observe_user_message: (a: number, b: number, c: number, d: number) => any;
+  readonly miracore_observe_user_register: (a: number, b: number, c: number) => any;
+  readonly miracore_preprocess_input: (a: number, b: number, c: number) => any;
+  readonly miracore_queue_expression: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+  readonly miracore_reset_baseline: (a: number) => void;
+  readonly miracore_reset_motion_cooldowns: (a: number) => void;
+  readonly miracore_resolve_display_expression: (a: number) => any;
+  readonly miracore_select_motion: (a: number, b: number, c: number, d: number) => [number, number, number];
+  readonly miracore_set_affect_dynamics: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_set_baseline_enabled: (a: number, b: number) => void;
+  readonly miracore_set_bubble_options: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_set_crisis: (a: number, b: number) => void;
+  readonly miracore_set_idle_mode: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_set_parameter_base_values: (a: number, b: any) => [number, number];
+  readonly miracore_set_persona_rules: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_set_register: (a: number, b: number, c: number) => [number, number];
+  readonly miracore_set_register_mirroring: (a: number, b: number) => void;
+  readonly miracore_set_seed: (a: number, b: number) => void;
+  readonly miracore_set_truncation_budget: (a: number, b: number, c: number, d: number) => void;
+  readonly miracore_split_bubbles: (a: number, b: number, c: number) => any;
+  readonly miracore_state_version: () => number;
+  readonly miracore_tick: (a: number, b: number) => any;
+  readonly miracore_truncate_response: (a: number, b: number, c: number, d: number, e: number, f: number) => any;
   readonly polish_text: (a: number, b: number) => [number, number];
+  readonly quick_mood_check: (a: number, b: number) => [number, number];
+  readonly rotate_sealed_state: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number, number];
+  readonly seal_state: (a: number, b: number, c: number, d: number) => [number, number, number, number];
+  readonly sessionmanager_active: (a: number) => number;
+  readonly sessionmanager_active_session_id: (a: number) => [number, number];
+  readonly sessionmanager_aggregate_stats: (a: number) => any;
+  readonly sessionmanager_create_session: (a: number, b: number, c: number) => [number, number, number];
+  readonly sessionmanager_delete_session: (a: number, b: number, c: number) => number;
+  readonly sessionmanager_has_session: (a: number, b: number, c: number) => number;
+  readonly sessionmanager_list_sessions: (a: number) => [number, number];
+  readonly sessionmanager_load_expression_registry: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+  readonly sessionmanager_load_style_rules: (a: number, b: number, c: number) => [number, number];
+  readonly sessionmanager_new: () => number;
+  readonly sessionmanager_session: (a: number, b: number, c: number) => [number, number, number];
+  readonly sessionmanager_switch_session: (a: number, b: number, c: number) => [number, number, number];
+  readonly unseal_state: (a: number, b: number, c: number, d: number) => [number, number, number, number];
   readonly __wbindgen_malloc: (a: number, b: number) => number;
   readonly __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
-  readonly __wbindgen_export_2: WebAssembly.Table;
-  readonly __wbindgen_free: (a: number, b: number, c: number) => void;
+  readonly __wbindgen_exn_store: (a: number) => void;
   readonly __externref_table_alloc: () => number;
+  readonly __wbindgen_export_4: WebAssembly.Table;
+  readonly __externref_table_dealloc: (a: number) => void;
+  readonly __wbindgen_free: (a: number, b: number, c: number) => void;
   readonly __externref_drop_slice: (a: number, b: number) => void;
   readonly __wbindgen_start: () => void;
 }
@@ -30,6 +30,84 @@ function getStringFromWasm0(ptr, len) {
     return decodeText(ptr, len);
 }
 
+let WASM_VECTOR_LEN = 0;
+
+const cachedTextEncoder = new TextEncoder();
+
+if (!('encodeInto' in cachedTextEncoder)) {
+    cachedTextEncoder.encodeInto = function (arg, view) {
+        const buf = cachedTextEncoder.encode(arg);
+        view.set(buf);
+        return {
+            read: arg.length,
+            written: buf.length
+        };
+    }
+}
+
+function passStringToWasm0(arg, malloc, realloc) {
+
+    if (realloc === undefined) {
+        const buf = cachedTextEncoder.encode(arg);
+        const ptr = malloc(buf.length, 1) >>> 0;
+        getUint8ArrayMemory0().subarray(ptr, ptr + buf.length).set(buf);
+        WASM_VECTOR_LEN = buf.length;
+        return ptr;
+    }
+
+    let len = arg.length;
+    let ptr = malloc(len, 1) >>> 0;
+
+    const mem = getUint8ArrayMemory0();
+
+    let offset = 0;
+
+    for (; offset < len; offset++) {
+        const code = arg.charCodeAt(offset);
+        if (code > 0x7F) break;
+        mem[ptr + offset] = code;
+    }
+
+    if (offset !== len) {
+        if (offset !== 0) {
+            arg = arg.slice(offset);
+        }
+        ptr = realloc(ptr, len, len = offset + arg.length * 3, 1) >>> 0;
+        const view = getUint8ArrayMemory0().subarray(ptr + offset, ptr + len);
+        const ret = cachedTextEncoder.encodeInto(arg, view);
+
+        offset += ret.written;
+        ptr = realloc(ptr, len, offset, 1) >>> 0;
+    }
+
+    WASM_VECTOR_LEN = offset;
+    return ptr;
+}
+
+let cachedDataViewMemory0 = null;
+
+function getDataViewMemory0() {
+    if (cachedDataViewMemory0 === null || cachedDataViewMemory0.buffer.detached === true || (cachedDataViewMemory0.buffer.detached === undefined && cachedDataViewMemory0.buffer !== wasm.memory.buffer)) {
+        cachedDataViewMemory0 = new DataView(wasm.memory.buffer);
+    }
+    return cachedDataViewMemory0;
+}
+
+function addToExternrefTable0(obj) {
+    const idx = wasm.__externref_table_alloc();
+    wasm.__wbindgen_export_4.set(idx, obj);
+    return idx;
+}
+
+function handleError(f, args) {
+    try {
+        return f.apply(this, args);
+    } catch (e) {
+        const idx = addToExternrefTable0(e);
+        wasm.__wbindgen_exn_store(idx);
+    }
+}
+
 function getArrayU8FromWasm0(ptr, len) {
     ptr = ptr >>> 0;
     return getUint8ArrayMemory0().subarray(ptr / 1, ptr / 1 + len);
@@ -104,95 +182,39 @@ function debugString(val) {
     return className;
 }
 
-let WASM_VECTOR_LEN = 0;
-
-const cachedTextEncoder = new TextEncoder();
-
-if (!('encodeInto' in cachedTextEncoder)) {
-    cachedTextEncoder.encodeInto = function (arg, view) {
-        const buf = cachedTextEncoder.encode(arg);
-        view.set(buf);
-        return {
-            read: arg.length,
-            written: buf.length
-        };
-    }
+function takeFromExternrefTable0(idx) {
+    const value = wasm.__wbindgen_export_4.get(idx);
+    wasm.__externref_table_dealloc(idx);
+    return value;
 }
-
-function passStringToWasm0(arg, malloc, realloc) {
-
-    if (realloc === undefined) {
-        const buf = cachedTextEncoder.encode(arg);
-        const ptr = malloc(buf.length, 1) >>> 0;
-        getUint8ArrayMemory0().subarray(ptr, ptr + buf.length).set(buf);
-        WASM_VECTOR_LEN = buf.length;
-        return ptr;
-    }
-
-    let len = arg.length;
-    let ptr = malloc(len, 1) >>> 0;
-
-    const mem = getUint8ArrayMemory0();
-
-    let offset = 0;
-
-    for (; offset < len; offset++) {
-        const code = arg.charCodeAt(offset);
-        if (code > 0x7F) break;
-        mem[ptr + offset] = code;
-    }
-
-    if (offset !== len) {
-        if (offset !== 0) {
-            arg = arg.slice(offset);
+/**
+ * @param {string} state
+ * @param {string} passphrase
+ * @returns {string}
+ */
+export function seal_state(state, passphrase) {
+    let deferred4_0;
+    let deferred4_1;
+    try {
+        const ptr0 = passStringToWasm0(state, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(passphrase, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.seal_state(ptr0, len0, ptr1, len1);
+        var ptr3 = ret[0];
+        var len3 = ret[1];
+        if (ret[3]) {
+            ptr3 = 0; len3 = 0;
+            throw takeFromExternrefTable0(ret[2]);
         }
-        ptr = realloc(ptr, len, len = offset + arg.length * 3, 1) >>> 0;
-        const view = getUint8ArrayMemory0().subarray(ptr + offset, ptr + len);
-        const ret = cachedTextEncoder.encodeInto(arg, view);
-
-        offset += ret.written;
-        ptr = realloc(ptr, len, offset, 1) >>> 0;
-    }
-
-    WASM_VECTOR_LEN = offset;
-    return ptr;
-}
-
-let cachedDataViewMemory0 = null;
-
-function getDataViewMemory0() {
-    if (cachedDataViewMemory0 === null || cachedDataViewMemory0.buffer.detached === true || (cachedDataViewMemory0.buffer.detached === undefined && cachedDataViewMemory0.buffer !== wasm.memory.buffer)) {
-        cachedDataViewMemory0 = new DataView(wasm.memory.buffer);
-    }
-    return cachedDataViewMemory0;
-}
-
-function addToExternrefTable0(obj) {
-    const idx = wasm.__externref_table_alloc();
-    wasm.__wbindgen_export_2.set(idx, obj);
-    return idx;
-}
-
-function passArrayJsValueToWasm0(array, malloc) {
-    const ptr = malloc(array.length * 4, 4) >>> 0;
-    for (let i = 0; i < array.length; i++) {
-        const add = addToExternrefTable0(array[i]);
-        getDataViewMemory0().setUint32(ptr + 4 * i, add, true);
+        deferred4_0 = ptr3;
+        deferred4_1 = len3;
+        return getStringFromWasm0(ptr3, len3);
+    } finally {
+        wasm.__wbindgen_free(deferred4_0, deferred4_1, 1);
     }
-    WASM_VECTOR_LEN = array.length;
-    return ptr;
 }
 
-function getArrayJsValueFromWasm0(ptr, len) {
-    ptr = ptr >>> 0;
-    const mem = getDataViewMemory0();
-    const result = [];
-    for (let i = ptr; i < ptr + 4 * len; i += 4) {
-        result.push(wasm.__wbindgen_export_2.get(mem.getUint32(i, true)));
-    }
-    wasm.__externref_drop_slice(ptr, len);
-    return result;
-}
 /**
  * @param {string} input
  * @returns {string}
@@ -212,6 +234,66 @@ export function quick_mood_check(input) {
     }
 }
 
+/**
+ * Re-encrypt a sealed snapshot under a new passphrase (fresh salt and nonce).
+ * @param {string} sealed
+ * @param {string} old_passphrase
+ * @param {string} new_passphrase
+ * @returns {string}
+ */
+export function rotate_sealed_state(sealed, old_passphrase, new_passphrase) {
+    let deferred5_0;
+    let deferred5_1;
+    try {
+        const ptr0 = passStringToWasm0(sealed, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(old_passphrase, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ptr2 = passStringToWasm0(new_passphrase, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len2 = WASM_VECTOR_LEN;
+        const ret = wasm.rotate_sealed_state(ptr0, len0, ptr1, len1, ptr2, len2);
+        var ptr4 = ret[0];
+        var len4 = ret[1];
+        if (ret[3]) {
+            ptr4 = 0; len4 = 0;
+            throw takeFromExternrefTable0(ret[2]);
+        }
+        deferred5_0 = ptr4;
+        deferred5_1 = len4;
+        return getStringFromWasm0(ptr4, len4);
+    } finally {
+        wasm.__wbindgen_free(deferred5_0, deferred5_1, 1);
+    }
+}
+
+/**
+ * @param {string} sealed
+ * @param {string} passphrase
+ * @returns {string}
+ */
+export function unseal_state(sealed, passphrase) {
+    let deferred4_0;
+    let deferred4_1;
+    try {
+        const ptr0 = passStringToWasm0(sealed, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(passphrase, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.unseal_state(ptr0, len0, ptr1, len1);
+        var ptr3 = ret[0];
+        var len3 = ret[1];
+        if (ret[3]) {
+            ptr3 = 0; len3 = 0;
+            throw takeFromExternrefTable0(ret[2]);
+        }
+        deferred4_0 = ptr3;
+        deferred4_1 = len3;
+        return getStringFromWasm0(ptr3, len3);
+    } finally {
+        wasm.__wbindgen_free(deferred4_0, deferred4_1, 1);
+    }
+}
+
 /**
  * @param {string} text
  * @returns {string}
@@ -231,71 +313,870 @@ export function polish_text(text) {
     }
 }
 
-const MiraCoreFinalization = (typeof FinalizationRegistry === 'undefined')
-    ? { register: () => {}, unregister: () => {} }
-    : new FinalizationRegistry(ptr => wasm.__wbg_miracore_free(ptr >>> 0, 1));
+function getArrayJsValueFromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    const mem = getDataViewMemory0();
+    const result = [];
+    for (let i = ptr; i < ptr + 4 * len; i += 4) {
+        result.push(wasm.__wbindgen_export_4.get(mem.getUint32(i, true)));
+    }
+    wasm.__externref_drop_slice(ptr, len);
+    return result;
+}
 
-export class MiraCore {
+let cachedFloat32ArrayMemory0 = null;
+
+function getFloat32ArrayMemory0() {
+    if (cachedFloat32ArrayMemory0 === null || cachedFloat32ArrayMemory0.byteLength === 0) {
+        cachedFloat32ArrayMemory0 = new Float32Array(wasm.memory.buffer);
+    }
+    return cachedFloat32ArrayMemory0;
+}
+
+function getArrayF32FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getFloat32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
+}
+
+function passArrayJsValueToWasm0(array, malloc) {
+    const ptr = malloc(array.length * 4, 4) >>> 0;
+    for (let i = 0; i < array.length; i++) {
+        const add = addToExternrefTable0(array[i]);
+        getDataViewMemory0().setUint32(ptr + 4 * i, add, true);
+    }
+    WASM_VECTOR_LEN = array.length;
+    return ptr;
+}
+
+const ExpressionInterpolatorFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_expressioninterpolator_free(ptr >>> 0, 1));
+/**
+ * Crossfades between expressions. Time is always passed in (`now_ms`), so
+ * the same inputs give the same frames in tests, replays and the browser.
+ */
+export class ExpressionInterpolator {
+
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(ExpressionInterpolator.prototype);
+        obj.__wbg_ptr = ptr;
+        ExpressionInterpolatorFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
 
     __destroy_into_raw() {
         const ptr = this.__wbg_ptr;
         this.__wbg_ptr = 0;
-        MiraCoreFinalization.unregister(this);
+        ExpressionInterpolatorFinalization.unregister(this);
         return ptr;
     }
 
     free() {
         const ptr = this.__destroy_into_raw();
-        wasm.__wbg_miracore_free(ptr, 0);
-    }
-    constructor() {
-        const ret = wasm.miracore_new();
-        this.__wbg_ptr = ret >>> 0;
-        MiraCoreFinalization.register(this, this.__wbg_ptr, this);
-        return this;
-    }
-    /**
-     * @param {string} input
-     * @returns {any}
-     */
-    preprocess_input(input) {
-        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
-        const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.miracore_preprocess_input(this.__wbg_ptr, ptr0, len0);
-        return ret;
+        wasm.__wbg_expressioninterpolator_free(ptr, 0);
     }
     /**
-     * @param {string} text
-     * @returns {any}
+     * @returns {boolean}
      */
-    calculate_sentiment_advanced(text) {
-        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
-        const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.miracore_calculate_sentiment_advanced(this.__wbg_ptr, ptr0, len0);
-        return ret;
+    is_animating() {
+        const ret = wasm.expressioninterpolator_is_animating(this.__wbg_ptr);
+        return ret !== 0;
     }
     /**
-     * Legacy method for backward compatibility
-     * @param {string} text
-     * @returns {number}
+     * Start a transition now, replacing anything running or chained. The
+     * new one starts from whichever expression dominates at `now_ms`.
+     * `easing` is anything `Easing::parse` accepts.
+     * @param {string} to
+     * @param {number} duration_ms
+     * @param {string} easing
+     * @param {number} now_ms
      */
-    calculate_sentiment(text) {
-        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    transition_to(to, duration_ms, easing, now_ms) {
+        const ptr0 = passStringToWasm0(to, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
         const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.miracore_calculate_sentiment(this.__wbg_ptr, ptr0, len0);
-        return ret;
+        const ptr1 = passStringToWasm0(easing, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.expressioninterpolator_transition_to(this.__wbg_ptr, ptr0, len0, duration_ms, ptr1, len1, now_ms);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
     }
     /**
-     * @param {string} user_input
      * @returns {string}
      */
-    detect_mood(user_input) {
-        let deferred2_0;
-        let deferred2_1;
+    current_expression() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.expressioninterpolator_current_expression(this.__wbg_ptr);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * @param {string} initial_expression
+     */
+    constructor(initial_expression) {
+        const ptr0 = passStringToWasm0(initial_expression, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.expressioninterpolator_new(ptr0, len0);
+        this.__wbg_ptr = ret >>> 0;
+        ExpressionInterpolatorFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Queue a transition to run after the last one, or right away when idle.
+     * @param {string} to
+     * @param {number} duration_ms
+     * @param {string} easing
+     * @param {number} now_ms
+     */
+    then(to, duration_ms, easing, now_ms) {
+        const ptr0 = passStringToWasm0(to, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(easing, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.expressioninterpolator_then(this.__wbg_ptr, ptr0, len0, duration_ms, ptr1, len1, now_ms);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Drop the running and chained transitions and settle on the expression
+     * that dominates at `now_ms`. Returns that expression.
+     * @param {number} now_ms
+     * @returns {string}
+     */
+    cancel(now_ms) {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.expressioninterpolator_cancel(this.__wbg_ptr, now_ms);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * @param {number} now_ms
+     * @returns {any}
+     */
+    sample(now_ms) {
+        const ret = wasm.expressioninterpolator_sample(this.__wbg_ptr, now_ms);
+        return ret;
+    }
+}
+if (Symbol.dispose) ExpressionInterpolator.prototype[Symbol.dispose] = ExpressionInterpolator.prototype.free;
+
+const GazeSolverFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_gazesolver_free(ptr >>> 0, 1));
+/**
+ * Turns a pointer (or any target) position on the canvas into smoothed
+ * head, eye and body angles. Springs are stepped by the time between
+ * `update` calls, so results depend only on the inputs and `now_ms`.
+ */
+export class GazeSolver {
+
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(GazeSolver.prototype);
+        obj.__wbg_ptr = ptr;
+        GazeSolverFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        GazeSolverFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_gazesolver_free(ptr, 0);
+    }
+    /**
+     * Canvas size and where the face is on it, all in the same units as the
+     * target (usually CSS pixels).
+     * @param {number} width
+     * @param {number} height
+     * @param {number} face_x
+     * @param {number} face_y
+     */
+    set_canvas(width, height, face_x, face_y) {
+        wasm.gazesolver_set_canvas(this.__wbg_ptr, width, height, face_x, face_y);
+    }
+    /**
+     * Maximum head angles and body turn, in degrees.
+     * @param {number} angle_x
+     * @param {number} angle_y
+     * @param {number} angle_z
+     * @param {number} body_x
+     */
+    set_limits(angle_x, angle_y, angle_z, body_x) {
+        wasm.gazesolver_set_limits(this.__wbg_ptr, angle_x, angle_y, angle_z, body_x);
+    }
+    /**
+     * @param {number} x
+     * @param {number} y
+     */
+    set_target(x, y) {
+        wasm.gazesolver_set_target(this.__wbg_ptr, x, y);
+    }
+    /**
+     * Pointer left the canvas: ease back to looking straight ahead.
+     */
+    clear_target() {
+        wasm.gazesolver_clear_target(this.__wbg_ptr);
+    }
+    /**
+     * @param {number} now_ms
+     * @returns {any}
+     */
+    update_frame(now_ms) {
+        const ret = wasm.gazesolver_update_frame(this.__wbg_ptr, now_ms);
+        return ret;
+    }
+    /**
+     * @returns {string[]}
+     */
+    static parameter_ids() {
+        const ret = wasm.gazesolver_parameter_ids();
+        var v1 = getArrayJsValueFromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * Look away after this much unbroken eye contact. 0 or less goes back
+     * to the mood's default; `Infinity` never looks away.
+     * @param {number} ms
+     */
+    set_look_away_after(ms) {
+        wasm.gazesolver_set_look_away_after(this.__wbg_ptr, ms);
+    }
+    /**
+     * The face anchor defaults to the horizontal centre, a third of the way
+     * down, which suits a bust-up model.
+     * @param {number} canvas_width
+     * @param {number} canvas_height
+     */
+    constructor(canvas_width, canvas_height) {
+        const ret = wasm.gazesolver_new(canvas_width, canvas_height);
+        this.__wbg_ptr = ret >>> 0;
+        GazeSolverFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Step the springs to `now_ms` and get the values, laid out like
+     * `parameter_ids()`.
+     * @param {number} now_ms
+     * @returns {Float32Array}
+     */
+    update(now_ms) {
+        const ret = wasm.gazesolver_update(this.__wbg_ptr, now_ms);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @param {string} mood
+     */
+    set_mood(mood) {
+        const ptr0 = passStringToWasm0(mood, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.gazesolver_set_mood(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+}
+if (Symbol.dispose) GazeSolver.prototype[Symbol.dispose] = GazeSolver.prototype.free;
+
+const IdleAnimatorFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_idleanimator_free(ptr >>> 0, 1));
+/**
+ * Procedural idle motion between messages: blinks with jitter and the odd
+ * double blink, breathing, small brow/mouth/head drift and glances.
+ * Everything is derived from `seed` and the time passed to `sample`, so the
+ * same seed and calls give the same curves.
+ */
+export class IdleAnimator {
+
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(IdleAnimator.prototype);
+        obj.__wbg_ptr = ptr;
+        IdleAnimatorFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        IdleAnimatorFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_idleanimator_free(ptr, 0);
+    }
+    /**
+     * 0 (drowsy) to 1 (energetic); clamped. Applied like `set_mood`.
+     * @param {number} energy
+     */
+    set_energy(energy) {
+        wasm.idleanimator_set_energy(this.__wbg_ptr, energy);
+    }
+    /**
+     * `{time_ms, mood, energy, blinking, glancing, parameters: {ParamId: value}}`
+     * @param {number} now_ms
+     * @returns {any}
+     */
+    sample_frame(now_ms) {
+        const ret = wasm.idleanimator_sample_frame(this.__wbg_ptr, now_ms);
+        return ret;
+    }
+    /**
+     * @returns {string[]}
+     */
+    static parameter_ids() {
+        const ret = wasm.idleanimator_parameter_ids();
+        var v1 = getArrayJsValueFromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @param {number} seed
+     * @param {number} now_ms
+     */
+    constructor(seed, now_ms) {
+        const ret = wasm.idleanimator_new(seed, now_ms);
+        this.__wbg_ptr = ret >>> 0;
+        IdleAnimatorFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Parameter values at `now_ms`, laid out like `parameter_ids()`.
+     * @param {number} now_ms
+     * @returns {Float32Array}
+     */
+    sample(now_ms) {
+        const ret = wasm.idleanimator_sample(this.__wbg_ptr, now_ms);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * Blinks and glances pick up the new pace from the next one on;
+     * breathing keeps its phase and movement eases over, so nothing jumps.
+     * @param {string} mood
+     */
+    set_mood(mood) {
+        const ptr0 = passStringToWasm0(mood, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.idleanimator_set_mood(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+}
+if (Symbol.dispose) IdleAnimator.prototype[Symbol.dispose] = IdleAnimator.prototype.free;
+
+const LipSyncTrackFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_lipsynctrack_free(ptr >>> 0, 1));
+/**
+ * Timed `ParamMouthOpenY` / `ParamMouthForm` keyframes for a piece of text,
+ * for lip-sync when there's no TTS audio to analyse.
+ */
+export class LipSyncTrack {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        LipSyncTrackFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_lipsynctrack_free(ptr, 0);
+    }
+    /**
+     * @returns {number}
+     */
+    duration_ms() {
+        const ret = wasm.lipsynctrack_duration_ms(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * `syllables_per_second` of 0 or less uses the default rate.
+     * @param {string} text
+     * @param {number} syllables_per_second
+     */
+    constructor(text, syllables_per_second) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.lipsynctrack_new(ptr0, len0, syllables_per_second);
+        this.__wbg_ptr = ret >>> 0;
+        LipSyncTrackFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * `[ParamMouthOpenY, ParamMouthForm]` at `time_ms` into the track.
+     * @param {number} time_ms
+     * @returns {Float32Array}
+     */
+    sample(time_ms) {
+        const ret = wasm.lipsynctrack_sample(this.__wbg_ptr, time_ms);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {any}
+     */
+    segments() {
+        const ret = wasm.lipsynctrack_segments(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {any}
+     */
+    keyframes() {
+        const ret = wasm.lipsynctrack_keyframes(this.__wbg_ptr);
+        return ret;
+    }
+}
+if (Symbol.dispose) LipSyncTrack.prototype[Symbol.dispose] = LipSyncTrack.prototype.free;
+
+const MiraCoreFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_miracore_free(ptr >>> 0, 1));
+/**
+ * One conversation. Cheap to create from a shared lexicon; handles returned
+ * by `SessionManager` share their state with the manager.
+ */
+export class MiraCore {
+
+    static __wrap(ptr) {
+        ptr = ptr >>> 0;
+        const obj = Object.create(MiraCore.prototype);
+        obj.__wbg_ptr = ptr;
+        MiraCoreFinalization.register(obj, obj.__wbg_ptr, obj);
+        return obj;
+    }
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        MiraCoreFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_miracore_free(ptr, 0);
+    }
+    /**
+     * `{energy, warmth, rapport, ...}` as of `now_ms`, including the drift
+     * since the last event. Doesn't change the state.
+     * @param {number} now_ms
+     * @returns {any}
+     */
+    get_affect(now_ms) {
+        const ret = wasm.miracore_get_affect(this.__wbg_ptr, now_ms);
+        return ret;
+    }
+    /**
+     * Force the crisis profile on or off. It is also set (and cleared) by
+     * every `detect_mood` / `detect_user_emotion` call.
+     * @param {boolean} active
+     */
+    set_crisis(active) {
+        wasm.miracore_set_crisis(this.__wbg_ptr, active);
+    }
+    /**
+     * @param {string} user_input
+     * @returns {string}
+     */
+    detect_mood(user_input) {
+        let deferred2_0;
+        let deferred2_1;
+        try {
+            const ptr0 = passStringToWasm0(user_input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len0 = WASM_VECTOR_LEN;
+            const ret = wasm.miracore_detect_mood(this.__wbg_ptr, ptr0, len0);
+            deferred2_0 = ret[0];
+            deferred2_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+        }
+    }
+    /**
+     * Dry run of `detect_mood`: the full decision path for `user_input`
+     * against the current session, without recording anything.
+     * @param {string} user_input
+     * @returns {any}
+     */
+    explain_mood(user_input) {
+        const ptr0 = passStringToWasm0(user_input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_explain_mood(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Serialize the conversation state (moods, cache, history) to a compact,
+     * versioned JSON snapshot that can be persisted and restored later.
+     * @returns {string}
+     */
+    export_state() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.miracore_export_state(this.__wbg_ptr);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * Learned sentiment baseline of this user (samples, mean, std dev, warm-up).
+     * @returns {any}
+     */
+    get_baseline() {
+        const ret = wasm.miracore_get_baseline(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {string}
+     */
+    get_register() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.miracore_get_register(this.__wbg_ptr);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
+     * Restore a snapshot produced by `export_state`. Older versions are
+     * migrated; invalid snapshots are rejected and leave the state untouched.
+     * @param {string} json
+     */
+    import_state(json) {
+        const ptr0 = passStringToWasm0(json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_import_state(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Run only the emoji stage of `humanize_response` on `text`: `{text,
+     * placed: [{emoji, kaomoji, placement, keyword}], skipped}`. Replies
+     * that read sad or concerned, crisis replies and serious profiles get
+     * none.
+     * @param {string} text
+     * @returns {any}
+     */
+    inject_emoji(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_inject_emoji(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Register `humanize_response` writes in: "formal", "casual" (the
+     * default) or "gaul". While mirroring, the user's register wins once
+     * they have shown one.
+     * @param {string} register
+     */
+    set_register(register) {
+        const ptr0 = passStringToWasm0(register, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_set_register(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * `{seed, draws}`; exported snapshots carry the same and resume from
+     * the exact draw.
+     * @returns {any}
+     */
+    get_rng_state() {
+        const ret = wasm.miracore_get_rng_state(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Pick a gesture for `expression_id` and the current mood: weighted
+     * random among motions that are off cooldown and weren't played last.
+     * Returns `{group, index, expression, mood}`, or null when none fit.
+     * @param {string} expression_id
+     * @param {number} now_ms
+     * @returns {any}
+     */
+    select_motion(expression_id, now_ms) {
+        const ptr0 = passStringToWasm0(expression_id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_select_motion(this.__wbg_ptr, ptr0, len0, now_ms);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * "neutral" rests on the neutral expression, "mood" on the one mapped
+     * to the current mood.
+     * @param {string} mode
+     */
+    set_idle_mode(mode) {
+        const ptr0 = passStringToWasm0(mode, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_set_idle_mode(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Split a (humanized) reply into chat bubbles with typing delays:
+     * `{bubbles: [{text, typing_ms, at_ms}], total_ms, mood, pace}`. Play
+     * each bubble at its `at_ms`, with the typing indicator on before it.
+     * @param {string} text
+     * @returns {any}
+     */
+    split_bubbles(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_split_bubbles(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    static state_version() {
+        const ret = wasm.miracore_state_version();
+        return ret >>> 0;
+    }
+    /**
+     * @param {string[]} messages
+     * @returns {string[]}
+     */
+    extract_topics(messages) {
+        const ptr0 = passArrayJsValueToWasm0(messages, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_extract_topics(this.__wbg_ptr, ptr0, len0);
+        var v2 = getArrayJsValueFromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v2;
+    }
+    /**
+     * @param {string} id
+     * @returns {boolean}
+     */
+    has_expression(id) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_has_expression(this.__wbg_ptr, ptr0, len0);
+        return ret !== 0;
+    }
+    reset_baseline() {
+        wasm.miracore_reset_baseline(this.__wbg_ptr);
+    }
+    /**
+     * How formal `text` is: `{register, formality, hits, scores}`, with
+     * formality from 0 (gaul) to 1 (formal).
+     * @param {string} text
+     * @returns {any}
+     */
+    detect_register(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_detect_register(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Clean a raw model reply before humanizing it: strips `<think>`
+     * blocks, markdown headings and "Sebagai AI" style disclaimers, fixes
+     * pronouns and length. Returns `{text, compliant, regenerate,
+     * violations: [{kind, detail, fixed}]}`; `regenerate` is set when a
+     * problem couldn't be fixed (English reply, nothing left).
+     * @param {string} response
+     * @returns {any}
+     */
+    enforce_persona(response) {
+        const ptr0 = passStringToWasm0(response, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_enforce_persona(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @returns {any}
+     */
+    generate_riddle() {
+        const ret = wasm.miracore_generate_riddle(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {any}
+     */
+    get_emoji_rules() {
+        const ret = wasm.miracore_get_emoji_rules(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {any}
+     */
+    get_style_rules() {
+        const ret = wasm.miracore_get_style_rules(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Rewrite `text` into another register (pronouns, verbs, particles and
+     * spelling), in either direction.
+     * @param {string} text
+     * @param {string} register
+     * @returns {string}
+     */
+    convert_register(text, register) {
+        let deferred4_0;
+        let deferred4_1;
+        try {
+            const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len0 = WASM_VECTOR_LEN;
+            const ptr1 = passStringToWasm0(register, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len1 = WASM_VECTOR_LEN;
+            const ret = wasm.miracore_convert_register(this.__wbg_ptr, ptr0, len0, ptr1, len1);
+            var ptr3 = ret[0];
+            var len3 = ret[1];
+            if (ret[3]) {
+                ptr3 = 0; len3 = 0;
+                throw takeFromExternrefTable0(ret[2]);
+            }
+            deferred4_0 = ptr3;
+            deferred4_1 = len3;
+            return getStringFromWasm0(ptr3, len3);
+        } finally {
+            wasm.__wbindgen_free(deferred4_0, deferred4_1, 1);
+        }
+    }
+    /**
+     * @returns {any}
+     */
+    get_motion_table() {
+        const ret = wasm.miracore_get_motion_table(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {string[]}
+     */
+    list_expressions() {
+        const ret = wasm.miracore_list_expressions(this.__wbg_ptr);
+        var v1 = getArrayJsValueFromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * Replace the emoji rules (`{emotions: {"<emotion>": {emoji, kaomoji}},
+     * keywords: [{words, emoji}], moods: {"<mood>": {chance,
+     * kaomoji_chance, max_per_reply}}, serious_emotions, chars_per_emoji,
+     * repeat_window}`). "neutral" and "chill" are required as fallbacks.
+     * @param {string} json
+     */
+    load_emoji_rules(json) {
+        const ptr0 = passStringToWasm0(json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_load_emoji_rules(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Replace the style rules (`{"rules": [{id, from, to, priority, regex,
+     * whole_word, case_sensitive}]}`). They are compiled here, once; a bad
+     * rule set leaves the current one in place.
+     * @param {string} rules_json
+     */
+    load_style_rules(rules_json) {
+        const ptr0 = passStringToWasm0(rules_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_load_style_rules(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {string} input
+     * @returns {any}
+     */
+    preprocess_input(input) {
+        const ptr0 = passStringToWasm0(input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_preprocess_input(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Schedule an expression to be held for `hold_ms`. It starts right away
+     * if the face is idle, or if the current expression is interruptible and
+     * has a lower priority; otherwise it waits in priority order. Returns
+     * whether it started immediately.
+     * @param {string} id
+     * @param {number} priority
+     * @param {number} hold_ms
+     * @param {boolean} interruptible
+     * @param {number} now_ms
+     * @returns {boolean}
+     */
+    queue_expression(id, priority, hold_ms, interruptible, now_ms) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_queue_expression(this.__wbg_ptr, ptr0, len0, priority, hold_ms, interruptible, now_ms);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return ret[0] !== 0;
+    }
+    /**
+     * Map emotion to Live2D expression with blending
+     * @param {number} emotion_score
+     * @param {string} context
+     * @returns {any}
+     */
+    blend_expressions(emotion_score, context) {
+        const ptr0 = passStringToWasm0(context, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_blend_expressions(this.__wbg_ptr, emotion_score, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @param {string} text
+     * @returns {string}
+     */
+    detect_expression(text) {
+        let deferred2_0;
+        let deferred2_1;
         try {
-            const ptr0 = passStringToWasm0(user_input, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
             const len0 = WASM_VECTOR_LEN;
-            const ret = wasm.miracore_detect_mood(this.__wbg_ptr, ptr0, len0);
+            const ret = wasm.miracore_detect_expression(this.__wbg_ptr, ptr0, len0);
             deferred2_0 = ret[0];
             deferred2_1 = ret[1];
             return getStringFromWasm0(ret[0], ret[1]);
@@ -303,6 +1184,14 @@ export class MiraCore {
             wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
         }
     }
+    /**
+     * Decision path of the most recent `detect_mood` call, or null.
+     * @returns {any}
+     */
+    explain_last_mood() {
+        const ret = wasm.miracore_explain_last_mood(this.__wbg_ptr);
+        return ret;
+    }
     /**
      * @returns {string}
      */
@@ -319,17 +1208,23 @@ export class MiraCore {
         }
     }
     /**
-     * @param {string} current_mood
-     * @param {number} sentiment_score
+     * @returns {any}
+     */
+    get_persona_rules() {
+        const ret = wasm.miracore_get_persona_rules(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @param {string} response
      * @returns {string}
      */
-    calculate_mood_transition(current_mood, sentiment_score) {
+    humanize_response(response) {
         let deferred2_0;
         let deferred2_1;
         try {
-            const ptr0 = passStringToWasm0(current_mood, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const ptr0 = passStringToWasm0(response, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
             const len0 = WASM_VECTOR_LEN;
-            const ret = wasm.miracore_calculate_mood_transition(this.__wbg_ptr, ptr0, len0, sentiment_score);
+            const ret = wasm.miracore_humanize_response(this.__wbg_ptr, ptr0, len0);
             deferred2_0 = ret[0];
             deferred2_1 = ret[1];
             return getStringFromWasm0(ret[0], ret[1]);
@@ -338,57 +1233,457 @@ export class MiraCore {
         }
     }
     /**
+     * Replace the gesture table (`{"expressions": {...}, "moods": {...}}`,
+     * each a list of `{group, index, weight, cooldown_ms}`). Shared with
+     * other sessions of the same `SessionManager`.
+     * @param {string} table_json
+     */
+    load_motion_table(table_json) {
+        const ptr0 = passStringToWasm0(table_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_load_motion_table(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Override the persona rules with a (partial) JSON object:
+     * `{banned_phrases, max_chars, english_threshold,
+     * min_words_for_language}`.
+     * @param {string} rules_json
+     */
+    set_persona_rules(rules_json) {
+        const ptr0 = passStringToWasm0(rules_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_set_persona_rules(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Shorten `text` to the given limits (zero = no limit) without cutting
+     * inside code, links or list items: `{text, truncated, units_kept,
+     * units_total}`.
+     * @param {string} text
+     * @param {number} max_sentences
+     * @param {number} max_chars
+     * @param {number} max_tokens
+     * @returns {any}
+     */
+    truncate_response(text, max_sentences, max_chars, max_tokens) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_truncate_response(this.__wbg_ptr, ptr0, len0, max_sentences, max_chars, max_tokens);
+        return ret;
+    }
+    /**
+     * Report an event directly: "message", "compliment" or "insult".
+     * @param {string} event
+     * @param {number} now_ms
+     * @returns {any}
+     */
+    apply_affect_event(event, now_ms) {
+        const ptr0 = passStringToWasm0(event, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_apply_affect_event(this.__wbg_ptr, ptr0, len0, now_ms);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    /**
+     * Gaze solver already tuned to the session's current mood.
+     * @param {number} canvas_width
+     * @param {number} canvas_height
+     * @returns {GazeSolver}
+     */
+    create_gaze_solver(canvas_width, canvas_height) {
+        const ret = wasm.miracore_create_gaze_solver(this.__wbg_ptr, canvas_width, canvas_height);
+        return GazeSolver.__wrap(ret);
+    }
+    /**
+     * @returns {any}
+     */
+    get_bubble_options() {
+        const ret = wasm.miracore_get_bubble_options(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Override the bubble options with a (partial) JSON object:
+     * `{min_chars, max_chars, max_bubbles, words_per_minute, mood_pace,
+     * jitter, min_delay_ms, max_delay_ms, pause_ms}`.
+     * @param {string} options_json
+     */
+    set_bubble_options(options_json) {
+        const ptr0 = passStringToWasm0(options_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_set_bubble_options(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Legacy method for backward compatibility
+     * @param {string} text
+     * @returns {number}
+     */
+    calculate_sentiment(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_calculate_sentiment(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Read what the user wrote and remember it as the user channel for
+     * `resolve_display_expression`.
      * @param {string} text
+     * @returns {any}
+     */
+    detect_user_emotion(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_detect_user_emotion(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Same as `export_state`, but encrypted under `passphrase`
+     * (Argon2id + ChaCha20-Poly1305).
+     * @param {string} passphrase
      * @returns {string}
      */
-    detect_expression(text) {
-        let deferred2_0;
-        let deferred2_1;
+    export_sealed_state(passphrase) {
+        let deferred3_0;
+        let deferred3_1;
         try {
-            const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const ptr0 = passStringToWasm0(passphrase, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
             const len0 = WASM_VECTOR_LEN;
-            const ret = wasm.miracore_detect_expression(this.__wbg_ptr, ptr0, len0);
-            deferred2_0 = ret[0];
-            deferred2_1 = ret[1];
+            const ret = wasm.miracore_export_sealed_state(this.__wbg_ptr, ptr0, len0);
+            var ptr2 = ret[0];
+            var len2 = ret[1];
+            if (ret[3]) {
+                ptr2 = 0; len2 = 0;
+                throw takeFromExternrefTable0(ret[2]);
+            }
+            deferred3_0 = ptr2;
+            deferred3_1 = len2;
+            return getStringFromWasm0(ptr2, len2);
+        } finally {
+            wasm.__wbindgen_free(deferred3_0, deferred3_1, 1);
+        }
+    }
+    /**
+     * @param {string} mood
+     * @returns {string}
+     */
+    expression_for_mood(mood) {
+        let deferred3_0;
+        let deferred3_1;
+        try {
+            const ptr0 = passStringToWasm0(mood, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len0 = WASM_VECTOR_LEN;
+            const ret = wasm.miracore_expression_for_mood(this.__wbg_ptr, ptr0, len0);
+            var ptr2 = ret[0];
+            var len2 = ret[1];
+            if (ret[3]) {
+                ptr2 = 0; len2 = 0;
+                throw takeFromExternrefTable0(ret[2]);
+            }
+            deferred3_0 = ptr2;
+            deferred3_1 = len2;
+            return getStringFromWasm0(ptr2, len2);
+        } finally {
+            wasm.__wbindgen_free(deferred3_0, deferred3_1, 1);
+        }
+    }
+    /**
+     * @returns {any}
+     */
+    get_register_mirror() {
+        const ret = wasm.miracore_get_register_mirror(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Decrypt and restore a sealed snapshot. Throws `WrongPassphraseError`
+     * or `TamperedError` (see `error.name`) without touching the state.
+     * @param {string} sealed
+     * @param {string} passphrase
+     */
+    import_sealed_state(sealed, passphrase) {
+        const ptr0 = passStringToWasm0(sealed, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(passphrase, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_import_sealed_state(this.__wbg_ptr, ptr0, len0, ptr1, len1);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Override the affect dynamics with a (partial) JSON object; missing
+     * fields keep their defaults.
+     * @param {string} dynamics_json
+     */
+    set_affect_dynamics(dynamics_json) {
+        const ptr0 = passStringToWasm0(dynamics_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_set_affect_dynamics(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * Idle animator already tuned to the session's current mood and
+     * MIRA's energy.
+     * @param {number} seed
+     * @param {number} now_ms
+     * @returns {IdleAnimator}
+     */
+    create_idle_animator(seed, now_ms) {
+        const ret = wasm.miracore_create_idle_animator(this.__wbg_ptr, seed, now_ms);
+        return IdleAnimator.__wrap(ret);
+    }
+    /**
+     * Read MIRA's own reply with the bot-speech lexicon and remember it as
+     * the reply channel.
+     * @param {string} text
+     * @returns {any}
+     */
+    detect_reply_emotion(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_detect_reply_emotion(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * @returns {string}
+     */
+    get_offline_response() {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.miracore_get_offline_response(this.__wbg_ptr);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
             return getStringFromWasm0(ret[0], ret[1]);
         } finally {
-            wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
         }
     }
     /**
-     * Advanced expression detection dengan secondary emotion
+     * Update MIRA's affect from a user message: every message counts, and
+     * compliments or insults aimed at her move warmth and rapport. Returns
+     * `{events, affect}`.
      * @param {string} text
+     * @param {number} now_ms
      * @returns {any}
      */
-    detect_expression_with_intensity(text) {
+    observe_user_message(text, now_ms) {
         const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
         const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.miracore_detect_expression_with_intensity(this.__wbg_ptr, ptr0, len0);
+        const ret = wasm.miracore_observe_user_message(this.__wbg_ptr, ptr0, len0, now_ms);
         return ret;
     }
     /**
-     * Map emotion to Live2D expression with blending
-     * @param {number} emotion_score
-     * @param {string} context
+     * Turn per-user calibration on or off. The learned stats are kept either way.
+     * @param {boolean} enabled
+     */
+    set_baseline_enabled(enabled) {
+        wasm.miracore_set_baseline_enabled(this.__wbg_ptr, enabled);
+    }
+    /**
+     * One line describing MIRA's state, for the LLM system prompt.
+     * @param {number} now_ms
+     * @returns {string}
+     */
+    affect_prompt_context(now_ms) {
+        let deferred1_0;
+        let deferred1_1;
+        try {
+            const ret = wasm.miracore_affect_prompt_context(this.__wbg_ptr, now_ms);
+            deferred1_0 = ret[0];
+            deferred1_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
+        }
+    }
+    /**
      * @returns {any}
      */
-    blend_expressions(emotion_score, context) {
-        const ptr0 = passStringToWasm0(context, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    get_humanize_profiles() {
+        const ret = wasm.miracore_get_humanize_profiles(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Learn how the user talks from one of their messages: register,
+     * pronouns ("aku/kamu", "gue/lo", "saya/anda") and the honorific they
+     * call MIRA ("kak", "min", "sayang"). Returns the tracked state.
+     * @param {string} text
+     * @returns {any}
+     */
+    observe_user_register(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_observe_user_register(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Limits for `humanize_response`: sentences (list items and code
+     * blocks count as one each), characters and approximate tokens. Zero
+     * means no limit. A tired MIRA keeps one sentence fewer.
+     * @param {number} max_sentences
+     * @param {number} max_chars
+     * @param {number} max_tokens
+     */
+    set_truncation_budget(max_sentences, max_chars, max_tokens) {
+        wasm.miracore_set_truncation_budget(this.__wbg_ptr, max_sentences, max_chars, max_tokens);
+    }
+    clear_expression_queue() {
+        wasm.miracore_clear_expression_queue(this.__wbg_ptr);
+    }
+    /**
+     * @param {string} emotion
+     * @returns {string}
+     */
+    expression_for_emotion(emotion) {
+        let deferred3_0;
+        let deferred3_1;
+        try {
+            const ptr0 = passStringToWasm0(emotion, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len0 = WASM_VECTOR_LEN;
+            const ret = wasm.miracore_expression_for_emotion(this.__wbg_ptr, ptr0, len0);
+            var ptr2 = ret[0];
+            var len2 = ret[1];
+            if (ret[3]) {
+                ptr2 = 0; len2 = 0;
+                throw takeFromExternrefTable0(ret[2]);
+            }
+            deferred3_0 = ptr2;
+            deferred3_1 = len2;
+            return getStringFromWasm0(ptr2, len2);
+        } finally {
+            wasm.__wbindgen_free(deferred3_0, deferred3_1, 1);
+        }
+    }
+    /**
+     * `[[expressionId, startedAtMs], ...]`, oldest first.
+     * @returns {any}
+     */
+    get_expression_history() {
+        const ret = wasm.miracore_get_expression_history(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Dominant expression of a crossfade. Use
+     * `interpolate_expression_parameters` for the actual blended values.
+     * @param {string} current
+     * @param {string} target
+     * @param {number} progress
+     * @returns {string}
+     */
+    interpolate_expression(current, target, progress) {
+        let deferred3_0;
+        let deferred3_1;
+        try {
+            const ptr0 = passStringToWasm0(current, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len0 = WASM_VECTOR_LEN;
+            const ptr1 = passStringToWasm0(target, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const len1 = WASM_VECTOR_LEN;
+            const ret = wasm.miracore_interpolate_expression(this.__wbg_ptr, ptr0, len0, ptr1, len1, progress);
+            deferred3_0 = ret[0];
+            deferred3_1 = ret[1];
+            return getStringFromWasm0(ret[0], ret[1]);
+        } finally {
+            wasm.__wbindgen_free(deferred3_0, deferred3_1, 1);
+        }
+    }
+    /**
+     * Replace the humanization profiles (`{"profiles": {"<mood>" or
+     * "crisis": [{min_intensity, playful, fillers, filler_chance, particles,
+     * particle_chance, laughter, laughter_chance, emoji_chance,
+     * max_sentences}]}}`). A "chill" profile is required as the fallback.
+     * Emoji themselves come from the emoji rules; unknown fields, such as
+     * an `emoji` list on a tier, are an error.
+     * @param {string} json
+     */
+    load_humanize_profiles(json) {
+        const ptr0 = passStringToWasm0(json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_load_humanize_profiles(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    reset_motion_cooldowns() {
+        wasm.miracore_reset_motion_cooldowns(this.__wbg_ptr);
+    }
+    /**
+     * Turn mirroring off to always answer in the `set_register` register.
+     * @param {boolean} enabled
+     */
+    set_register_mirroring(enabled) {
+        wasm.miracore_set_register_mirroring(this.__wbg_ptr, enabled);
+    }
+    /**
+     * @returns {any}
+     */
+    get_expression_registry() {
+        const ret = wasm.miracore_get_expression_registry(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * @returns {string[]}
+     */
+    expression_parameter_ids() {
+        const ret = wasm.miracore_expression_parameter_ids(this.__wbg_ptr);
+        var v1 = getArrayJsValueFromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * Same as `humanize_response`, plus which style rules fired, whether it
+     * was shortened, the profile used and what it added: `{text, fired,
+     * truncated, profile, filler, particle, laughter, emoji: [{emoji,
+     * kaomoji, placement, keyword}], emoji_skipped}`.
+     * @param {string} response
+     * @returns {any}
+     */
+    humanize_response_report(response) {
+        const ptr0 = passStringToWasm0(response, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
         const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.miracore_blend_expressions(this.__wbg_ptr, emotion_score, ptr0, len0);
+        const ret = wasm.miracore_humanize_response_report(this.__wbg_ptr, ptr0, len0);
         return ret;
     }
     /**
-     * @param {string} response
+     * Replace the expression set with the one from a Live2D `model3.json`,
+     * mapped through `mapping_json` (`{"emotions": {...}, "moods": {...}}`).
+     * Every mapped ID must exist in the model. Shared with other sessions of
+     * the same `SessionManager`.
+     * @param {string} model3_json
+     * @param {string} mapping_json
+     */
+    load_expression_registry(model3_json, mapping_json) {
+        const ptr0 = passStringToWasm0(model3_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(mapping_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_load_expression_registry(this.__wbg_ptr, ptr0, len0, ptr1, len1);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {string} current_mood
+     * @param {number} sentiment_score
      * @returns {string}
      */
-    humanize_response(response) {
+    calculate_mood_transition(current_mood, sentiment_score) {
         let deferred2_0;
         let deferred2_1;
         try {
-            const ptr0 = passStringToWasm0(response, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+            const ptr0 = passStringToWasm0(current_mood, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
             const len0 = WASM_VECTOR_LEN;
-            const ret = wasm.miracore_humanize_response(this.__wbg_ptr, ptr0, len0);
+            const ret = wasm.miracore_calculate_mood_transition(this.__wbg_ptr, ptr0, len0, sentiment_score);
             deferred2_0 = ret[0];
             deferred2_1 = ret[1];
             return getStringFromWasm0(ret[0], ret[1]);
@@ -396,6 +1691,17 @@ export class MiraCore {
             wasm.__wbindgen_free(deferred2_0, deferred2_1, 1);
         }
     }
+    /**
+     * Resting values of the model's parameters (`{ParamId: value}`); the
+     * starting point for Overwrite blending. Unset parameters rest at 0.
+     * @param {any} base_values
+     */
+    set_parameter_base_values(base_values) {
+        const ret = wasm.miracore_set_parameter_base_values(this.__wbg_ptr, base_values);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
     /**
      * @param {string[]} messages
      * @returns {string}
@@ -415,73 +1721,51 @@ export class MiraCore {
         }
     }
     /**
-     * @param {string[]} messages
-     * @returns {string[]}
+     * Attach a Live2D `exp3.json` to a registered expression ID.
+     * @param {string} id
+     * @param {string} exp3_json
      */
-    extract_topics(messages) {
-        const ptr0 = passArrayJsValueToWasm0(messages, wasm.__wbindgen_malloc);
+    load_expression_parameters(id, exp3_json) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
         const len0 = WASM_VECTOR_LEN;
-        const ret = wasm.miracore_extract_topics(this.__wbg_ptr, ptr0, len0);
-        var v2 = getArrayJsValueFromWasm0(ret[0], ret[1]).slice();
-        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
-        return v2;
+        const ptr1 = passStringToWasm0(exp3_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_load_expression_parameters(this.__wbg_ptr, ptr0, len0, ptr1, len1);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
     }
     /**
+     * The expression to show, from both channels: the reply's emotion when
+     * it has a clear one, otherwise MIRA's empathic answer to the user's.
+     * MIRA's affect scales the intensity.
      * @returns {any}
      */
-    generate_riddle() {
-        const ret = wasm.miracore_generate_riddle(this.__wbg_ptr);
+    resolve_display_expression() {
+        const ret = wasm.miracore_resolve_display_expression(this.__wbg_ptr);
         return ret;
     }
     /**
-     * @returns {string}
-     */
-    get_offline_response() {
-        let deferred1_0;
-        let deferred1_1;
-        try {
-            const ret = wasm.miracore_get_offline_response(this.__wbg_ptr);
-            deferred1_0 = ret[0];
-            deferred1_1 = ret[1];
-            return getStringFromWasm0(ret[0], ret[1]);
-        } finally {
-            wasm.__wbindgen_free(deferred1_0, deferred1_1, 1);
-        }
-    }
-    /**
-     * @param {string} _current
-     * @param {string} target
-     * @param {number} _progress
-     * @returns {string}
+     * Blend `{expressionId: weight}` (weights 0-1) into `{ParamId: value}`
+     * using each parameter's Add / Multiply / Overwrite mode.
+     * @param {any} weights
+     * @returns {any}
      */
-    interpolate_expression(_current, target, _progress) {
-        let deferred3_0;
-        let deferred3_1;
-        try {
-            const ptr0 = passStringToWasm0(_current, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
-            const len0 = WASM_VECTOR_LEN;
-            const ptr1 = passStringToWasm0(target, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
-            const len1 = WASM_VECTOR_LEN;
-            const ret = wasm.miracore_interpolate_expression(this.__wbg_ptr, ptr0, len0, ptr1, len1, _progress);
-            deferred3_0 = ret[0];
-            deferred3_1 = ret[1];
-            return getStringFromWasm0(ret[0], ret[1]);
-        } finally {
-            wasm.__wbindgen_free(deferred3_0, deferred3_1, 1);
+    blend_expression_parameters(weights) {
+        const ret = wasm.miracore_blend_expression_parameters(this.__wbg_ptr, weights);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
         }
+        return takeFromExternrefTable0(ret[0]);
     }
     /**
-     * @param {string} from
-     * @param {string} to
-     * @param {bigint} duration_ms
+     * @param {string} text
      * @returns {any}
      */
-    create_expression_interpolator(from, to, duration_ms) {
-        const ptr0 = passStringToWasm0(from, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+    calculate_sentiment_advanced(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
         const len0 = WASM_VECTOR_LEN;
-        const ptr1 = passStringToWasm0(to, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
-        const len1 = WASM_VECTOR_LEN;
-        const ret = wasm.miracore_create_expression_interpolator(this.__wbg_ptr, ptr0, len0, ptr1, len1, duration_ms);
+        const ret = wasm.miracore_calculate_sentiment_advanced(this.__wbg_ptr, ptr0, len0);
         return ret;
     }
     /**
@@ -498,9 +1782,286 @@ export class MiraCore {
         const ret = wasm.miracore_interpolate_expression_smooth(this.__wbg_ptr, ptr0, len0, ptr1, len1, progress);
         return ret;
     }
+    /**
+     * @param {string} from
+     * @param {string} to
+     * @param {number} duration_ms
+     * @param {number} now_ms
+     * @returns {ExpressionInterpolator}
+     */
+    create_expression_interpolator(from, to, duration_ms, now_ms) {
+        const ptr0 = passStringToWasm0(from, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(to, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_create_expression_interpolator(this.__wbg_ptr, ptr0, len0, ptr1, len1, duration_ms, now_ms);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return ExpressionInterpolator.__wrap(ret[0]);
+    }
+    /**
+     * Advanced expression detection dengan secondary emotion. `confidence`
+     * comes from the lexicon evidence (hits, agreement, margin, negation,
+     * sarcasm), which is returned under `evidence`.
+     * @param {string} text
+     * @returns {any}
+     */
+    detect_expression_with_intensity(text) {
+        const ptr0 = passStringToWasm0(text, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_detect_expression_with_intensity(this.__wbg_ptr, ptr0, len0);
+        return ret;
+    }
+    /**
+     * Same as `blend_expression_parameters`, as a Float32Array laid out like
+     * `expression_parameter_ids()`. Untouched parameters hold their base value.
+     * @param {any} weights
+     * @returns {Float32Array}
+     */
+    blend_expression_parameters_array(weights) {
+        const ret = wasm.miracore_blend_expression_parameters_array(this.__wbg_ptr, weights);
+        if (ret[3]) {
+            throw takeFromExternrefTable0(ret[2]);
+        }
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * Blended parameters `progress` (0-1) of the way from `current` to `target`.
+     * @param {string} current
+     * @param {string} target
+     * @param {number} progress
+     * @returns {any}
+     */
+    interpolate_expression_parameters(current, target, progress) {
+        const ptr0 = passStringToWasm0(current, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(target, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_interpolate_expression_parameters(this.__wbg_ptr, ptr0, len0, ptr1, len1, progress);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return takeFromExternrefTable0(ret[0]);
+    }
+    constructor() {
+        const ret = wasm.miracore_new();
+        this.__wbg_ptr = ret >>> 0;
+        MiraCoreFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * Ease `progress` (0.0-1.0) with a preset name, `"cubic-bezier(...)"`
+     * or a JSON spring/elastic spec (see `Easing::parse`).
+     * @param {string} easing
+     * @param {number} progress
+     * @returns {number}
+     */
+    ease(easing, progress) {
+        const ptr0 = passStringToWasm0(easing, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.miracore_ease(this.__wbg_ptr, ptr0, len0, progress);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return ret[0];
+    }
+    /**
+     * Advance the expression timeline and get what to render: the current
+     * expression, crossfade weights and, when exp3 parameters are loaded,
+     * the blended parameter values.
+     * @param {number} now_ms
+     * @returns {any}
+     */
+    tick(now_ms) {
+        const ret = wasm.miracore_tick(this.__wbg_ptr, now_ms);
+        return ret;
+    }
+    /**
+     * @returns {number}
+     */
+    get_seed() {
+        const ret = wasm.miracore_get_seed(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * Reseed the session RNG. Every random choice (humanization, motions,
+     * riddles, offline replies) goes through it, so the same seed and the
+     * same calls give the same output. Sessions start from a random seed.
+     * @param {number} seed
+     */
+    set_seed(seed) {
+        wasm.miracore_set_seed(this.__wbg_ptr, seed);
+    }
+    /**
+     * @returns {boolean}
+     */
+    is_crisis() {
+        const ret = wasm.miracore_is_crisis(this.__wbg_ptr);
+        return ret !== 0;
+    }
 }
 if (Symbol.dispose) MiraCore.prototype[Symbol.dispose] = MiraCore.prototype.free;
 
+const SessionManagerFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_sessionmanager_free(ptr >>> 0, 1));
+/**
+ * Keeps many conversations on top of a single lexicon. `session(id)` and
+ * `active()` return `MiraCore` handles that read and write the managed state
+ * directly, so nothing needs to be synced back.
+ */
+export class SessionManager {
+
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        SessionManagerFinalization.unregister(this);
+        return ptr;
+    }
+
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_sessionmanager_free(ptr, 0);
+    }
+    /**
+     * @param {string} id
+     * @returns {boolean}
+     */
+    has_session(id) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sessionmanager_has_session(this.__wbg_ptr, ptr0, len0);
+        return ret !== 0;
+    }
+    /**
+     * @returns {string[]}
+     */
+    list_sessions() {
+        const ret = wasm.sessionmanager_list_sessions(this.__wbg_ptr);
+        var v1 = getArrayJsValueFromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * Create an empty session. Fails if the ID is already taken. The first
+     * session created becomes the active one.
+     * @param {string} id
+     * @returns {MiraCore}
+     */
+    create_session(id) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sessionmanager_create_session(this.__wbg_ptr, ptr0, len0);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return MiraCore.__wrap(ret[0]);
+    }
+    /**
+     * Remove a session. Returns false if it didn't exist. Deleting the active
+     * session leaves no session active.
+     * @param {string} id
+     * @returns {boolean}
+     */
+    delete_session(id) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sessionmanager_delete_session(this.__wbg_ptr, ptr0, len0);
+        return ret !== 0;
+    }
+    /**
+     * @param {string} id
+     * @returns {MiraCore}
+     */
+    switch_session(id) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sessionmanager_switch_session(this.__wbg_ptr, ptr0, len0);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return MiraCore.__wrap(ret[0]);
+    }
+    /**
+     * @returns {any}
+     */
+    aggregate_stats() {
+        const ret = wasm.sessionmanager_aggregate_stats(this.__wbg_ptr);
+        return ret;
+    }
+    /**
+     * Load humanization style rules for every session (see
+     * `MiraCore::load_style_rules`).
+     * @param {string} rules_json
+     */
+    load_style_rules(rules_json) {
+        const ptr0 = passStringToWasm0(rules_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sessionmanager_load_style_rules(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @returns {string | undefined}
+     */
+    active_session_id() {
+        const ret = wasm.sessionmanager_active_session_id(this.__wbg_ptr);
+        let v1;
+        if (ret[0] !== 0) {
+            v1 = getStringFromWasm0(ret[0], ret[1]).slice();
+            wasm.__wbindgen_free(ret[0], ret[1] * 1, 1);
+        }
+        return v1;
+    }
+    /**
+     * Load the Live2D expression registry for every session (see
+     * `MiraCore::load_expression_registry`).
+     * @param {string} model3_json
+     * @param {string} mapping_json
+     */
+    load_expression_registry(model3_json, mapping_json) {
+        const ptr0 = passStringToWasm0(model3_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passStringToWasm0(mapping_json, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.sessionmanager_load_expression_registry(this.__wbg_ptr, ptr0, len0, ptr1, len1);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    constructor() {
+        const ret = wasm.sessionmanager_new();
+        this.__wbg_ptr = ret >>> 0;
+        SessionManagerFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @returns {MiraCore | undefined}
+     */
+    active() {
+        const ret = wasm.sessionmanager_active(this.__wbg_ptr);
+        return ret === 0 ? undefined : MiraCore.__wrap(ret);
+    }
+    /**
+     * @param {string} id
+     * @returns {MiraCore}
+     */
+    session(id) {
+        const ptr0 = passStringToWasm0(id, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.sessionmanager_session(this.__wbg_ptr, ptr0, len0);
+        if (ret[2]) {
+            throw takeFromExternrefTable0(ret[1]);
+        }
+        return MiraCore.__wrap(ret[0]);
+    }
+}
+if (Symbol.dispose) SessionManager.prototype[Symbol.dispose] = SessionManager.prototype.free;
+
 const EXPECTED_RESPONSE_TYPES = new Set(['basic', 'cors', 'default']);
 
 async function __wbg_load(module, imports) {
@@ -543,10 +2104,44 @@ function __wbg_get_imports() {
         const ret = Error(getStringFromWasm0(arg0, arg1));
         return ret;
     };
-    imports.wbg.__wbg_getwithrefkey_1dc361bd10053bfe = function(arg0, arg1) {
-        const ret = arg0[arg1];
+    imports.wbg.__wbg_String_8f0eb39a4a4c2f66 = function(arg0, arg1) {
+        const ret = String(arg1);
+        const ptr1 = passStringToWasm0(ret, wasm.__wbindgen_malloc, wasm.__wbindgen_realloc);
+        const len1 = WASM_VECTOR_LEN;
+        getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
+        getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
+    };
+    imports.wbg.__wbg_call_13410aac570ffff7 = function() { return handleError(function (arg0, arg1) {
+        const ret = arg0.call(arg1);
+        return ret;
+    }, arguments) };
+    imports.wbg.__wbg_call_a5400b25a865cfd8 = function() { return handleError(function (arg0, arg1, arg2) {
+        const ret = arg0.call(arg1, arg2);
+        return ret;
+    }, arguments) };
+    imports.wbg.__wbg_crypto_86f2631e91b51511 = function(arg0) {
+        const ret = arg0.crypto;
+        return ret;
+    };
+    imports.wbg.__wbg_done_75ed0ee6dd243d9d = function(arg0) {
+        const ret = arg0.done;
+        return ret;
+    };
+    imports.wbg.__wbg_entries_2be2f15bd5554996 = function(arg0) {
+        const ret = Object.entries(arg0);
+        return ret;
+    };
+    imports.wbg.__wbg_getRandomValues_b3f15fcbfabb0f8b = function() { return handleError(function (arg0, arg1) {
+        arg0.getRandomValues(arg1);
+    }, arguments) };
+    imports.wbg.__wbg_get_0da715ceaecea5c8 = function(arg0, arg1) {
+        const ret = arg0[arg1 >>> 0];
         return ret;
     };
+    imports.wbg.__wbg_get_458e874b43b18b25 = function() { return handleError(function (arg0, arg1) {
+        const ret = Reflect.get(arg0, arg1);
+        return ret;
+    }, arguments) };
     imports.wbg.__wbg_instanceof_ArrayBuffer_67f3012529f6a2dd = function(arg0) {
         let result;
         try {
@@ -567,10 +2162,22 @@ function __wbg_get_imports() {
         const ret = result;
         return ret;
     };
+    imports.wbg.__wbg_iterator_f370b34483c71a1c = function() {
+        const ret = Symbol.iterator;
+        return ret;
+    };
+    imports.wbg.__wbg_length_186546c51cd61acd = function(arg0) {
+        const ret = arg0.length;
+        return ret;
+    };
     imports.wbg.__wbg_length_6bb7e81f9d7713e4 = function(arg0) {
         const ret = arg0.length;
         return ret;
     };
+    imports.wbg.__wbg_msCrypto_d562bbe83e0d4b91 = function(arg0) {
+        const ret = arg0.msCrypto;
+        return ret;
+    };
     imports.wbg.__wbg_new_19c25a3f2fa63a02 = function() {
         const ret = new Object();
         return ret;
@@ -579,27 +2186,93 @@ function __wbg_get_imports() {
         const ret = new Array();
         return ret;
     };
+    imports.wbg.__wbg_new_2ff1f68f3676ea53 = function() {
+        const ret = new Map();
+        return ret;
+    };
     imports.wbg.__wbg_new_638ebfaedbf32a5e = function(arg0) {
         const ret = new Uint8Array(arg0);
         return ret;
     };
-    imports.wbg.__wbg_now_1e80617bcee43265 = function() {
-        const ret = Date.now();
+    imports.wbg.__wbg_new_da9dc54c5db29dfa = function(arg0, arg1) {
+        const ret = new Error(getStringFromWasm0(arg0, arg1));
+        return ret;
+    };
+    imports.wbg.__wbg_newnoargs_254190557c45b4ec = function(arg0, arg1) {
+        const ret = new Function(getStringFromWasm0(arg0, arg1));
+        return ret;
+    };
+    imports.wbg.__wbg_newwithlength_a167dcc7aaa3ba77 = function(arg0) {
+        const ret = new Uint8Array(arg0 >>> 0);
+        return ret;
+    };
+    imports.wbg.__wbg_next_5b3530e612fde77d = function(arg0) {
+        const ret = arg0.next;
+        return ret;
+    };
+    imports.wbg.__wbg_next_692e82279131b03c = function() { return handleError(function (arg0) {
+        const ret = arg0.next();
+        return ret;
+    }, arguments) };
+    imports.wbg.__wbg_node_e1f24f89a7336c2e = function(arg0) {
+        const ret = arg0.node;
+        return ret;
+    };
+    imports.wbg.__wbg_process_3975fd6c72f520aa = function(arg0) {
+        const ret = arg0.process;
         return ret;
     };
     imports.wbg.__wbg_prototypesetcall_3d4a26c1ed734349 = function(arg0, arg1, arg2) {
         Uint8Array.prototype.set.call(getArrayU8FromWasm0(arg0, arg1), arg2);
     };
-    imports.wbg.__wbg_random_7ed63a0b38ee3b75 = function() {
-        const ret = Math.random();
+    imports.wbg.__wbg_randomFillSync_f8c153b79f285817 = function() { return handleError(function (arg0, arg1) {
+        arg0.randomFillSync(arg1);
+    }, arguments) };
+    imports.wbg.__wbg_require_b74f47fc2d022fd6 = function() { return handleError(function () {
+        const ret = module.require;
         return ret;
-    };
+    }, arguments) };
     imports.wbg.__wbg_set_3f1d0b984ed272ed = function(arg0, arg1, arg2) {
         arg0[arg1] = arg2;
     };
     imports.wbg.__wbg_set_90f6c0f7bd8c0415 = function(arg0, arg1, arg2) {
         arg0[arg1 >>> 0] = arg2;
     };
+    imports.wbg.__wbg_set_b7f1cf4fae26fe2a = function(arg0, arg1, arg2) {
+        const ret = arg0.set(arg1, arg2);
+        return ret;
+    };
+    imports.wbg.__wbg_setname_832b43d4602cb930 = function(arg0, arg1, arg2) {
+        arg0.name = getStringFromWasm0(arg1, arg2);
+    };
+    imports.wbg.__wbg_static_accessor_GLOBAL_8921f820c2ce3f12 = function() {
+        const ret = typeof global === 'undefined' ? null : global;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_static_accessor_GLOBAL_THIS_f0a4409105898184 = function() {
+        const ret = typeof globalThis === 'undefined' ? null : globalThis;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_static_accessor_SELF_995b214ae681ff99 = function() {
+        const ret = typeof self === 'undefined' ? null : self;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_static_accessor_WINDOW_cde3890479c675ea = function() {
+        const ret = typeof window === 'undefined' ? null : window;
+        return isLikeNone(ret) ? 0 : addToExternrefTable0(ret);
+    };
+    imports.wbg.__wbg_subarray_70fd07feefe14294 = function(arg0, arg1, arg2) {
+        const ret = arg0.subarray(arg1 >>> 0, arg2 >>> 0);
+        return ret;
+    };
+    imports.wbg.__wbg_value_dd9372230531eade = function(arg0) {
+        const ret = arg0.value;
+        return ret;
+    };
+    imports.wbg.__wbg_versions_4e31226f5e8dc909 = function(arg0) {
+        const ret = arg0.versions;
+        return ret;
+    };
     imports.wbg.__wbg_wbindgenbooleanget_3fe6f642c7d97746 = function(arg0) {
         const v = arg0;
         const ret = typeof(v) === 'boolean' ? v : undefined;
@@ -612,8 +2285,8 @@ function __wbg_get_imports() {
         getDataViewMemory0().setInt32(arg0 + 4 * 1, len1, true);
         getDataViewMemory0().setInt32(arg0 + 4 * 0, ptr1, true);
     };
-    imports.wbg.__wbg_wbindgenin_d7a1ee10933d2d55 = function(arg0, arg1) {
-        const ret = arg0 in arg1;
+    imports.wbg.__wbg_wbindgenisfunction_8cee7dce3725ae74 = function(arg0) {
+        const ret = typeof(arg0) === 'function';
         return ret;
     };
     imports.wbg.__wbg_wbindgenisobject_307a53c6bd97fbf8 = function(arg0) {
@@ -621,6 +2294,10 @@ function __wbg_get_imports() {
         const ret = typeof(val) === 'object' && val !== null;
         return ret;
     };
+    imports.wbg.__wbg_wbindgenisstring_d4fa939789f003b0 = function(arg0) {
+        const ret = typeof(arg0) === 'string';
+        return ret;
+    };
     imports.wbg.__wbg_wbindgenisundefined_c4b71d073b92f3c5 = function(arg0) {
         const ret = arg0 === undefined;
         return ret;
@@ -651,9 +2328,14 @@ function __wbg_get_imports() {
         const ret = getStringFromWasm0(arg0, arg1);
         return ret;
     };
-    imports.wbg.__wbindgen_cast_9ae0607507abb057 = function(arg0) {
-        // Cast intrinsic for `I64 -> Externref`.
-        const ret = arg0;
+    imports.wbg.__wbindgen_cast_4625c577ab2ec9ee = function(arg0) {
+        // Cast intrinsic for `U64 -> Externref`.
+        const ret = BigInt.asUintN(64, arg0);
+        return ret;
+    };
+    imports.wbg.__wbindgen_cast_cb9088102bce6b30 = function(arg0, arg1) {
+        // Cast intrinsic for `Ref(Slice(U8)) -> NamedExternref("Uint8Array")`.
+        const ret = getArrayU8FromWasm0(arg0, arg1);
         return ret;
     };
     imports.wbg.__wbindgen_cast_d6cd19b81560fd6e = function(arg0) {
@@ -662,7 +2344,7 @@ function __wbg_get_imports() {
         return ret;
     };
     imports.wbg.__wbindgen_init_externref_table = function() {
-        const table = wasm.__wbindgen_export_2;
+        const table = wasm.__wbindgen_export_4;
         const offset = table.grow(4);
         table.set(0, undefined);
         table.set(offset + 0, undefined);
@@ -683,6 +2365,7 @@ function __wbg_finalize_init(instance, module) {
     wasm = instance.exports;
     __wbg_init.__wbindgen_wasm_module = module;
     cachedDataViewMemory0 = null;
+    cachedFloat32ArrayMemory0 = null;
     cachedUint8ArrayMemory0 = null;
 
 
@@ -1,31 +1,151 @@
 /* tslint:disable */
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
+export const __wbg_expressioninterpolator_free: (a: number, b: number) => void;
+export const __wbg_gazesolver_free: (a: number, b: number) => void;
+export const __wbg_idleanimator_free: (a: number, b: number) => void;
+export const __wbg_lipsynctrack_free: (a: number, b: number) => void;
 export const __wbg_miracore_free: (a: number, b: number) => void;
-export const miracore_new: () => number;
-export const miracore_preprocess_input: (a: number, b: number, c: number) => any;
-export const miracore_calculate_sentiment_advanced: (a: number, b: number, c: number) => any;
-export const miracore_calculate_sentiment: (a: number, b: number, c: number) => number;
-export const miracore_detect_mood: (a: number, b: number, c: number) => [number, number];
-export const miracore_get_dominant_mood: (a: number) => [number, number];
+export const __wbg_sessionmanager_free: (a: number, b: number) => void;
+export const expressioninterpolator_cancel: (a: number, b: number) => [number, number];
+export const expressioninterpolator_current_expression: (a: number) => [number, number];
+export const expressioninterpolator_is_animating: (a: number) => number;
+export const expressioninterpolator_new: (a: number, b: number) => number;
+export const expressioninterpolator_sample: (a: number, b: number) => any;
+export const expressioninterpolator_then: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number];
+export const expressioninterpolator_transition_to: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number];
+export const gazesolver_clear_target: (a: number) => void;
+export const gazesolver_new: (a: number, b: number) => number;
+export const gazesolver_parameter_ids: () => [number, number];
+export const gazesolver_set_canvas: (a: number, b: number, c: number, d: number, e: number) => void;
+export const gazesolver_set_limits: (a: number, b: number, c: number, d: number, e: number) => void;
+export const gazesolver_set_look_away_after: (a: number, b: number) => void;
+export const gazesolver_set_mood: (a: number, b: number, c: number) => [number, number];
+export const gazesolver_set_target: (a: number, b: number, c: number) => void;
+export const gazesolver_update: (a: number, b: number) => [number, number];
+export const gazesolver_update_frame: (a: number, b: number) => any;
+export const idleanimator_new: (a: number, b: number) => number;
+export const idleanimator_parameter_ids: () => [number, number];
+export const idleanimator_sample: (a: number, b: number) => [number, number];
+export const idleanimator_sample_frame: (a: number, b: number) => any;
+export const idleanimator_set_energy: (a: number, b: number) => void;
+export const idleanimator_set_mood: (a: number, b: number, c: number) => [number, number];
+export const lipsynctrack_duration_ms: (a: number) => number;
+export const lipsynctrack_keyframes: (a: number) => any;
+export const lipsynctrack_new: (a: number, b: number, c: number) => number;
+export const lipsynctrack_sample: (a: number, b: number) => [number, number];
+export const lipsynctrack_segments: (a: number) => any;
+export const miracore_affect_prompt_context: (a: number, b: number) => [number, number];
+export const miracore_apply_affect_event: (a: number, b: number, c: number, d: number) => [number, number, number];
+export const miracore_blend_expression_parameters: (a: number, b: any) => [number, number, number];
+export const miracore_blend_expression_parameters_array: (a: number, b: any) => [number, number, number, number];
+export const miracore_blend_expressions: (a: number, b: number, c: number, d: number) => any;
+export const miracore_build_conversation_context: (a: number, b: number, c: number) => [number, number];
 export const miracore_calculate_mood_transition: (a: number, b: number, c: number, d: number) => [number, number];
+export const miracore_calculate_sentiment: (a: number, b: number, c: number) => number;
+export const miracore_calculate_sentiment_advanced: (a: number, b: number, c: number) => any;
+export const miracore_clear_expression_queue: (a: number) => void;
+export const miracore_convert_register: (a: number, b: number, c: number, d: number, e: number) => [number, number, number, number];
+export const miracore_create_expression_interpolator: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+export const miracore_create_gaze_solver: (a: number, b: number, c: number) => number;
+export const miracore_create_idle_animator: (a: number, b: number, c: number) => number;
 export const miracore_detect_expression: (a: number, b: number, c: number) => [number, number];
 export const miracore_detect_expression_with_intensity: (a: number, b: number, c: number) => any;
-export const miracore_blend_expressions: (a: number, b: number, c: number, d: number) => any;
-export const miracore_humanize_response: (a: number, b: number, c: number) => [number, number];
-export const miracore_build_conversation_context: (a: number, b: number, c: number) => [number, number];
+export const miracore_detect_mood: (a: number, b: number, c: number) => [number, number];
+export const miracore_detect_register: (a: number, b: number, c: number) => any;
+export const miracore_detect_reply_emotion: (a: number, b: number, c: number) => any;
+export const miracore_detect_user_emotion: (a: number, b: number, c: number) => any;
+export const miracore_ease: (a: number, b: number, c: number, d: number) => [number, number, number];
+export const miracore_enforce_persona: (a: number, b: number, c: number) => any;
+export const miracore_explain_last_mood: (a: number) => any;
+export const miracore_explain_mood: (a: number, b: number, c: number) => any;
+export const miracore_export_sealed_state: (a: number, b: number, c: number) => [number, number, number, number];
+export const miracore_export_state: (a: number) => [number, number];
+export const miracore_expression_for_emotion: (a: number, b: number, c: number) => [number, number, number, number];
+export const miracore_expression_for_mood: (a: number, b: number, c: number) => [number, number, number, number];
+export const miracore_expression_parameter_ids: (a: number) => [number, number];
 export const miracore_extract_topics: (a: number, b: number, c: number) => [number, number];
 export const miracore_generate_riddle: (a: number) => any;
+export const miracore_get_affect: (a: number, b: number) => any;
+export const miracore_get_baseline: (a: number) => any;
+export const miracore_get_bubble_options: (a: number) => any;
+export const miracore_get_dominant_mood: (a: number) => [number, number];
+export const miracore_get_emoji_rules: (a: number) => any;
+export const miracore_get_expression_history: (a: number) => any;
+export const miracore_get_expression_registry: (a: number) => any;
+export const miracore_get_humanize_profiles: (a: number) => any;
+export const miracore_get_motion_table: (a: number) => any;
 export const miracore_get_offline_response: (a: number) => [number, number];
+export const miracore_get_persona_rules: (a: number) => any;
+export const miracore_get_register: (a: number) => [number, number];
+export const miracore_get_register_mirror: (a: number) => any;
+export const miracore_get_rng_state: (a: number) => any;
+export const miracore_get_seed: (a: number) => number;
+export const miracore_get_style_rules: (a: number) => any;
+export const miracore_has_expression: (a: number, b: number, c: number) => number;
+export const miracore_humanize_response: (a: number, b: number, c: number) => [number, number];
+export const miracore_humanize_response_report: (a: number, b: number, c: number) => any;
+export const miracore_import_sealed_state: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const miracore_import_state: (a: number, b: number, c: number) => [number, number];
+export const miracore_inject_emoji: (a: number, b: number, c: number) => any;
 export const miracore_interpolate_expression: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number];
-export const miracore_create_expression_interpolator: (a: number, b: number, c: number, d: number, e: number, f: bigint) => any;
+export const miracore_interpolate_expression_parameters: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number];
 export const miracore_interpolate_expression_smooth: (a: number, b: number, c: number, d: number, e: number, f: number) => number;
-export const quick_mood_check: (a: number, b: number) => [number, number];
+export const miracore_is_crisis: (a: number) => number;
+export const miracore_list_expressions: (a: number) => [number, number];
+export const miracore_load_emoji_rules: (a: number, b: number, c: number) => [number, number];
+export const miracore_load_expression_parameters: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const miracore_load_expression_registry: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const miracore_load_humanize_profiles: (a: number, b: number, c: number) => [number, number];
+export const miracore_load_motion_table: (a: number, b: number, c: number) => [number, number];
+export const miracore_load_style_rules: (a: number, b: number, c: number) => [number, number];
+export const miracore_new: () => number;
+export const miracore_observe_user_message: (a: number, b: number, c: number, d: number) => any;
+export const miracore_observe_user_register: (a: number, b: number, c: number) => any;
+export const miracore_preprocess_input: (a: number, b: number, c: number) => any;
+export const miracore_queue_expression: (a: number, b: number, c: number, d: number, e: number, f: number, g: number) => [number, number, number];
+export const miracore_reset_baseline: (a: number) => void;
+export const miracore_reset_motion_cooldowns: (a: number) => void;
+export const miracore_resolve_display_expression: (a: number) => any;
+export const miracore_select_motion: (a: number, b: number, c: number, d: number) => [number, number, number];
+export const miracore_set_affect_dynamics: (a: number, b: number, c: number) => [number, number];
+export const miracore_set_baseline_enabled: (a: number, b: number) => void;
+export const miracore_set_bubble_options: (a: number, b: number, c: number) => [number, number];
+export const miracore_set_crisis: (a: number, b: number) => void;
+export const miracore_set_idle_mode: (a: number, b: number, c: number) => [number, number];
+export const miracore_set_parameter_base_values: (a: number, b: any) => [number, number];
+export const miracore_set_persona_rules: (a: number, b: number, c: number) => [number, number];
+export const miracore_set_register: (a: number, b: number, c: number) => [number, number];
+export const miracore_set_register_mirroring: (a: number, b: number) => void;
+export const miracore_set_seed: (a: number, b: number) => void;
+export const miracore_set_truncation_budget: (a: number, b: number, c: number, d: number) => void;
+export const miracore_split_bubbles: (a: number, b: number, c: number) => any;
+export const miracore_state_version: () => number;
+export const miracore_tick: (a: number, b: number) => any;
+export const miracore_truncate_response: (a: number, b: number, c: number, d: number, e: number, f: number) => any;
 export const polish_text: (a: number, b: number) => [number, number];
+export const quick_mood_check: (a: number, b: number) => [number, number];
+export const rotate_sealed_state: (a: number, b: number, c: number, d: number, e: number, f: number) => [number, number, number, number];
+export const seal_state: (a: number, b: number, c: number, d: number) => [number, number, number, number];
+export const sessionmanager_active: (a: number) => number;
+export const sessionmanager_active_session_id: (a: number) => [number, number];
+export const sessionmanager_aggregate_stats: (a: number) => any;
+export const sessionmanager_create_session: (a: number, b: number, c: number) => [number, number, number];
+export const sessionmanager_delete_session: (a: number, b: number, c: number) => number;
+export const sessionmanager_has_session: (a: number, b: number, c: number) => number;
+export const sessionmanager_list_sessions: (a: number) => [number, number];
+export const sessionmanager_load_expression_registry: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const sessionmanager_load_style_rules: (a: number, b: number, c: number) => [number, number];
+export const sessionmanager_new: () => number;
+export const sessionmanager_session: (a: number, b: number, c: number) => [number, number, number];
+export const sessionmanager_switch_session: (a: number, b: number, c: number) => [number, number, number];
+export const unseal_state: (a: number, b: number, c: number, d: number) => [number, number, number, number];
 export const __wbindgen_malloc: (a: number, b: number) => number;
 export const __wbindgen_realloc: (a: number, b: number, c: number, d: number) => number;
-export const __wbindgen_export_2: WebAssembly.Table;
-export const __wbindgen_free: (a: number, b: number, c: number) => void;
+export const __wbindgen_exn_store: (a: number) => void;
 export const __externref_table_alloc: () => number;
+export const __wbindgen_export_4: WebAssembly.Table;
+export const __externref_table_dealloc: (a: number) => void;
+export const __wbindgen_free: (a: number, b: number, c: number) => void;
 export const __externref_drop_slice: (a: number, b: number) => void;
 export const __wbindgen_start: () => void;
@@ -0,0 +1,282 @@
+// src/wasm/src/affect.rs
+use serde::{Deserialize, Serialize};
+
+use crate::empathy::DisplayDecision;
+use crate::lexicon::Lexicon;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct AffectDelta {
+    pub energy: f32,
+    pub warmth: f32,
+    pub rapport: f32,
+}
+
+/// How MIRA's affect moves: where each variable rests, how fast it drifts
+/// back and how far messages, compliments and insults push it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct AffectDynamics {
+    /// Where each variable drifts back to when nothing happens.
+    pub resting: AffectDelta,
+    /// Time for half the distance to `resting` to go, per variable.
+    pub half_life_ms: AffectDelta,
+    pub message: AffectDelta,
+    pub compliment: AffectDelta,
+    pub insult: AffectDelta,
+    /// Applied per message once a conversation passes `long_chat_after`.
+    pub long_chat: AffectDelta,
+    pub long_chat_after: u32,
+    /// A gap this long ends the conversation; the next message is a return.
+    pub away_after_ms: f64,
+    pub returned: AffectDelta,
+}
+
+impl Default for AffectDynamics {
+    fn default() -> Self {
+        const MINUTE: f32 = 60_000.0;
+        Self {
+            resting: AffectDelta { energy: 0.6, warmth: 0.5, rapport: 0.0 },
+            half_life_ms: AffectDelta {
+                energy: 20.0 * MINUTE,
+                warmth: 120.0 * MINUTE,
+                rapport: 7.0 * 24.0 * 60.0 * MINUTE,
+            },
+            message: AffectDelta { energy: -0.005, warmth: 0.01, rapport: 0.01 },
+            compliment: AffectDelta { energy: 0.06, warmth: 0.12, rapport: 0.04 },
+            insult: AffectDelta { energy: -0.08, warmth: -0.2, rapport: -0.06 },
+            long_chat: AffectDelta { energy: -0.02, warmth: 0.0, rapport: 0.01 },
+            long_chat_after: 30,
+            away_after_ms: 30.0 * MINUTE as f64,
+            returned: AffectDelta { energy: 0.0, warmth: 0.05, rapport: 0.0 },
+        }
+    }
+}
+
+impl AffectDynamics {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let dynamics: AffectDynamics = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let half_lives = [
+            dynamics.half_life_ms.energy,
+            dynamics.half_life_ms.warmth,
+            dynamics.half_life_ms.rapport,
+        ];
+        if half_lives.iter().any(|h| !h.is_finite() || *h <= 0.0) {
+            return Err("half_life_ms values must be positive".to_string());
+        }
+        if !dynamics.away_after_ms.is_finite() || dynamics.away_after_ms <= 0.0 {
+            return Err("away_after_ms must be positive".to_string());
+        }
+        Ok(dynamics)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum AffectEvent {
+    Message,
+    Compliment,
+    Insult,
+    LongChat,
+    Returned,
+}
+
+impl AffectEvent {
+    /// Events callers may report directly; the others follow from time and
+    /// message counts.
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "message" => Some(AffectEvent::Message),
+            "compliment" => Some(AffectEvent::Compliment),
+            "insult" => Some(AffectEvent::Insult),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct AffectReport<'a> {
+    pub events: Vec<AffectEvent>,
+    pub affect: &'a Affect,
+}
+
+// ========== AFFECT STATE ==========
+/// MIRA's own state, separate from the user's mood. All variables are 0-1.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Affect {
+    pub energy: f32,
+    pub warmth: f32,
+    pub rapport: f32,
+    pub messages_in_conversation: u32,
+    pub updated_at: Option<f64>,
+}
+
+impl Affect {
+    pub fn new() -> Self {
+        let resting = AffectDynamics::default().resting;
+        Self {
+            energy: resting.energy,
+            warmth: resting.warmth,
+            rapport: resting.rapport,
+            messages_in_conversation: 0,
+            updated_at: None,
+        }
+    }
+
+    /// The state as it would be at `now_ms`, without recording anything.
+    pub fn at(&self, now_ms: f64, dynamics: &AffectDynamics) -> Self {
+        let mut affect = self.clone();
+        affect.relax(now_ms, dynamics);
+        affect
+    }
+
+    /// Drift toward resting for the time since the last update. Returns
+    /// true when the gap was long enough to count as time away.
+    fn relax(&mut self, now_ms: f64, dynamics: &AffectDynamics) -> bool {
+        let Some(last) = self.updated_at else {
+            self.updated_at = Some(now_ms);
+            return false;
+        };
+        let dt = (now_ms - last).max(0.0);
+        let decay = |value: f32, rest: f32, half_life: f32| {
+            rest + (value - rest) * 0.5f32.powf((dt / half_life as f64) as f32)
+        };
+
+        let (rest, half) = (dynamics.resting, dynamics.half_life_ms);
+        self.energy = decay(self.energy, rest.energy, half.energy);
+        self.warmth = decay(self.warmth, rest.warmth, half.warmth);
+        self.rapport = decay(self.rapport, rest.rapport, half.rapport);
+        self.updated_at = Some(last.max(now_ms));
+        dt >= dynamics.away_after_ms
+    }
+
+    fn nudge(&mut self, delta: AffectDelta) {
+        self.energy = (self.energy + delta.energy).clamp(0.0, 1.0);
+        self.warmth = (self.warmth + delta.warmth).clamp(0.0, 1.0);
+        self.rapport = (self.rapport + delta.rapport).clamp(0.0, 1.0);
+    }
+
+    /// Apply `event` at `now_ms`. Returns every event that took effect,
+    /// including implied ones (returning after a break, a long chat).
+    pub fn apply(
+        &mut self,
+        event: AffectEvent,
+        now_ms: f64,
+        dynamics: &AffectDynamics,
+    ) -> Vec<AffectEvent> {
+        let mut applied = Vec::new();
+        if self.relax(now_ms, dynamics) {
+            self.messages_in_conversation = 0;
+            self.nudge(dynamics.returned);
+            applied.push(AffectEvent::Returned);
+        }
+
+        applied.push(event);
+        match event {
+            AffectEvent::Message => {
+                self.messages_in_conversation = self.messages_in_conversation.saturating_add(1);
+                self.nudge(dynamics.message);
+                if self.messages_in_conversation > dynamics.long_chat_after {
+                    self.nudge(dynamics.long_chat);
+                    applied.push(AffectEvent::LongChat);
+                }
+            }
+            AffectEvent::Compliment => self.nudge(dynamics.compliment),
+            AffectEvent::Insult => self.nudge(dynamics.insult),
+            AffectEvent::LongChat => self.nudge(dynamics.long_chat),
+            AffectEvent::Returned => self.nudge(dynamics.returned),
+        }
+        applied
+    }
+
+    pub fn is_valid(&self) -> bool {
+        let unit = |v: f32| (0.0..=1.0).contains(&v);
+        unit(self.energy)
+            && unit(self.warmth)
+            && unit(self.rapport)
+            && self.updated_at.is_none_or(f64::is_finite)
+    }
+
+    // ========== EFFECTS ==========
+    /// Tired MIRA emotes less, energetic MIRA more.
+    pub fn expressiveness(&self) -> f32 {
+        0.6 + 0.8 * self.energy
+    }
+
+    /// Scale the displayed intensity, and keep smiles reserved while she is
+    /// cold toward the user.
+    pub fn shade(&self, decision: &mut DisplayDecision) {
+        decision.intensity = (decision.intensity * self.expressiveness()).min(1.0);
+        if self.warmth < 0.3 && decision.emotion == "happy" {
+            decision.emotion = "soft_smile".to_string();
+        }
+    }
+
+    /// Scale for the casual touches of humanized text (fillers, particles,
+    /// emoji, laughter); grows with rapport.
+    pub fn casualness(&self) -> f64 {
+        0.5 + self.rapport as f64
+    }
+
+    /// Sentences humanization keeps out of `base`; a tired MIRA keeps it
+    /// shorter.
+    pub fn sentence_budget(&self, base: usize) -> usize {
+        if self.energy < 0.3 {
+            base.saturating_sub(1).max(1)
+        } else {
+            base
+        }
+    }
+
+    /// One line for the LLM system prompt.
+    pub fn prompt_context(&self) -> String {
+        let energy = match self.energy {
+            e if e < 0.3 => "low (agak capek, jawab singkat)",
+            e if e < 0.7 => "normal",
+            _ => "high (semangat)",
+        };
+        let warmth = match self.warmth {
+            w if w < 0.3 => "cool (lagi agak jaga jarak)",
+            w if w < 0.7 => "warm",
+            _ => "very warm (lagi seneng sama user)",
+        };
+        let rapport = match self.rapport {
+            r if r < 0.2 => "new (baru kenal)",
+            r if r < 0.6 => "familiar",
+            _ => "close (udah akrab banget)",
+        };
+        format!("[MIRA's state: energy {}, warmth {}, rapport {}]", energy, warmth, rapport)
+    }
+}
+
+impl Default for Affect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Events a user message implies: always a message, plus a compliment or
+/// an insult when one is aimed at MIRA (or is plain thanks).
+pub fn classify(lexicon: &Lexicon, text: &str) -> Vec<AffectEvent> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+        .filter(|w| !w.is_empty())
+        .collect();
+    let pairs: Vec<String> = words.windows(2).map(|w| w.join(" ")).collect();
+    let has = |list: &[String]| {
+        list.iter()
+            .any(|entry| words.contains(&entry.as_str()) || pairs.contains(entry))
+    };
+
+    let addressed = has(&lexicon.address_words);
+    let mut events = vec![AffectEvent::Message];
+    if has(&lexicon.gratitude_words) || (addressed && has(&lexicon.compliment_words)) {
+        events.push(AffectEvent::Compliment);
+    }
+    if addressed && has(&lexicon.insult_words) {
+        events.push(AffectEvent::Insult);
+    }
+    events
+}
@@ -0,0 +1,158 @@
+// src/wasm/src/baseline.rs
+use serde::{Deserialize, Serialize};
+
+/// Messages needed before the baseline is trusted. Until then `detect_mood`
+/// uses the raw sentiment score with the fixed thresholds.
+pub const WARMUP_SAMPLES: u32 = 8;
+
+/// After this many samples the running stats become an exponential moving
+/// average, so the baseline follows a user whose tone drifts over time.
+const WINDOW: u32 = 50;
+
+// The learned baseline may only shift the scale so far; an extreme user
+// should still be able to reach every mood.
+const MAX_MEAN_SHIFT: f32 = 3.0;
+const MIN_STD_DEV: f32 = 1.0;
+const MAX_STD_DEV: f32 = 4.0;
+
+/// Spread the fixed thresholds in `detect_mood` were tuned for.
+const NOMINAL_STD_DEV: f32 = 2.0;
+
+/// Running sentiment mean and variance of one user.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SentimentBaseline {
+    pub samples: u32,
+    pub mean: f32,
+    pub variance: f32,
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+pub struct BaselineInfo {
+    pub samples: u32,
+    pub warmup_samples: u32,
+    pub warmed_up: bool,
+    pub enabled: bool,
+    pub mean: f32,
+    pub std_dev: f32,
+    pub effective_mean: f32,
+    pub effective_std_dev: f32,
+}
+
+impl SentimentBaseline {
+    pub fn new() -> Self {
+        Self {
+            samples: 0,
+            mean: 0.0,
+            variance: 0.0,
+            enabled: true,
+        }
+    }
+
+    pub fn observe(&mut self, score: i32) {
+        let x = score as f32;
+        self.samples = self.samples.saturating_add(1);
+
+        let alpha = 1.0 / self.samples.min(WINDOW) as f32;
+        let diff = x - self.mean;
+        let incr = alpha * diff;
+        self.mean += incr;
+        self.variance = (1.0 - alpha) * (self.variance + diff * incr);
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.enabled && self.samples >= WARMUP_SAMPLES
+    }
+
+    fn effective_mean(&self) -> f32 {
+        self.mean.clamp(-MAX_MEAN_SHIFT, MAX_MEAN_SHIFT)
+    }
+
+    fn effective_std_dev(&self) -> f32 {
+        self.variance.sqrt().clamp(MIN_STD_DEV, MAX_STD_DEV)
+    }
+
+    /// Express `score` as a deviation from this user's norm, rescaled to the
+    /// nominal spread so the existing thresholds still apply. Returns the raw
+    /// score while warming up or when disabled.
+    pub fn adjust(&self, score: i32) -> i32 {
+        if !self.is_active() {
+            return score;
+        }
+
+        let deviation = score as f32 - self.effective_mean();
+        (deviation * NOMINAL_STD_DEV / self.effective_std_dev()).round() as i32
+    }
+
+    pub fn info(&self) -> BaselineInfo {
+        BaselineInfo {
+            samples: self.samples,
+            warmup_samples: WARMUP_SAMPLES,
+            warmed_up: self.samples >= WARMUP_SAMPLES,
+            enabled: self.enabled,
+            mean: self.mean,
+            std_dev: self.variance.sqrt(),
+            effective_mean: self.effective_mean(),
+            effective_std_dev: self.effective_std_dev(),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.mean.is_finite() && self.variance.is_finite() && self.variance >= 0.0
+    }
+}
+
+impl Default for SentimentBaseline {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn observed(scores: impl IntoIterator<Item = i32>) -> SentimentBaseline {
+        let mut baseline = SentimentBaseline::new();
+        scores.into_iter().for_each(|s| baseline.observe(s));
+        baseline
+    }
+
+    #[test]
+    fn raw_score_until_warmed_up() {
+        let mut baseline = observed([4; WARMUP_SAMPLES as usize - 1]);
+        assert!(!baseline.is_active());
+        assert_eq!(baseline.adjust(4), 4);
+
+        baseline.observe(4);
+        assert!(baseline.is_active());
+        assert_ne!(baseline.adjust(4), 4);
+
+        baseline.enabled = false;
+        assert_eq!(baseline.adjust(4), 4);
+    }
+
+    #[test]
+    fn mean_and_spread_are_bounded() {
+        // Always +5 with no spread: the mean stops at +3, the spread at 1
+        let upbeat = observed([5; 20]);
+        assert_eq!(upbeat.info().effective_mean, MAX_MEAN_SHIFT);
+        assert_eq!(upbeat.info().effective_std_dev, MIN_STD_DEV);
+        assert_eq!(upbeat.adjust(5), 4);
+
+        // Swings of ±10: the spread stops at 4
+        let volatile = observed([10, -10].repeat(10));
+        assert!(volatile.mean.abs() < 1e-3);
+        assert_eq!(volatile.info().effective_std_dev, MAX_STD_DEV);
+        assert_eq!(volatile.adjust(8), 4);
+        assert_eq!(volatile.adjust(-8), -4);
+    }
+
+    #[test]
+    fn follows_a_drifting_user() {
+        let baseline = observed(std::iter::repeat_n(0, 100).chain(std::iter::repeat_n(2, 100)));
+        // A plain running mean would sit at 1.0
+        assert!(baseline.mean > 1.5, "{}", baseline.mean);
+        assert!(baseline.is_valid());
+    }
+}
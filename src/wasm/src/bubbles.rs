@@ -0,0 +1,285 @@
+// src/wasm/src/bubbles.rs
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::rng::Rng;
+use crate::truncate::{self, UnitKind};
+
+/// Typing speed is quoted in words; a word is about this many characters.
+const CHARS_PER_WORD: f64 = 5.0;
+
+/// How replies are split into chat bubbles, and how long MIRA "types"
+/// each one before it lands.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct BubbleOptions {
+    /// A bubble shorter than this takes the next sentence along.
+    pub min_chars: usize,
+    /// Longer sentences are split after commas and semicolons where they
+    /// can be.
+    pub max_chars: usize,
+    /// 0 means no limit; past it the shortest neighbours are merged.
+    pub max_bubbles: usize,
+    pub words_per_minute: f64,
+    /// Typing time multiplier per mood; crisis replies use "crisis".
+    pub mood_pace: HashMap<String, f64>,
+    /// Every delay is scaled by a random factor in 1 ± jitter.
+    pub jitter: f64,
+    pub min_delay_ms: f64,
+    pub max_delay_ms: f64,
+    /// Gap between one bubble landing and MIRA typing the next.
+    pub pause_ms: f64,
+}
+
+impl Default for BubbleOptions {
+    fn default() -> Self {
+        Self {
+            min_chars: 40,
+            max_chars: 120,
+            max_bubbles: 4,
+            words_per_minute: 150.0,
+            mood_pace: [("chill", 1.0), ("playful", 0.8), ("reflective", 1.25), ("crisis", 1.4)]
+                .iter()
+                .map(|(mood, pace)| (mood.to_string(), *pace))
+                .collect(),
+            jitter: 0.2,
+            min_delay_ms: 600.0,
+            max_delay_ms: 5000.0,
+            pause_ms: 350.0,
+        }
+    }
+}
+
+impl BubbleOptions {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let options: BubbleOptions = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if options.max_chars == 0 || options.min_chars > options.max_chars {
+            return Err("max_chars must be positive and at least min_chars".to_string());
+        }
+        if !options.words_per_minute.is_finite() || options.words_per_minute <= 0.0 {
+            return Err("words_per_minute must be positive".to_string());
+        }
+        if !(0.0..1.0).contains(&options.jitter) {
+            return Err("jitter must be at least 0 and below 1".to_string());
+        }
+        if options.mood_pace.values().any(|p| !p.is_finite() || *p <= 0.0) {
+            return Err("mood_pace values must be positive".to_string());
+        }
+        let delays = [options.min_delay_ms, options.max_delay_ms, options.pause_ms];
+        if delays.iter().any(|d| !d.is_finite() || *d < 0.0) || options.min_delay_ms > options.max_delay_ms {
+            return Err("delays must be non-negative, min_delay_ms at most max_delay_ms".to_string());
+        }
+        Ok(options)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Bubble {
+    pub text: String,
+    /// How long MIRA "types" this bubble.
+    pub typing_ms: f64,
+    /// When it appears, from the start of the reply.
+    pub at_ms: f64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct BubblePlan {
+    pub bubbles: Vec<Bubble>,
+    pub total_ms: f64,
+    pub mood: String,
+    pub pace: f64,
+}
+
+/// A run of consecutive units shown together. `clause` marks one that ends
+/// mid-sentence, after a comma or semicolon.
+#[derive(Clone, Debug)]
+struct Group {
+    kind: UnitKind,
+    range: Range<usize>,
+    clause: bool,
+}
+
+fn chars(text: &str, range: &Range<usize>) -> usize {
+    text[range.clone()].chars().count()
+}
+
+// ========== SPLITTING ==========
+/// Cut a long sentence after commas and semicolons so each piece fits
+/// `max_chars` where it can. A cut never falls inside code, a link or
+/// emphasis; a sentence with no such place stays whole.
+fn clauses(text: &str, sentence: Range<usize>, max_chars: usize) -> Vec<Group> {
+    let segment = &text[sentence.clone()];
+    let protected = truncate::protected_spans(segment);
+    let cuts: Vec<usize> = segment
+        .char_indices()
+        .filter(|&(i, c)| matches!(c, ',' | ';') && segment[i + 1..].starts_with([' ', '\n']))
+        .map(|(i, _)| i + 1)
+        .filter(|&cut| !protected.iter().any(|p| p.start < cut && cut < p.end))
+        .filter(|&cut| segment[..cut].matches('*').count().is_multiple_of(2))
+        .collect();
+
+    let mut groups = Vec::new();
+    let mut start = 0;
+    while segment[start..].chars().count() > max_chars {
+        let fitting = cuts
+            .iter()
+            .rev()
+            .find(|&&cut| cut > start && segment[start..cut].chars().count() <= max_chars);
+        let Some(&cut) = fitting.or_else(|| cuts.iter().find(|&&cut| cut > start)) else {
+            break;
+        };
+        groups.push(Group {
+            kind: UnitKind::Sentence,
+            range: sentence.start + start..sentence.start + cut,
+            clause: true,
+        });
+        start = cut + (segment[cut..].len() - segment[cut..].trim_start().len());
+    }
+    groups.push(Group {
+        kind: UnitKind::Sentence,
+        range: sentence.start + start..sentence.end,
+        clause: false,
+    });
+    groups
+}
+
+/// Byte ranges of the bubbles `text` splits into, and whether each ends
+/// mid-sentence. Code blocks stand alone, list items stay with their list,
+/// sentences never cross a paragraph, and short ones take the next along.
+fn groups(text: &str, options: &BubbleOptions) -> Vec<Group> {
+    let mut groups: Vec<Group> = Vec::new();
+    let mut previous_end = None;
+
+    for (kind, range) in truncate::segments(text) {
+        let paragraph_break = previous_end.is_some_and(|end| text[end..range.start].matches('\n').count() >= 2);
+        previous_end = Some(range.end);
+
+        let pieces = match kind {
+            UnitKind::Sentence => clauses(text, range, options.max_chars),
+            _ => vec![Group { kind, range, clause: false }],
+        };
+        for piece in pieces {
+            let joins = groups.last().is_some_and(|last| {
+                let combined = chars(text, &(last.range.start..piece.range.end));
+                match (last.kind, piece.kind) {
+                    (UnitKind::Sentence, UnitKind::Sentence) => {
+                        !paragraph_break
+                            && !last.clause
+                            && chars(text, &last.range) < options.min_chars
+                            && combined <= options.max_chars
+                    }
+                    (UnitKind::ListItem, UnitKind::ListItem) => combined <= options.max_chars,
+                    _ => false,
+                }
+            });
+            match groups.last_mut() {
+                Some(last) if joins => {
+                    last.range.end = piece.range.end;
+                    last.clause = piece.clause;
+                }
+                _ => groups.push(piece),
+            }
+        }
+    }
+
+    // Too many: merge the neighbours that make the shortest bubble, code
+    // last of all
+    while options.max_bubbles > 0 && groups.len() > options.max_bubbles {
+        let cost = |i: usize| {
+            let code = groups[i].kind == UnitKind::Code || groups[i + 1].kind == UnitKind::Code;
+            (code, chars(text, &(groups[i].range.start..groups[i + 1].range.end)))
+        };
+        let i = (0..groups.len() - 1).min_by_key(|&i| cost(i)).unwrap();
+        let next = groups.remove(i + 1);
+        let merged = &mut groups[i];
+        merged.range.end = next.range.end;
+        merged.clause = next.clause;
+        if merged.kind != next.kind {
+            merged.kind = UnitKind::Sentence;
+        }
+    }
+    groups
+}
+
+/// The bubbles `text` splits into, each a slice of it with markdown intact.
+/// A bubble cut mid-sentence drops its trailing comma.
+pub fn split(text: &str, options: &BubbleOptions) -> Vec<String> {
+    groups(text, options)
+        .into_iter()
+        .map(|group| {
+            let bubble = &text[group.range];
+            if group.clause {
+                bubble.trim_end_matches([',', ';']).to_string()
+            } else {
+                bubble.to_string()
+            }
+        })
+        .collect()
+}
+
+// ========== TIMING ==========
+/// Split `text` and lay the bubbles out on a timeline: each is typed at
+/// `words_per_minute`, scaled by the mood's pace and jittered from `rng`,
+/// then lands after a short pause from the one before.
+pub fn plan(text: &str, options: &BubbleOptions, mood: &str, rng: &mut Rng) -> BubblePlan {
+    let pace = options.mood_pace.get(mood).copied().unwrap_or(1.0);
+    let ms_per_char = 60_000.0 / (options.words_per_minute * CHARS_PER_WORD);
+    let mut jittered = |ms: f64| ms * (1.0 + options.jitter * (rng.next_f64() * 2.0 - 1.0));
+
+    let mut now = 0.0;
+    let mut bubbles = Vec::new();
+    for (i, text) in split(text, options).into_iter().enumerate() {
+        if i > 0 {
+            now += jittered(options.pause_ms * pace);
+        }
+        let typing = jittered(text.chars().count() as f64 * ms_per_char * pace)
+            .clamp(options.min_delay_ms, options.max_delay_ms);
+        now += typing;
+        bubbles.push(Bubble {
+            text,
+            typing_ms: typing.round(),
+            at_ms: now.round(),
+        });
+    }
+
+    BubblePlan {
+        total_ms: now.round(),
+        bubbles,
+        mood: mood.to_string(),
+        pace,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_text_plans_no_bubbles() {
+        for text in ["", "   \n\n  "] {
+            let plan = plan(text, &BubbleOptions::default(), "chill", &mut Rng::new(1));
+            assert!(plan.bubbles.is_empty(), "{:?}", text);
+            assert_eq!(plan.total_ms, 0.0);
+        }
+    }
+
+    #[test]
+    fn short_sentences_share_a_bubble_and_code_stands_alone() {
+        let text = "Oke. Siap!\n\n```py\nprint(1)\n```\n\nSemoga membantu.";
+        assert_eq!(
+            split(text, &BubbleOptions::default()),
+            ["Oke. Siap!", "```py\nprint(1)\n```", "Semoga membantu."]
+        );
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_timeline() {
+        let text = "Halo. Apa kabar kamu hari ini? Semoga baik-baik aja ya, aku kangen ngobrol.";
+        let options = BubbleOptions::default();
+        let a = plan(text, &options, "playful", &mut Rng::new(3));
+        let b = plan(text, &options, "playful", &mut Rng::new(3));
+        assert_eq!(a, b);
+        assert!(a.bubbles.windows(2).all(|w| w[0].at_ms < w[1].at_ms));
+    }
+}
@@ -0,0 +1,338 @@
+// src/wasm/src/easing.rs
+use serde::{Deserialize, Serialize};
+
+/// Easing curves mapping linear progress `t` in 0..1 to eased progress.
+/// Every curve returns exactly 0 at `t = 0` and 1 at `t = 1`; spring and
+/// elastic may overshoot in between.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Easing {
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    #[default]
+    CubicInOut,
+    /// CSS-style `cubic-bezier(x1, y1, x2, y2)`; x1 and x2 are clamped to 0..1.
+    CubicBezier { x1: f32, y1: f32, x2: f32, y2: f32 },
+    /// Damped spring; `damping` is the damping ratio (1.0 = critically damped,
+    /// below 1 bounces).
+    Spring { stiffness: f32, damping: f32 },
+    /// Elastic ease-out; smaller `period` wobbles faster.
+    Elastic { period: f32 },
+}
+
+impl Easing {
+    /// Named presets: "linear", "quad-in", "quad-out", "quad-in-out",
+    /// "cubic-in", "cubic-out", "cubic-in-out", "ease" (CSS ease),
+    /// "spring", "spring-bouncy", "elastic".
+    pub fn from_name(name: &str) -> Option<Self> {
+        let easing = match name {
+            "linear" => Easing::Linear,
+            "quad-in" => Easing::QuadIn,
+            "quad-out" => Easing::QuadOut,
+            "quad-in-out" => Easing::QuadInOut,
+            "cubic-in" => Easing::CubicIn,
+            "cubic-out" => Easing::CubicOut,
+            "cubic-in-out" => Easing::CubicInOut,
+            "ease" => Easing::CubicBezier { x1: 0.25, y1: 0.1, x2: 0.25, y2: 1.0 },
+            "spring" => Easing::Spring { stiffness: 10.0, damping: 1.0 },
+            "spring-bouncy" => Easing::Spring { stiffness: 14.0, damping: 0.4 },
+            "elastic" => Easing::Elastic { period: 0.3 },
+            _ => return None,
+        };
+        Some(easing)
+    }
+
+    /// Parse what JS passes as an easing: a preset name,
+    /// `"cubic-bezier(x1, y1, x2, y2)"`, or a JSON spec such as
+    /// `{"type": "spring", "stiffness": 12, "damping": 0.5}` or
+    /// `{"type": "elastic", "period": 0.4}`.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        if let Some(easing) = Self::from_name(spec) {
+            return Ok(easing);
+        }
+        let easing = if let Some(args) = spec
+            .strip_prefix("cubic-bezier(")
+            .and_then(|rest| rest.strip_suffix(')'))
+        {
+            let values: Vec<f32> = args
+                .split(',')
+                .map(|v| v.trim().parse::<f32>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| format!("invalid cubic-bezier '{}'", spec))?;
+            let [x1, y1, x2, y2] = values[..] else {
+                return Err(format!("cubic-bezier takes 4 numbers, got {}", values.len()));
+            };
+            Easing::CubicBezier { x1, y1, x2, y2 }
+        } else if spec.starts_with('{') {
+            serde_json::from_str(spec).map_err(|e| format!("invalid easing spec: {}", e))?
+        } else {
+            return Err(format!("unknown easing '{}'", spec));
+        };
+        easing.validate()
+    }
+
+    /// Reject parameters the curves can't honour exactly.
+    fn validate(self) -> Result<Self, String> {
+        match self {
+            Easing::CubicBezier { x1, y1, x2, y2 } => {
+                if ![x1, y1, x2, y2].iter().all(|v| v.is_finite()) {
+                    return Err("cubic-bezier values must be finite".to_string());
+                }
+                if !(0.0..=1.0).contains(&x1) || !(0.0..=1.0).contains(&x2) {
+                    return Err("cubic-bezier x1 and x2 must be between 0 and 1".to_string());
+                }
+            }
+            Easing::Spring { stiffness, damping } => {
+                if !stiffness.is_finite() || stiffness <= 0.0 {
+                    return Err("spring stiffness must be positive".to_string());
+                }
+                if !damping.is_finite() || damping < 0.0 {
+                    return Err("spring damping must be non-negative".to_string());
+                }
+            }
+            Easing::Elastic { period } if !period.is_finite() || period <= 0.0 => {
+                return Err("elastic period must be positive".to_string());
+            }
+            _ => {}
+        }
+        Ok(self)
+    }
+
+    pub fn apply(&self, t: f32) -> f32 {
+        if t <= 0.0 {
+            return 0.0;
+        }
+        if t >= 1.0 {
+            return 1.0;
+        }
+
+        match *self {
+            Easing::Linear => t,
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::CubicBezier { x1, y1, x2, y2 } => {
+                cubic_bezier(x1.clamp(0.0, 1.0), y1, x2.clamp(0.0, 1.0), y2, t)
+            }
+            Easing::Spring { stiffness, damping } => {
+                // Corrected so the curve lands exactly on 1 at t = 1
+                let raw = |t: f32| spring(stiffness, damping, t);
+                raw(t) + t * (1.0 - raw(1.0))
+            }
+            Easing::Elastic { period } => {
+                let period = period.max(0.05);
+                let c = std::f32::consts::TAU / period;
+                2f32.powf(-10.0 * t) * ((t - period / 4.0) * c).sin() + 1.0
+            }
+        }
+    }
+}
+
+/// Unit step response of a damped oscillator with natural frequency
+/// `stiffness` (rad per unit time) and damping ratio `damping`.
+fn spring(stiffness: f32, damping: f32, t: f32) -> f32 {
+    let omega = stiffness.max(0.1);
+    let zeta = damping.max(0.0);
+
+    if zeta < 1.0 {
+        let omega_d = omega * (1.0 - zeta * zeta).sqrt();
+        let decay = (-zeta * omega * t).exp();
+        1.0 - decay * ((omega_d * t).cos() + (zeta * omega / omega_d) * (omega_d * t).sin())
+    } else if zeta == 1.0 {
+        1.0 - (-omega * t).exp() * (1.0 + omega * t)
+    } else {
+        let root = (zeta * zeta - 1.0).sqrt();
+        let r1 = -omega * (zeta - root);
+        let r2 = -omega * (zeta + root);
+        1.0 - (r2 * (r1 * t).exp() - r1 * (r2 * t).exp()) / (r2 - r1)
+    }
+}
+
+fn cubic_bezier(x1: f32, y1: f32, x2: f32, y2: f32, x: f32) -> f32 {
+    let bezier = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * u * u * t * a + 3.0 * u * t * t * b + t * t * t
+    };
+    let slope = |a: f32, b: f32, t: f32| {
+        let u = 1.0 - t;
+        3.0 * u * u * a + 6.0 * u * t * (b - a) + 3.0 * t * t * (1.0 - b)
+    };
+
+    // Newton first, bisection when the slope is too flat to trust
+    let mut t = x;
+    for _ in 0..8 {
+        let err = bezier(x1, x2, t) - x;
+        if err.abs() < 1e-6 {
+            return bezier(y1, y2, t);
+        }
+        let d = slope(x1, x2, t);
+        if d.abs() < 1e-6 {
+            break;
+        }
+        t = (t - err / d).clamp(0.0, 1.0);
+    }
+
+    let (mut lo, mut hi) = (0.0f32, 1.0f32);
+    t = x;
+    for _ in 0..32 {
+        let value = bezier(x1, x2, t);
+        if (value - x).abs() < 1e-6 {
+            break;
+        }
+        if value < x {
+            lo = t;
+        } else {
+            hi = t;
+        }
+        t = (lo + hi) / 2.0;
+    }
+    bezier(y1, y2, t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn all_curves() -> Vec<Easing> {
+        [
+            "linear",
+            "quad-in",
+            "quad-out",
+            "quad-in-out",
+            "cubic-in",
+            "cubic-out",
+            "cubic-in-out",
+            "ease",
+            "spring",
+            "spring-bouncy",
+            "elastic",
+        ]
+        .iter()
+        .map(|name| Easing::from_name(name).unwrap())
+        .chain([
+            Easing::CubicBezier { x1: 0.42, y1: 0.0, x2: 0.58, y2: 1.0 },
+            Easing::Spring { stiffness: 8.0, damping: 1.6 },
+        ])
+        .collect()
+    }
+
+    fn assert_monotonic(easing: Easing) {
+        let mut last = easing.apply(0.0);
+        for i in 1..=200 {
+            let value = easing.apply(i as f32 / 200.0);
+            assert!(value >= last - 1e-5, "{:?} decreases at step {}", easing, i);
+            last = value;
+        }
+    }
+
+    #[test]
+    fn every_curve_hits_both_endpoints() {
+        for easing in all_curves() {
+            assert_eq!(easing.apply(0.0), 0.0, "{:?} at 0", easing);
+            assert_eq!(easing.apply(1.0), 1.0, "{:?} at 1", easing);
+            assert_eq!(easing.apply(-0.5), 0.0, "{:?} below 0", easing);
+            assert_eq!(easing.apply(1.5), 1.0, "{:?} above 1", easing);
+        }
+    }
+
+    #[test]
+    fn polynomial_curves_are_monotonic() {
+        let names = ["linear", "quad-in", "quad-out", "quad-in-out", "cubic-in", "cubic-out", "cubic-in-out"];
+        for name in names {
+            assert_monotonic(Easing::from_name(name).unwrap());
+        }
+    }
+
+    #[test]
+    fn cubic_bezier_is_monotonic_and_matches_linear_controls() {
+        assert_monotonic(Easing::from_name("ease").unwrap());
+        assert_monotonic(Easing::CubicBezier { x1: 0.42, y1: 0.0, x2: 0.58, y2: 1.0 });
+
+        let linear = Easing::CubicBezier { x1: 0.0, y1: 0.0, x2: 1.0, y2: 1.0 };
+        for i in 0..=10 {
+            let t = i as f32 / 10.0;
+            assert!((linear.apply(t) - t).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn damped_springs_are_monotonic() {
+        assert_monotonic(Easing::from_name("spring").unwrap());
+        assert_monotonic(Easing::Spring { stiffness: 8.0, damping: 1.6 });
+    }
+
+    #[test]
+    fn bouncy_spring_and_elastic_overshoot() {
+        let overshoots = |easing: Easing| (1..100).any(|i| easing.apply(i as f32 / 100.0) > 1.0);
+        assert!(overshoots(Easing::from_name("spring-bouncy").unwrap()));
+        assert!(overshoots(Easing::from_name("elastic").unwrap()));
+    }
+
+    #[test]
+    fn cubic_in_out_is_symmetric() {
+        let easing = Easing::CubicInOut;
+        assert!((easing.apply(0.5) - 0.5).abs() < 1e-6);
+        for i in 0..=10 {
+            let t = i as f32 / 10.0;
+            assert!((easing.apply(t) + easing.apply(1.0 - t) - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn unknown_names_are_rejected() {
+        assert_eq!(Easing::from_name("bounce"), None);
+    }
+
+    #[test]
+    fn parse_accepts_presets_bezier_and_json_specs() {
+        assert_eq!(Easing::parse("cubic-out"), Ok(Easing::CubicOut));
+        assert_eq!(
+            Easing::parse("cubic-bezier(0.42, 0, 0.58, 1)"),
+            Ok(Easing::CubicBezier { x1: 0.42, y1: 0.0, x2: 0.58, y2: 1.0 })
+        );
+        assert_eq!(
+            Easing::parse(r#"{"type": "spring", "stiffness": 12, "damping": 0.5}"#),
+            Ok(Easing::Spring { stiffness: 12.0, damping: 0.5 })
+        );
+        assert_eq!(
+            Easing::parse(r#"{"type": "elastic", "period": 0.4}"#),
+            Ok(Easing::Elastic { period: 0.4 })
+        );
+    }
+
+    #[test]
+    fn parse_rejects_bad_specs() {
+        for spec in [
+            "bounce",
+            "cubic-bezier(0.4, 0, 1)",
+            "cubic-bezier(1.5, 0, 0.5, 1)",
+            "cubic-bezier(a, b, c, d)",
+            r#"{"type": "spring", "stiffness": 0, "damping": 1}"#,
+            r#"{"type": "spring", "stiffness": 10, "damping": -1}"#,
+            r#"{"type": "elastic", "period": 0}"#,
+            r#"{"type": "wobble"}"#,
+        ] {
+            assert!(Easing::parse(spec).is_err(), "{} should be rejected", spec);
+        }
+    }
+}
@@ -0,0 +1,281 @@
+// src/wasm/src/emoji.rs
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::rng::Rng;
+use crate::style::verbatim_spans;
+use crate::truncate;
+
+/// Palettes, keyword emoji and per-mood density used until
+/// `load_emoji_rules` replaces them.
+const DEFAULT_RULES: &str = include_str!("../data/emoji_rules.json");
+
+/// Emoji and kaomoji that fit one emotion of the reply.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct Palette {
+    pub emoji: Vec<String>,
+    pub kaomoji: Vec<String>,
+}
+
+/// Emoji placed right after one of `words` ("makan 🍜") instead of at the
+/// end of the sentence.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct KeywordEmoji {
+    pub words: Vec<String>,
+    pub emoji: Vec<String>,
+}
+
+/// How much MIRA decorates in one mood. `chance` is rolled per sentence.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(default)]
+pub struct MoodEmoji {
+    pub chance: f64,
+    pub kaomoji_chance: f64,
+    pub max_per_reply: usize,
+}
+
+impl Default for MoodEmoji {
+    fn default() -> Self {
+        Self {
+            chance: 0.0,
+            kaomoji_chance: 0.0,
+            max_per_reply: 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Placement {
+    SentenceEnd,
+    Keyword,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct PlacedEmoji {
+    pub emoji: String,
+    pub kaomoji: bool,
+    pub placement: Placement,
+    pub keyword: Option<String>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EmojiOutput {
+    pub text: String,
+    pub placed: Vec<PlacedEmoji>,
+    /// Why nothing was added to a reply that is no place for emoji.
+    pub skipped: Option<String>,
+}
+
+impl EmojiOutput {
+    pub fn skipped(text: &str, reason: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            placed: Vec::new(),
+            skipped: Some(reason.to_string()),
+        }
+    }
+}
+
+/// Roughly: pictographs, symbols and dingbats. Enough to notice emoji a
+/// reply already has.
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F300..=0x1FAFF | 0x2600..=0x27BF)
+}
+
+/// Byte end of the first whole-word occurrence of `word` in `text`,
+/// ignoring ASCII case.
+fn find_word(text: &str, word: &str) -> Option<usize> {
+    let is_word = |c: Option<char>| c.is_some_and(char::is_alphanumeric);
+    text.char_indices().find_map(|(at, _)| {
+        let end = at + word.len();
+        let matches = text.get(at..end).is_some_and(|s| s.eq_ignore_ascii_case(word));
+        let bounded = !is_word(text[..at].chars().next_back()) && !is_word(text.get(end..)?.chars().next());
+        (matches && bounded).then_some(end)
+    })
+}
+
+// ========== RULES ==========
+/// Emoji palettes per reply emotion, keyword emoji and per-mood density.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EmojiRules {
+    /// Must include "neutral", the fallback for emotions without one.
+    pub emotions: BTreeMap<String, Palette>,
+    #[serde(default)]
+    pub keywords: Vec<KeywordEmoji>,
+    /// Moods without an entry use chill's.
+    pub moods: BTreeMap<String, MoodEmoji>,
+    /// Reply emotions that get no emoji at all.
+    #[serde(default)]
+    pub serious_emotions: Vec<String>,
+    /// At most one emoji per this many characters of reply.
+    pub chars_per_emoji: usize,
+    /// An emoji is not used again until this many others have been.
+    pub repeat_window: usize,
+}
+
+impl Default for EmojiRules {
+    fn default() -> Self {
+        Self::from_json(DEFAULT_RULES).unwrap()
+    }
+}
+
+impl EmojiRules {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut rules: EmojiRules = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if !rules.emotions.contains_key("neutral") {
+            return Err("a 'neutral' palette is required as the fallback".to_string());
+        }
+        if !rules.moods.contains_key("chill") {
+            return Err("a 'chill' mood is required as the fallback".to_string());
+        }
+        for (mood, style) in &rules.moods {
+            if ![style.chance, style.kaomoji_chance].iter().all(|c| (0.0..=1.0).contains(c)) {
+                return Err(format!("mood '{}' has a chance outside 0-1", mood));
+            }
+        }
+        if rules.chars_per_emoji == 0 {
+            return Err("chars_per_emoji must be positive".to_string());
+        }
+        for keyword in &mut rules.keywords {
+            keyword.words = keyword.words.iter().map(|w| w.trim().to_lowercase()).collect();
+            keyword.words.retain(|w| !w.is_empty());
+        }
+        Ok(rules)
+    }
+
+    /// The density for `mood`, chill's when it has none.
+    pub fn mood(&self, mood: &str) -> MoodEmoji {
+        *self.moods.get(mood).unwrap_or(&self.moods["chill"])
+    }
+
+    /// Draw from `options`, skipping anything used within the window.
+    fn fresh<'a>(&self, options: &'a [String], recent: &[String], rng: &mut Rng) -> Option<&'a String> {
+        let fresh: Vec<&String> = options.iter().filter(|e| !recent.contains(e)).collect();
+        rng.pick(&fresh).copied()
+    }
+
+    /// Add emoji to `text` for a reply that reads as `emotion`, at the
+    /// density of `style` (usually `mood(..)`). Each prose sentence rolls
+    /// once: a keyword in it gets its emoji right after, otherwise one from
+    /// the emotion's palette goes at the end. `recent` holds what earlier
+    /// replies used, newest last.
+    pub fn inject(
+        &self,
+        text: &str,
+        emotion: &str,
+        style: MoodEmoji,
+        recent: &mut Vec<String>,
+        rng: &mut Rng,
+    ) -> EmojiOutput {
+        if self.serious_emotions.iter().any(|e| e == emotion) {
+            return EmojiOutput::skipped(text, "serious_emotion");
+        }
+        let palette = self.emotions.get(emotion).unwrap_or(&self.emotions["neutral"]);
+
+        let density = (text.chars().count() / self.chars_per_emoji).max(1);
+        let already = text.chars().filter(|&c| is_emoji(c)).count();
+        let mut budget = density.min(style.max_per_reply).saturating_sub(already);
+
+        let verbatim = verbatim_spans(text);
+        let inside_verbatim = |at: usize| verbatim.iter().any(|s| s.start < at && at < s.end);
+        let mut edits: Vec<(usize, String)> = Vec::new();
+        let mut placed = Vec::new();
+
+        for range in truncate::sentence_ranges(text) {
+            if budget == 0 {
+                break;
+            }
+            let sentence = &text[range.clone()];
+            if sentence.chars().any(is_emoji) || rng.next_f64() >= style.chance {
+                continue;
+            }
+
+            let keyword = self.keywords.iter().find_map(|k| {
+                k.words.iter().find_map(|w| {
+                    let end = find_word(sentence, w)?;
+                    (!inside_verbatim(range.start + end)).then_some((k, w, end))
+                })
+            });
+            let choice = match keyword {
+                Some((k, word, end)) => self
+                    .fresh(&k.emoji, recent, rng)
+                    .map(|e| (e, false, Placement::Keyword, Some(word.clone()), range.start + end)),
+                None => {
+                    let kaomoji = !palette.kaomoji.is_empty() && rng.next_f64() < style.kaomoji_chance;
+                    let options = if kaomoji { &palette.kaomoji } else { &palette.emoji };
+                    self.fresh(options, recent, rng)
+                        .map(|e| (e, kaomoji, Placement::SentenceEnd, None, range.end))
+                }
+            };
+            let Some((emoji, kaomoji, placement, keyword, at)) = choice else {
+                continue;
+            };
+            if inside_verbatim(at) {
+                continue;
+            }
+
+            edits.push((at, format!(" {}", emoji)));
+            recent.push(emoji.clone());
+            if recent.len() > self.repeat_window {
+                recent.drain(..recent.len() - self.repeat_window);
+            }
+            placed.push(PlacedEmoji {
+                emoji: emoji.clone(),
+                kaomoji,
+                placement,
+                keyword,
+            });
+            budget -= 1;
+        }
+
+        let mut text = text.to_string();
+        for (at, emoji) in edits.into_iter().rev() {
+            text.insert_str(at, &emoji);
+        }
+        EmojiOutput {
+            text,
+            placed,
+            skipped: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style(chance: f64) -> MoodEmoji {
+        MoodEmoji {
+            chance,
+            kaomoji_chance: 0.0,
+            max_per_reply: 1,
+        }
+    }
+
+    fn inject(text: &str, emotion: &str, style: MoodEmoji) -> EmojiOutput {
+        EmojiRules::default().inject(text, emotion, style, &mut Vec::new(), &mut Rng::new(1))
+    }
+
+    #[test]
+    fn chance_decides_whether_a_sentence_gets_emoji() {
+        assert!(inject("Oke, aku ngerti.", "happy", style(0.0)).placed.is_empty());
+        assert_eq!(inject("Oke, aku ngerti.", "happy", style(1.0)).placed.len(), 1);
+    }
+
+    #[test]
+    fn keyword_emoji_goes_right_after_the_word() {
+        let output = inject("Jangan lupa makan ya.", "neutral", style(1.0));
+        assert_eq!(output.placed[0].placement, Placement::Keyword);
+        let expected = ["Jangan lupa makan 🍜 ya.", "Jangan lupa makan 🍚 ya."];
+        assert!(expected.contains(&output.text.as_str()), "{}", output.text);
+    }
+
+    #[test]
+    fn serious_emotions_get_none() {
+        let output = inject("Aku di sini kok.", "sad", style(1.0));
+        assert_eq!(output.skipped.as_deref(), Some("serious_emotion"));
+        assert_eq!(output.text, "Aku di sini kok.");
+    }
+}
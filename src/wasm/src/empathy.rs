@@ -0,0 +1,174 @@
+// src/wasm/src/empathy.rs
+use serde::Serialize;
+
+use crate::ExpressionDetection;
+
+/// What MIRA's face does in answer to how the user feels. The user's
+/// sadness shouldn't make her look sad, it should make her look concerned.
+const EMPATHY: [(&str, &str); 6] = [
+    ("neutral", "neutral"),
+    ("happy", "happy"),
+    ("sad", "concerned"),
+    ("surprised", "surprised"),
+    ("curious", "curious"),
+    ("confused", "soft_smile"),
+];
+
+/// (user emotion, reply emotion, shown instead): reply faces that would
+/// clash with the user's state are toned down.
+const TONE_DOWN: [(&str, &str, &str); 3] = [
+    ("sad", "happy", "soft_smile"),
+    ("sad", "surprised", "concerned"),
+    ("confused", "happy", "soft_smile"),
+];
+
+/// How strongly MIRA mirrors an emotion she is only reacting to.
+const EMPATHY_INTENSITY: f32 = 0.8;
+
+pub fn empathy_for(user_emotion: &str) -> &'static str {
+    EMPATHY
+        .iter()
+        .find(|(user, _)| *user == user_emotion)
+        .map_or("neutral", |(_, mira)| mira)
+}
+
+fn tone_down<'a>(user_emotion: &str, reply_emotion: &'a str) -> &'a str {
+    TONE_DOWN
+        .iter()
+        .find(|(user, reply, _)| *user == user_emotion && *reply == reply_emotion)
+        .map_or(reply_emotion, |(_, _, shown)| shown)
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DisplaySource {
+    Reply,   // MIRA's own words set the face
+    Empathy, // the reply was flat; she reacts to the user instead
+    Neutral,
+}
+
+#[derive(Serialize)]
+pub struct DisplayedExpression<'a> {
+    pub expression: String,
+    pub secondary: String,
+    pub emotion: String,
+    pub secondary_emotion: String,
+    pub source: DisplaySource,
+    pub intensity: f32,
+    pub confidence: f32,
+    pub user: Option<&'a ExpressionDetection>,
+    pub reply: Option<&'a ExpressionDetection>,
+}
+
+/// Emotion labels for the face to show; the caller resolves them to
+/// expression IDs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayDecision {
+    pub emotion: String,
+    pub secondary_emotion: String,
+    pub source: DisplaySource,
+    pub intensity: f32,
+    pub confidence: f32,
+}
+
+/// Combine the two channels. A reply that clearly shows an emotion wins
+/// (toned down if it clashes with the user); otherwise the empathy table
+/// answers the user's emotion.
+pub fn resolve(
+    user: Option<&ExpressionDetection>,
+    reply: Option<&ExpressionDetection>,
+) -> DisplayDecision {
+    let user_emotion = user.map_or("neutral", |u| u.primary_emotion.as_str());
+    let empathy = empathy_for(user_emotion);
+
+    if let Some(reply) = reply.filter(|r| r.primary_emotion != "neutral") {
+        let shown = tone_down(user_emotion, &reply.primary_emotion);
+        let secondary = if empathy != shown {
+            empathy
+        } else {
+            reply.secondary_emotion.as_str()
+        };
+        return DisplayDecision {
+            emotion: shown.to_string(),
+            secondary_emotion: secondary.to_string(),
+            source: DisplaySource::Reply,
+            intensity: reply.intensity,
+            confidence: reply.confidence,
+        };
+    }
+
+    match user.filter(|_| empathy != "neutral") {
+        Some(user) => DisplayDecision {
+            emotion: empathy.to_string(),
+            secondary_emotion: "neutral".to_string(),
+            source: DisplaySource::Empathy,
+            intensity: user.intensity * EMPATHY_INTENSITY,
+            confidence: user.confidence,
+        },
+        None => DisplayDecision {
+            emotion: "neutral".to_string(),
+            secondary_emotion: "neutral".to_string(),
+            source: DisplaySource::Neutral,
+            intensity: 0.0,
+            confidence: reply.or(user).map_or(0.0, |d| d.confidence),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evidence::ExpressionEvidence;
+
+    fn detection(emotion: &str, secondary: &str) -> ExpressionDetection {
+        ExpressionDetection {
+            primary: String::new(),
+            secondary: String::new(),
+            primary_emotion: emotion.to_string(),
+            secondary_emotion: secondary.to_string(),
+            intensity: 0.5,
+            confidence: 0.7,
+            evidence: ExpressionEvidence {
+                hits: 1,
+                scores: Default::default(),
+                agreement: 1.0,
+                margin: 1.0,
+                negation: false,
+                sarcasm: false,
+            },
+        }
+    }
+
+    #[test]
+    fn clear_reply_wins_but_is_toned_down_for_a_sad_user() {
+        let sad = detection("sad", "neutral");
+        let decision = resolve(Some(&sad), Some(&detection("happy", "neutral")));
+        assert_eq!(decision.source, DisplaySource::Reply);
+        assert_eq!(decision.emotion, "soft_smile");
+        assert_eq!(decision.secondary_emotion, "concerned");
+
+        let cheerful = detection("happy", "surprised");
+        let decision = resolve(Some(&cheerful), Some(&detection("happy", "curious")));
+        assert_eq!((decision.emotion.as_str(), decision.secondary_emotion.as_str()), ("happy", "curious"));
+    }
+
+    #[test]
+    fn flat_reply_answers_the_user_with_empathy() {
+        let sad = detection("sad", "neutral");
+        let decision = resolve(Some(&sad), Some(&detection("neutral", "neutral")));
+        assert_eq!(decision.source, DisplaySource::Empathy);
+        assert_eq!(decision.emotion, "concerned");
+        assert_eq!(decision.intensity, 0.5 * EMPATHY_INTENSITY);
+    }
+
+    #[test]
+    fn nothing_to_show_is_neutral() {
+        let decision = resolve(None, None);
+        assert_eq!((decision.source, decision.emotion.as_str()), (DisplaySource::Neutral, "neutral"));
+        assert_eq!(decision.confidence, 0.0);
+
+        let flat = detection("neutral", "neutral");
+        assert_eq!(resolve(Some(&flat), Some(&flat)).confidence, 0.7);
+        assert_eq!(empathy_for("bored"), "neutral");
+    }
+}
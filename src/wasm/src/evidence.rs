@@ -0,0 +1,268 @@
+// src/wasm/src/evidence.rs
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::lexicon::Lexicon;
+
+/// Below this the detection is too thin to show anything but neutral.
+pub const MIN_CONFIDENCE: f32 = 0.35;
+
+/// Words whose lexicon category doesn't say which face to make.
+const EMOTION_OVERRIDES: [(&str, &str); 10] = [
+    ("wow", "surprised"),
+    ("gila", "surprised"),
+    ("astaga", "surprised"),
+    ("buset", "surprised"),
+    ("jir", "surprised"),
+    ("wah", "surprised"),
+    ("gokil", "surprised"),
+    ("bangsat", "surprised"),
+    ("bingung", "confused"),
+    ("huh", "confused"),
+];
+
+/// Whose text is being read. User text says how the user feels; MIRA's
+/// replies are read with the reply dictionary first, for the face she makes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Channel {
+    User,
+    Reply,
+}
+
+/// Emotion label a lexicon hit counts toward, before negation.
+fn emotion_for(word: &str, category: &str, score: i32) -> &'static str {
+    match category {
+        "reply_concern" => return "concerned",
+        "reply_warm" => return "soft_smile",
+        "reply_cheer" => return "happy",
+        "reply_curious" => return "curious",
+        "reply_surprise" => return "surprised",
+        _ => {}
+    }
+    if let Some((_, emotion)) = EMOTION_OVERRIDES.iter().find(|(w, _)| *w == word) {
+        return emotion;
+    }
+    match category {
+        "curious" => "curious",
+        "context_expression" if score == 0 => "curious",
+        _ if score > 0 => "happy",
+        _ if score < 0 => "sad",
+        _ => "neutral",
+    }
+}
+
+/// "Not happy" reads as sad and "not sad" as mildly happy; surprise and
+/// curiosity don't flip into anything clear.
+fn negated(emotion: &'static str) -> &'static str {
+    match emotion {
+        "happy" => "sad",
+        "sad" => "happy",
+        _ => "neutral",
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExpressionEvidence {
+    pub hits: usize,
+    pub scores: BTreeMap<String, f32>,
+    pub agreement: f32, // share of all evidence behind the top emotion
+    pub margin: f32,    // (top - second) / top
+    pub negation: bool,
+    pub sarcasm: bool,
+}
+
+/// Ranked emotions plus the evidence behind them.
+pub struct EmotionRanking {
+    pub ranked: Vec<(String, f32)>, // strongest first, only labels with evidence
+    pub confidence: f32,
+    pub evidence: ExpressionEvidence,
+}
+
+impl EmotionRanking {
+    /// Tally lexicon hits per emotion. Each hit weighs its score magnitude
+    /// times any intensifier, scaled by the word's intensity level.
+    pub fn gather(lexicon: &Lexicon, text: &str, channel: Channel) -> Self {
+        let lookup = |phrase: &str| match channel {
+            Channel::User => lexicon.sentiment_dict.get(phrase),
+            Channel::Reply => lexicon
+                .reply_dict
+                .get(phrase)
+                .or_else(|| lexicon.sentiment_dict.get(phrase)),
+        };
+
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        let mut scores: BTreeMap<String, f32> = BTreeMap::new();
+        let mut hits = 0;
+        let mut negation = false;
+        let mut negate_next = false;
+        let mut multiplier = 1.0f32;
+        let mut last_hit: Option<(&str, f32)> = None;
+
+        let mut i = 0;
+        while i < words.len() {
+            // Two-word phrases ("terima kasih", "gimana sih") win over single words
+            let pair = words.get(i + 1).map(|next| format!("{} {}", words[i], next));
+            let (phrase, len) = match pair {
+                Some(p) if lookup(&p).is_some() || lexicon.intensifier_words.contains_key(&p) =>
+                {
+                    (p, 2)
+                }
+                _ => (words[i].to_string(), 1),
+            };
+            i += len;
+
+            if lexicon.negation_words.contains(&phrase) {
+                negate_next = true;
+                last_hit = None;
+                continue;
+            }
+            if let Some(&m) = lexicon.intensifier_words.get(&phrase) {
+                // "senang banget" boosts the word before it, "sangat senang"
+                // the word after
+                match last_hit.take() {
+                    Some((emotion, weight)) => {
+                        *scores.entry(emotion.to_string()).or_insert(0.0) += weight * (m - 1.0);
+                    }
+                    None => multiplier = m,
+                }
+                continue;
+            }
+            let Some(entry) = lookup(&phrase) else {
+                last_hit = None;
+                continue;
+            };
+
+            let mut emotion = emotion_for(&phrase, &entry.category, entry.base_score);
+            let mut weight = (entry.base_score.abs().max(1) as f32)
+                * multiplier
+                * (entry.intensity_level as f32 / 5.0);
+            if negate_next {
+                emotion = negated(emotion);
+                weight *= 0.6;
+                negation = true;
+                negate_next = false;
+            }
+            multiplier = 1.0;
+
+            hits += 1;
+            *scores.entry(emotion.to_string()).or_insert(0.0) += weight;
+            last_hit = Some((emotion, weight));
+        }
+
+        let polarity = |e: &str| scores.get(e).copied().unwrap_or(0.0);
+        let sarcasm = lexicon.sarcasm_markers.iter().any(|m| lower.contains(m.as_str()))
+            || (polarity("happy") > 0.0 && polarity("sad") > 0.0 && lower.contains("..."));
+
+        let mut ranked: Vec<(String, f32)> = scores.iter().map(|(e, s)| (e.clone(), *s)).collect();
+        // Stable on ties: BTreeMap order breaks them alphabetically
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        let total: f32 = ranked.iter().map(|(_, s)| s).sum();
+        let top = ranked.first().map_or(0.0, |(_, s)| *s);
+        let second = ranked.get(1).map_or(0.0, |(_, s)| *s);
+        let (agreement, margin) = if top > 0.0 {
+            (top / total, (top - second) / top)
+        } else {
+            (0.0, 0.0)
+        };
+
+        // Enough evidence, pointing the same way, with a clear winner
+        let coverage = 1.0 - (-total).exp();
+        let mut confidence = coverage * (0.4 + 0.3 * agreement + 0.3 * margin);
+        if negation {
+            confidence *= 0.8;
+        }
+        if sarcasm {
+            confidence *= 0.5;
+        }
+
+        EmotionRanking {
+            ranked,
+            confidence: confidence.clamp(0.0, 1.0),
+            evidence: ExpressionEvidence {
+                hits,
+                scores,
+                agreement,
+                margin,
+                negation,
+                sarcasm,
+            },
+        }
+    }
+
+    /// (primary, secondary) labels; neutral leads when confidence is too low,
+    /// with the weak guess kept as secondary.
+    pub fn labels(&self) -> (&str, &str) {
+        let label = |i: usize| self.ranked.get(i).map_or("neutral", |(e, _)| e.as_str());
+        if self.confidence < MIN_CONFIDENCE {
+            ("neutral", label(0))
+        } else {
+            (label(0), label(1))
+        }
+    }
+
+    /// Strength of the top emotion, 0-1.
+    pub fn intensity(&self) -> f32 {
+        self.ranked.first().map_or(0.0, |(_, s)| (s / 5.0).min(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gather(text: &str) -> EmotionRanking {
+        EmotionRanking::gather(&Lexicon::new(), text, Channel::User)
+    }
+
+    #[test]
+    fn agreeing_evidence_raises_confidence() {
+        let one = gather("aku senang");
+        let many = gather("aku senang, bahagia, gembira");
+        assert_eq!(one.labels().0, "happy");
+        assert!(many.confidence > one.confidence);
+        assert_eq!(many.evidence.hits, 3);
+        assert_eq!(many.evidence.agreement, 1.0);
+    }
+
+    #[test]
+    fn mixed_feelings_keep_a_secondary() {
+        let mixed = gather("senang banget tapi agak sedih");
+        assert_eq!(mixed.labels(), ("happy", "sad"));
+        assert!(mixed.evidence.margin < 1.0);
+        assert!(mixed.confidence < gather("senang banget").confidence);
+    }
+
+    #[test]
+    fn negation_flips_and_sarcasm_discounts() {
+        let negated = gather("aku tidak senang");
+        assert_eq!(negated.ranked[0].0, "sad");
+        assert!(negated.evidence.negation);
+
+        let sarcastic = gather("hebat ya, aku senang");
+        assert!(sarcastic.evidence.sarcasm);
+        assert!(sarcastic.confidence < gather("aku senang").confidence);
+
+        assert_eq!(gather("wow").ranked[0].0, "surprised");
+    }
+
+    #[test]
+    fn thin_evidence_falls_back_to_neutral() {
+        let nothing = gather("meja kursi");
+        assert_eq!((nothing.confidence, nothing.labels()), (0.0, ("neutral", "neutral")));
+
+        let weak = EmotionRanking {
+            ranked: vec![("happy".to_string(), 0.2)],
+            confidence: MIN_CONFIDENCE - 0.01,
+            evidence: nothing.evidence,
+        };
+        assert_eq!(weak.labels(), ("neutral", "happy"));
+        assert_eq!(weak.intensity(), 0.04);
+    }
+}
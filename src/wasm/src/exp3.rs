@@ -0,0 +1,160 @@
+// src/wasm/src/exp3.rs
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::expressions::RegistryError;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum BlendMode {
+    #[default]
+    Add,
+    Multiply,
+    Overwrite,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ExpressionParameter {
+    pub id: String,
+    pub value: f32,
+    #[serde(default)]
+    pub blend: BlendMode,
+}
+
+/// Parsed `*.exp3.json`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Exp3 {
+    #[serde(default = "default_fade")]
+    pub fade_in_time: f32,
+    #[serde(default = "default_fade")]
+    pub fade_out_time: f32,
+    #[serde(default)]
+    pub parameters: Vec<ExpressionParameter>,
+}
+
+fn default_fade() -> f32 {
+    1.0
+}
+
+impl Exp3 {
+    pub fn from_json(id: &str, json: &str) -> Result<Self, RegistryError> {
+        let exp: Exp3 = serde_json::from_str(json)
+            .map_err(|e| RegistryError::Malformed(format!("{}.exp3.json: {}", id, e)))?;
+
+        if let Some(p) = exp.parameters.iter().find(|p| !p.value.is_finite()) {
+            return Err(RegistryError::Malformed(format!(
+                "{}.exp3.json: parameter '{}' is not a finite number",
+                id, p.id
+            )));
+        }
+        Ok(exp)
+    }
+}
+
+/// Per-parameter accumulator, following the Cubism SDK:
+/// `value = (overwrite + add) * multiply`.
+struct Accumulator {
+    add: f32,
+    multiply: f32,
+    overwrite: f32,
+}
+
+/// Blend weighted expressions into absolute parameter values.
+///
+/// `layers` is applied in order (Overwrite is order dependent). Parameters no
+/// expression touches are left out so the renderer keeps its own value.
+/// `base` holds the model's resting values; missing entries count as 0.
+pub fn blend(layers: &[(&Exp3, f32)], base: &HashMap<String, f32>) -> BTreeMap<String, f32> {
+    let mut acc: BTreeMap<String, Accumulator> = BTreeMap::new();
+
+    for (exp, weight) in layers {
+        let w = weight.clamp(0.0, 1.0);
+        if w == 0.0 {
+            continue;
+        }
+
+        for param in &exp.parameters {
+            let slot = acc.entry(param.id.clone()).or_insert_with(|| Accumulator {
+                add: 0.0,
+                multiply: 1.0,
+                overwrite: base.get(&param.id).copied().unwrap_or(0.0),
+            });
+
+            match param.blend {
+                BlendMode::Add => slot.add += param.value * w,
+                BlendMode::Multiply => slot.multiply *= 1.0 + (param.value - 1.0) * w,
+                BlendMode::Overwrite => slot.overwrite += (param.value - slot.overwrite) * w,
+            }
+        }
+    }
+
+    acc.into_iter()
+        .map(|(id, a)| (id, (a.overwrite + a.add) * a.multiply))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exp(params: &[(&str, f32, BlendMode)]) -> Exp3 {
+        Exp3 {
+            fade_in_time: 1.0,
+            fade_out_time: 1.0,
+            parameters: params
+                .iter()
+                .map(|&(id, value, blend)| ExpressionParameter {
+                    id: id.to_string(),
+                    value,
+                    blend,
+                })
+                .collect(),
+        }
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn add_and_multiply_scale_with_the_weight() {
+        let smile = exp(&[("Form", 0.5, BlendMode::Add), ("Open", 2.0, BlendMode::Multiply)]);
+        let grin = exp(&[("Form", 0.3, BlendMode::Add), ("Open", 1.0, BlendMode::Add)]);
+        let values = blend(&[(&smile, 1.0), (&grin, 0.5)], &HashMap::new());
+        assert!(close(values["Form"], 0.65));
+        // (overwrite 0 + add 0.5) * multiply 2
+        assert!(close(values["Open"], 1.0));
+
+        let half = blend(&[(&smile, 0.5), (&grin, 1.0)], &HashMap::new());
+        assert!(close(half["Open"], 1.5));
+    }
+
+    #[test]
+    fn overwrite_starts_from_the_base_and_follows_layer_order() {
+        let base = HashMap::from([("Brow".to_string(), 0.2)]);
+        let up = exp(&[("Brow", 1.0, BlendMode::Overwrite)]);
+        let down = exp(&[("Brow", 0.0, BlendMode::Overwrite)]);
+
+        assert!(close(blend(&[(&up, 0.5)], &base)["Brow"], 0.6));
+        assert!(close(blend(&[(&up, 1.0), (&down, 0.5)], &base)["Brow"], 0.5));
+        assert!(close(blend(&[(&down, 0.5), (&up, 1.0)], &base)["Brow"], 1.0));
+    }
+
+    #[test]
+    fn weights_are_clamped_and_zero_layers_skipped() {
+        let smile = exp(&[("Form", 0.5, BlendMode::Add)]);
+        assert!(close(blend(&[(&smile, 3.0)], &HashMap::new())["Form"], 0.5));
+        assert!(blend(&[(&smile, 0.0)], &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn parses_with_defaults_and_rejects_bad_values() {
+        let parsed = Exp3::from_json("smile", r#"{"Parameters": [{"Id": "Form", "Value": 0.5}]}"#).unwrap();
+        assert_eq!(parsed, exp(&[("Form", 0.5, BlendMode::Add)]));
+        assert!(matches!(
+            Exp3::from_json("smile", r#"{"Parameters": [{"Id": "Form"}]}"#),
+            Err(RegistryError::Malformed(_))
+        ));
+    }
+}
@@ -0,0 +1,455 @@
+// src/wasm/src/expressions.rs
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+use crate::exp3::{self, Exp3};
+use crate::motions::MotionTable;
+use crate::MOODS;
+
+/// Emotion labels the core decides between. A mapping file must bind each of
+/// these to an expression of the model.
+pub const CORE_EMOTIONS: [&str; 4] = ["neutral", "happy", "sad", "surprised"];
+
+/// Optional labels and the core label they borrow when not mapped.
+const EMOTION_ALIASES: [(&str, &str); 4] = [
+    ("curious", "surprised"),
+    ("confused", "surprised"),
+    ("concerned", "sad"),
+    ("soft_smile", "happy"),
+];
+
+/// Mood -> emotion used when the mapping file has no `moods` section.
+const DEFAULT_MOOD_EMOTIONS: [(&str, &str); 3] =
+    [("chill", "neutral"), ("playful", "happy"), ("reflective", "sad")];
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ExpressionEntry {
+    pub id: String,
+    pub file: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryError {
+    Malformed(String),
+    NoExpressions,
+    DuplicateId(String),
+    UnknownId { label: String, id: String },
+    MissingEmotion(String),
+    UnknownMood(String),
+    UnknownLabel(String),
+    NoParameters(String),
+    UnknownMotion { group: String, index: u32 },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RegistryError::Malformed(msg) => write!(f, "malformed expression config: {}", msg),
+            RegistryError::NoExpressions => write!(f, "model3.json declares no expressions"),
+            RegistryError::DuplicateId(id) => write!(f, "expression '{}' is declared twice", id),
+            RegistryError::UnknownId { label, id } => write!(
+                f,
+                "'{}' is mapped to expression '{}', which the model doesn't have",
+                label, id
+            ),
+            RegistryError::MissingEmotion(label) => {
+                write!(f, "mapping has no expression for emotion '{}'", label)
+            }
+            RegistryError::UnknownMood(mood) => write!(f, "unknown mood '{}'", mood),
+            RegistryError::UnknownLabel(label) => write!(f, "unknown emotion '{}'", label),
+            RegistryError::NoParameters(id) => {
+                write!(f, "no exp3.json parameters loaded for expression '{}'", id)
+            }
+            RegistryError::UnknownMotion { group, index } => {
+                write!(f, "model has no motion {}[{}]", group, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for RegistryError {}
+
+// ========== FILE FORMATS ==========
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Model3 {
+    file_references: Model3Files,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Model3Files {
+    #[serde(default)]
+    expressions: Vec<Model3Expression>,
+    motions: Option<HashMap<String, Vec<serde_json::Value>>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Model3Expression {
+    name: String,
+    file: String,
+}
+
+/// `{ "emotions": { "happy": "f02", ... }, "moods": { "playful": "f02", ... } }`
+#[derive(Deserialize)]
+struct ExpressionMapping {
+    emotions: HashMap<String, String>,
+    #[serde(default)]
+    moods: HashMap<String, String>,
+}
+
+// ========== REGISTRY ==========
+#[derive(Clone, Debug)]
+pub struct ExpressionRegistry {
+    expressions: Vec<ExpressionEntry>,
+    emotions: BTreeMap<String, String>,
+    moods: BTreeMap<String, String>,
+    parameters: HashMap<String, Exp3>,
+    base_values: HashMap<String, f32>,
+    motions: MotionTable,
+    motion_groups: Option<HashMap<String, usize>>, // group -> motion count, when known
+}
+
+#[derive(Serialize)]
+pub struct RegistryInfo<'a> {
+    pub expressions: &'a [ExpressionEntry],
+    pub emotions: &'a BTreeMap<String, String>,
+    pub moods: &'a BTreeMap<String, String>,
+}
+
+impl ExpressionRegistry {
+    /// Built-in mapping for the bundled Shizuku model (f01-f04).
+    pub fn shizuku() -> Self {
+        let expressions = ["f01", "f02", "f03", "f04"]
+            .iter()
+            .map(|id| ExpressionEntry {
+                id: id.to_string(),
+                file: None,
+            })
+            .collect();
+        let emotions = CORE_EMOTIONS
+            .iter()
+            .zip(["f01", "f02", "f03", "f04"])
+            .map(|(label, id)| (label.to_string(), id.to_string()))
+            .collect();
+
+        let mut registry = Self::build(expressions, emotions, HashMap::new()).unwrap();
+        registry.motions = MotionTable::shizuku();
+        registry
+    }
+
+    /// Build from a model's `model3.json` and an emotion/mood mapping file.
+    pub fn from_json(model3_json: &str, mapping_json: &str) -> Result<Self, RegistryError> {
+        let model: Model3 = serde_json::from_str(model3_json)
+            .map_err(|e| RegistryError::Malformed(format!("model3.json: {}", e)))?;
+        let mapping: ExpressionMapping = serde_json::from_str(mapping_json)
+            .map_err(|e| RegistryError::Malformed(format!("mapping: {}", e)))?;
+
+        let expressions = model
+            .file_references
+            .expressions
+            .into_iter()
+            .map(|e| ExpressionEntry {
+                id: e.name,
+                file: Some(e.file),
+            })
+            .collect();
+
+        let mut registry = Self::build(expressions, mapping.emotions, mapping.moods)?;
+        registry.motion_groups = model
+            .file_references
+            .motions
+            .map(|groups| groups.into_iter().map(|(g, m)| (g, m.len())).collect());
+        Ok(registry)
+    }
+
+    fn build(
+        expressions: Vec<ExpressionEntry>,
+        emotions: HashMap<String, String>,
+        moods: HashMap<String, String>,
+    ) -> Result<Self, RegistryError> {
+        if expressions.is_empty() {
+            return Err(RegistryError::NoExpressions);
+        }
+
+        let mut ids = HashSet::new();
+        for entry in &expressions {
+            if !ids.insert(entry.id.as_str()) {
+                return Err(RegistryError::DuplicateId(entry.id.clone()));
+            }
+        }
+
+        let check = |label: &str, id: &str| {
+            if ids.contains(id) {
+                Ok(())
+            } else {
+                Err(RegistryError::UnknownId {
+                    label: label.to_string(),
+                    id: id.to_string(),
+                })
+            }
+        };
+
+        let mut emotions: BTreeMap<String, String> = emotions.into_iter().collect();
+        for (label, id) in &emotions {
+            check(label, id)?;
+        }
+        if let Some(missing) = CORE_EMOTIONS.iter().find(|l| !emotions.contains_key(**l)) {
+            return Err(RegistryError::MissingEmotion(missing.to_string()));
+        }
+        for (alias, fallback) in EMOTION_ALIASES {
+            if !emotions.contains_key(alias) {
+                let id = emotions[fallback].clone();
+                emotions.insert(alias.to_string(), id);
+            }
+        }
+
+        let mut moods: BTreeMap<String, String> = moods.into_iter().collect();
+        for (mood, id) in &moods {
+            if !MOODS.contains(&mood.as_str()) {
+                return Err(RegistryError::UnknownMood(mood.clone()));
+            }
+            check(mood, id)?;
+        }
+        for (mood, emotion) in DEFAULT_MOOD_EMOTIONS {
+            if !moods.contains_key(mood) {
+                let id = emotions[emotion].clone();
+                moods.insert(mood.to_string(), id);
+            }
+        }
+
+        Ok(Self {
+            expressions,
+            emotions,
+            moods,
+            parameters: HashMap::new(),
+            base_values: HashMap::new(),
+            motions: MotionTable::default(),
+            motion_groups: None,
+        })
+    }
+
+    /// Expression ID for one of the core emotion labels. Core labels are
+    /// guaranteed to be mapped once the registry is built.
+    pub fn emotion(&self, label: &str) -> &str {
+        &self.emotions[label]
+    }
+
+    pub fn for_emotion(&self, label: &str) -> Result<&str, RegistryError> {
+        self.emotions
+            .get(label)
+            .map(String::as_str)
+            .ok_or_else(|| RegistryError::UnknownLabel(label.to_string()))
+    }
+
+    pub fn for_mood(&self, mood: &str) -> Result<&str, RegistryError> {
+        self.moods
+            .get(mood)
+            .map(String::as_str)
+            .ok_or_else(|| RegistryError::UnknownMood(mood.to_string()))
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.expressions.iter().any(|e| e.id == id)
+    }
+
+    // ========== PARAMETERS ==========
+    /// Attach the parsed `exp3.json` of a registered expression.
+    pub fn load_parameters(&mut self, id: &str, exp3_json: &str) -> Result<(), RegistryError> {
+        if !self.contains(id) {
+            return Err(RegistryError::UnknownId {
+                label: "exp3.json".to_string(),
+                id: id.to_string(),
+            });
+        }
+        let exp = Exp3::from_json(id, exp3_json)?;
+        self.parameters.insert(id.to_string(), exp);
+        Ok(())
+    }
+
+    /// Resting parameter values of the model, used as the Overwrite start point.
+    pub fn set_base_values(&mut self, base_values: HashMap<String, f32>) {
+        self.base_values = base_values;
+    }
+
+    pub fn base_value(&self, param_id: &str) -> f32 {
+        self.base_values.get(param_id).copied().unwrap_or(0.0)
+    }
+
+    /// All parameter IDs any loaded expression touches, sorted. This is the
+    /// layout of `blend_parameters_array`.
+    pub fn parameter_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self
+            .parameters
+            .values()
+            .flat_map(|exp| exp.parameters.iter().map(|p| p.id.clone()))
+            .collect();
+        ids.sort();
+        ids.dedup();
+        ids
+    }
+
+    /// Blended parameter values for a weighted mix of expressions. Layers
+    /// are applied in model order so Overwrite results are deterministic.
+    pub fn blend_parameters(
+        &self,
+        weights: &HashMap<String, f32>,
+    ) -> Result<BTreeMap<String, f32>, RegistryError> {
+        for id in weights.keys() {
+            if !self.contains(id) {
+                return Err(RegistryError::UnknownId {
+                    label: "blend".to_string(),
+                    id: id.clone(),
+                });
+            }
+            if !self.parameters.contains_key(id) {
+                return Err(RegistryError::NoParameters(id.clone()));
+            }
+        }
+
+        let layers: Vec<(&Exp3, f32)> = self
+            .expressions
+            .iter()
+            .filter_map(|e| {
+                let weight = *weights.get(&e.id)?;
+                Some((&self.parameters[&e.id], weight))
+            })
+            .collect();
+
+        Ok(exp3::blend(&layers, &self.base_values))
+    }
+
+    // ========== MOTIONS ==========
+    /// Replace the motion table. Expression IDs and moods must be known, and
+    /// when the model3.json listed its motions, every group/index must exist.
+    pub fn set_motions(&mut self, table: MotionTable) -> Result<(), RegistryError> {
+        for id in table.expressions.keys() {
+            if !self.contains(id) {
+                return Err(RegistryError::UnknownId {
+                    label: "motions".to_string(),
+                    id: id.clone(),
+                });
+            }
+        }
+        if let Some(mood) = table.moods.keys().find(|m| !MOODS.contains(&m.as_str())) {
+            return Err(RegistryError::UnknownMood(mood.clone()));
+        }
+        if let Some(groups) = &self.motion_groups {
+            let missing = table
+                .entries()
+                .find(|e| groups.get(&e.group).is_none_or(|&count| e.index as usize >= count));
+            if let Some(entry) = missing {
+                return Err(RegistryError::UnknownMotion {
+                    group: entry.group.clone(),
+                    index: entry.index,
+                });
+            }
+        }
+
+        self.motions = table;
+        Ok(())
+    }
+
+    pub fn motions(&self) -> &MotionTable {
+        &self.motions
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.expressions.iter().map(|e| e.id.clone()).collect()
+    }
+
+    pub fn info(&self) -> RegistryInfo<'_> {
+        RegistryInfo {
+            expressions: &self.expressions,
+            emotions: &self.emotions,
+            moods: &self.moods,
+        }
+    }
+}
+
+impl Default for ExpressionRegistry {
+    fn default() -> Self {
+        Self::shizuku()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = r#"{"FileReferences": {"Expressions": [
+        {"Name": "calm", "File": "calm.exp3.json"},
+        {"Name": "smile", "File": "smile.exp3.json"},
+        {"Name": "frown", "File": "frown.exp3.json"},
+        {"Name": "gasp", "File": "gasp.exp3.json"}
+    ], "Motions": {"Idle": [{}, {}]}}}"#;
+
+    const EMOTIONS: &str =
+        r#""emotions": {"neutral": "calm", "happy": "smile", "sad": "frown", "surprised": "gasp"}"#;
+
+    fn registry(mapping: &str) -> Result<ExpressionRegistry, RegistryError> {
+        ExpressionRegistry::from_json(MODEL, mapping)
+    }
+
+    #[test]
+    fn unmapped_labels_and_moods_get_their_fallbacks() {
+        let registry = registry(&format!("{{{}}}", EMOTIONS)).unwrap();
+        assert_eq!(registry.for_emotion("curious"), Ok("gasp"));
+        assert_eq!(registry.for_emotion("concerned"), Ok("frown"));
+        assert_eq!(registry.for_mood("playful"), Ok("smile"));
+        assert_eq!(registry.for_mood("reflective"), Ok("frown"));
+        assert_eq!(registry.for_emotion("angry"), Err(RegistryError::UnknownLabel("angry".to_string())));
+    }
+
+    #[test]
+    fn mapping_to_an_unknown_id_is_rejected() {
+        let mapping = r#"{"emotions": {"neutral": "calm", "happy": "grin", "sad": "frown", "surprised": "gasp"}}"#;
+        assert_eq!(
+            registry(mapping).unwrap_err(),
+            RegistryError::UnknownId { label: "happy".to_string(), id: "grin".to_string() }
+        );
+
+        let mood = format!(r#"{{{}, "moods": {{"chill": "f01"}}}}"#, EMOTIONS);
+        assert!(matches!(registry(&mood), Err(RegistryError::UnknownId { .. })));
+    }
+
+    #[test]
+    fn incomplete_or_inconsistent_mappings_are_rejected() {
+        let partial = r#"{"emotions": {"neutral": "calm", "happy": "smile", "sad": "frown"}}"#;
+        assert_eq!(registry(partial).unwrap_err(), RegistryError::MissingEmotion("surprised".to_string()));
+
+        let mood = format!(r#"{{{}, "moods": {{"grumpy": "calm"}}}}"#, EMOTIONS);
+        assert_eq!(registry(&mood).unwrap_err(), RegistryError::UnknownMood("grumpy".to_string()));
+
+        let empty = r#"{"FileReferences": {}}"#;
+        let mapping = format!("{{{}}}", EMOTIONS);
+        assert_eq!(ExpressionRegistry::from_json(empty, &mapping).unwrap_err(), RegistryError::NoExpressions);
+    }
+
+    #[test]
+    fn parameters_need_a_registered_expression() {
+        let mut registry = registry(&format!("{{{}}}", EMOTIONS)).unwrap();
+        let exp3 = r#"{"Parameters": [{"Id": "ParamMouthForm", "Value": 1.0}]}"#;
+        assert!(matches!(registry.load_parameters("wink", exp3), Err(RegistryError::UnknownId { .. })));
+        registry.load_parameters("smile", exp3).unwrap();
+        assert_eq!(registry.parameter_ids(), ["ParamMouthForm"]);
+    }
+
+    #[test]
+    fn blends_only_the_weighted_expressions() {
+        let mut registry = registry(&format!("{{{}}}", EMOTIONS)).unwrap();
+        let exp3 = r#"{"Parameters": [{"Id": "ParamMouthForm", "Value": 1.0}]}"#;
+        registry.load_parameters("smile", exp3).unwrap();
+
+        // Other expressions have no exp3.json loaded and aren't in the blend
+        let weights = |id: &str| HashMap::from([(id.to_string(), 0.5)]);
+        assert_eq!(registry.blend_parameters(&weights("smile")).unwrap()["ParamMouthForm"], 0.5);
+        assert_eq!(
+            registry.blend_parameters(&weights("frown")).unwrap_err(),
+            RegistryError::NoParameters("frown".to_string())
+        );
+        assert!(matches!(registry.blend_parameters(&weights("wink")), Err(RegistryError::UnknownId { .. })));
+    }
+}
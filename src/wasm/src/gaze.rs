@@ -0,0 +1,416 @@
+// src/wasm/src/gaze.rs
+use serde::Serialize;
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+
+use crate::{to_js_object, MOODS};
+
+/// Parameters written by `GazeSolver`, in the order `update` returns them.
+pub const GAZE_PARAMETERS: [&str; 6] = [
+    "ParamAngleX",
+    "ParamAngleY",
+    "ParamAngleZ",
+    "ParamEyeBallX",
+    "ParamEyeBallY",
+    "ParamBodyAngleX",
+];
+
+// Spring stiffness (natural frequency, rad/s): eyes lead, the body lags
+const EYE_OMEGA: f32 = 28.0;
+const HEAD_OMEGA: f32 = 9.0;
+const BODY_OMEGA: f32 = 3.5;
+
+const LOOK_AWAY_MS: f64 = 1400.0;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct GazeLimits {
+    pub angle_x: f32,
+    pub angle_y: f32,
+    pub angle_z: f32,
+    pub body_x: f32,
+}
+
+impl Default for GazeLimits {
+    /// Ranges of a standard Cubism model.
+    fn default() -> Self {
+        Self {
+            angle_x: 30.0,
+            angle_y: 30.0,
+            angle_z: 30.0,
+            body_x: 10.0,
+        }
+    }
+}
+
+/// How a mood pays attention to the pointer.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Attention {
+    follow: f32,  // how far the head turns toward the target, 0-1
+    contact: f32, // how much the eyes meet the target, 0-1
+    avert: (f32, f32),
+    speed: f32,   // spring frequency multiplier
+    look_away_after_ms: f64,
+}
+
+impl Attention {
+    fn for_mood(mood: &str) -> Self {
+        match mood {
+            "playful" => Attention {
+                follow: 1.0,
+                contact: 1.0,
+                avert: (0.0, 0.0),
+                speed: 1.3,
+                look_away_after_ms: 8000.0,
+            },
+            // Sad: turns toward you a little but keeps the eyes low and aside
+            "reflective" => Attention {
+                follow: 0.45,
+                contact: 0.35,
+                avert: (0.35, -0.55),
+                speed: 0.7,
+                look_away_after_ms: 2500.0,
+            },
+            _ => Attention {
+                follow: 0.8,
+                contact: 0.9,
+                avert: (0.0, -0.05),
+                speed: 1.0,
+                look_away_after_ms: 5000.0,
+            },
+        }
+    }
+}
+
+/// Critically damped spring, stepped with the exact solution so any `dt`
+/// is stable and never overshoots.
+#[derive(Clone, Copy, Debug, Default)]
+struct Spring {
+    value: f32,
+    velocity: f32,
+}
+
+impl Spring {
+    fn step(&mut self, target: f32, omega: f32, dt: f32) {
+        let delta = self.value - target;
+        let temp = (self.velocity + omega * delta) * dt;
+        let decay = (-omega * dt).exp();
+        self.value = target + (delta + temp) * decay;
+        self.velocity = (self.velocity - omega * temp) * decay;
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GazeFrame {
+    pub time_ms: f64,
+    pub mood: String,
+    pub tracking: bool,
+    pub looking_away: bool,
+    pub eye_contact_ms: f64,
+    pub parameters: BTreeMap<String, f32>,
+}
+
+// ========== GAZE SOLVER ==========
+/// Turns a pointer (or any target) position on the canvas into smoothed
+/// head, eye and body angles. Springs are stepped by the time between
+/// `update` calls, so results depend only on the inputs and `now_ms`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct GazeSolver {
+    width: f32,
+    height: f32,
+    face: (f32, f32),
+    target: Option<(f32, f32)>,
+    mood: String,
+    attention: Attention,
+    limits: GazeLimits,
+    look_away_after_ms: Option<f64>, // overrides the mood's tolerance
+    springs: [Spring; 6],
+    last_ms: Option<f64>,
+    contact_ms: f64,
+    look_away_until: Option<f64>,
+    look_aways: u32,
+}
+
+#[wasm_bindgen]
+impl GazeSolver {
+    /// The face anchor defaults to the horizontal centre, a third of the way
+    /// down, which suits a bust-up model.
+    #[wasm_bindgen(constructor)]
+    pub fn new(canvas_width: f32, canvas_height: f32) -> Self {
+        let mut solver = Self {
+            width: 1.0,
+            height: 1.0,
+            face: (0.5, 0.33),
+            target: None,
+            mood: "chill".to_string(),
+            attention: Attention::for_mood("chill"),
+            limits: GazeLimits::default(),
+            look_away_after_ms: None,
+            springs: [Spring::default(); 6],
+            last_ms: None,
+            contact_ms: 0.0,
+            look_away_until: None,
+            look_aways: 0,
+        };
+        solver.set_canvas(canvas_width, canvas_height, canvas_width * 0.5, canvas_height * 0.33);
+        solver
+    }
+
+    /// Canvas size and where the face is on it, all in the same units as the
+    /// target (usually CSS pixels).
+    #[wasm_bindgen]
+    pub fn set_canvas(&mut self, width: f32, height: f32, face_x: f32, face_y: f32) {
+        self.width = if width.is_finite() && width > 0.0 { width } else { 1.0 };
+        self.height = if height.is_finite() && height > 0.0 { height } else { 1.0 };
+        self.face = (face_x / self.width, face_y / self.height);
+    }
+
+    #[wasm_bindgen]
+    pub fn set_target(&mut self, x: f32, y: f32) {
+        if x.is_finite() && y.is_finite() {
+            self.target = Some((x / self.width, y / self.height));
+        }
+    }
+
+    /// Pointer left the canvas: ease back to looking straight ahead.
+    #[wasm_bindgen]
+    pub fn clear_target(&mut self) {
+        self.target = None;
+        self.contact_ms = 0.0;
+        self.look_away_until = None;
+    }
+
+    #[wasm_bindgen]
+    pub fn set_mood(&mut self, mood: &str) -> Result<(), JsError> {
+        if !MOODS.contains(&mood) {
+            return Err(JsError::new(&format!("unknown mood '{}'", mood)));
+        }
+        self.mood = mood.to_string();
+        self.attention = Attention::for_mood(mood);
+        Ok(())
+    }
+
+    /// Maximum head angles and body turn, in degrees.
+    #[wasm_bindgen]
+    pub fn set_limits(&mut self, angle_x: f32, angle_y: f32, angle_z: f32, body_x: f32) {
+        let limit = |v: f32| if v.is_finite() { v.abs() } else { 0.0 };
+        self.limits = GazeLimits {
+            angle_x: limit(angle_x),
+            angle_y: limit(angle_y),
+            angle_z: limit(angle_z),
+            body_x: limit(body_x),
+        };
+    }
+
+    /// Look away after this much unbroken eye contact. 0 or less goes back
+    /// to the mood's default; `Infinity` never looks away.
+    #[wasm_bindgen]
+    pub fn set_look_away_after(&mut self, ms: f64) {
+        self.look_away_after_ms = if ms.is_nan() || ms <= 0.0 { None } else { Some(ms) };
+    }
+
+    #[wasm_bindgen]
+    pub fn parameter_ids() -> Vec<String> {
+        GAZE_PARAMETERS.iter().map(|p| p.to_string()).collect()
+    }
+
+    /// Step the springs to `now_ms` and get the values, laid out like
+    /// `parameter_ids()`.
+    #[wasm_bindgen]
+    pub fn update(&mut self, now_ms: f64) -> Vec<f32> {
+        self.step(now_ms).to_vec()
+    }
+
+    #[wasm_bindgen]
+    pub fn update_frame(&mut self, now_ms: f64) -> JsValue {
+        to_js_object(&self.frame(now_ms))
+    }
+}
+
+impl GazeSolver {
+    /// Where the target is relative to the face, -1..1 on both axes, +y up.
+    fn direction(&self) -> (f32, f32) {
+        match self.target {
+            Some((x, y)) => (
+                ((x - self.face.0) * 2.0).clamp(-1.0, 1.0),
+                ((self.face.1 - y) * 2.0).clamp(-1.0, 1.0),
+            ),
+            None => (0.0, 0.0),
+        }
+    }
+
+    /// Counts eye contact while a target is tracked; once it lasts longer
+    /// than the mood tolerates, glance aside for a moment and start over.
+    fn update_look_away(&mut self, now_ms: f64, dt_ms: f64) {
+        if self.target.is_none() {
+            return;
+        }
+        if let Some(until) = self.look_away_until {
+            if now_ms < until {
+                return;
+            }
+            self.look_away_until = None;
+            self.contact_ms = 0.0;
+        }
+
+        self.contact_ms += dt_ms;
+        let tolerance = self
+            .look_away_after_ms
+            .unwrap_or(self.attention.look_away_after_ms);
+        if self.contact_ms >= tolerance {
+            self.look_away_until = Some(now_ms + LOOK_AWAY_MS);
+            self.look_aways += 1;
+        }
+    }
+
+    fn targets(&self) -> [f32; 6] {
+        let (dx, dy) = self.direction();
+        let a = self.attention;
+        let l = self.limits;
+
+        let (head_x, head_y, eye_x, eye_y) = if self.look_away_until.is_some() {
+            // Alternate sides so it doesn't look scripted
+            let side = if self.look_aways.is_multiple_of(2) { 1.0 } else { -1.0 };
+            (dx * 0.3 + side * 0.35, -0.25, side * 0.8, -0.4)
+        } else {
+            let eye_x = dx * a.contact + a.avert.0 * (1.0 - a.contact) * dx.signum();
+            let eye_y = dy * a.contact + a.avert.1;
+            (dx * a.follow, dy * a.follow, eye_x, eye_y)
+        };
+
+        [
+            (head_x * l.angle_x).clamp(-l.angle_x, l.angle_x),
+            (head_y * l.angle_y).clamp(-l.angle_y, l.angle_y),
+            (-head_x * head_y * l.angle_z).clamp(-l.angle_z, l.angle_z),
+            eye_x.clamp(-1.0, 1.0),
+            eye_y.clamp(-1.0, 1.0),
+            (head_x * l.body_x).clamp(-l.body_x, l.body_x),
+        ]
+    }
+
+    pub fn step(&mut self, now_ms: f64) -> [f32; GAZE_PARAMETERS.len()] {
+        let dt_ms = self.last_ms.map_or(0.0, |last| (now_ms - last).max(0.0));
+        self.last_ms = Some(now_ms);
+        self.update_look_away(now_ms, dt_ms);
+
+        let targets = self.targets();
+        let dt = (dt_ms / 1000.0) as f32;
+        let speed = self.attention.speed;
+        let omegas = [HEAD_OMEGA, HEAD_OMEGA, HEAD_OMEGA, EYE_OMEGA, EYE_OMEGA, BODY_OMEGA];
+
+        let mut values = [0.0; GAZE_PARAMETERS.len()];
+        for (i, spring) in self.springs.iter_mut().enumerate() {
+            spring.step(targets[i], omegas[i] * speed, dt);
+            values[i] = spring.value;
+        }
+        values
+    }
+
+    pub fn frame(&mut self, now_ms: f64) -> GazeFrame {
+        let values = self.step(now_ms);
+        GazeFrame {
+            time_ms: now_ms,
+            mood: self.mood.clone(),
+            tracking: self.target.is_some(),
+            looking_away: self.look_away_until.is_some(),
+            eye_contact_ms: self.contact_ms,
+            parameters: GAZE_PARAMETERS
+                .iter()
+                .map(|id| id.to_string())
+                .zip(values)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EYE_Y: usize = 4;
+
+    /// Solver looking at the top-left corner, settled for `ms`.
+    fn settled(mood: &str, ms: f64) -> (GazeSolver, [f32; 6]) {
+        let mut solver = GazeSolver::new(800.0, 600.0);
+        solver.set_mood(mood).unwrap();
+        solver.set_look_away_after(f64::INFINITY);
+        solver.set_target(0.0, 0.0);
+        solver.step(0.0);
+        let values = solver.step(ms);
+        (solver, values)
+    }
+
+    #[test]
+    fn spring_settles_without_overshoot_for_any_dt() {
+        for dt in [0.016, 0.5, 5.0, 60.0] {
+            let mut spring = Spring::default();
+            for _ in 0..200 {
+                spring.step(10.0, EYE_OMEGA, dt);
+                assert!(spring.value <= 10.0 && spring.value.is_finite());
+            }
+            assert!((spring.value - 10.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn angles_stay_within_the_limits() {
+        let mut solver = GazeSolver::new(800.0, 600.0);
+        solver.set_mood("playful").unwrap();
+        solver.set_limits(12.0, -8.0, 5.0, f32::NAN);
+        solver.set_target(-5000.0, -5000.0);
+
+        // Long enough to settle and to glance away at least once
+        let mut looked_away = false;
+        for t in (0..20_000).step_by(50) {
+            let frame = solver.frame(t as f64);
+            looked_away |= frame.looking_away;
+            let p = |id: &str| frame.parameters[id].abs();
+            assert!(p("ParamAngleX") <= 12.0 + 1e-3);
+            assert!(p("ParamAngleY") <= 8.0 + 1e-3);
+            assert!(p("ParamAngleZ") <= 5.0 + 1e-3);
+            assert!(p("ParamEyeBallX") <= 1.0 && p("ParamEyeBallY") <= 1.0);
+            assert_eq!(p("ParamBodyAngleX"), 0.0);
+        }
+        assert!(looked_away);
+
+        // Playful follows all the way, so the head sits on the limits
+        solver.set_look_away_after(f64::INFINITY);
+        let values = solver.step(40_000.0);
+        assert!((values[0] + 12.0).abs() < 1e-3 && (values[1] - 8.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn looks_away_after_the_tolerance_and_comes_back() {
+        let mut solver = GazeSolver::new(800.0, 600.0);
+        solver.set_look_away_after(3000.0);
+        solver.set_target(400.0, 200.0);
+        solver.step(0.0);
+
+        assert!(!solver.frame(2999.0).looking_away);
+        assert!(solver.frame(3000.0).looking_away);
+        assert!(solver.frame(3000.0 + LOOK_AWAY_MS - 1.0).looking_away);
+
+        let back = solver.frame(3000.0 + LOOK_AWAY_MS);
+        assert!(!back.looking_away);
+        assert_eq!(back.eye_contact_ms, 1.0);
+    }
+
+    #[test]
+    fn no_target_never_looks_away() {
+        let mut solver = GazeSolver::new(800.0, 600.0);
+        solver.step(0.0);
+        let frame = solver.frame(60_000.0);
+        assert!(!frame.tracking && !frame.looking_away);
+        assert_eq!(frame.eye_contact_ms, 0.0);
+        assert_eq!(frame.parameters["ParamAngleX"], 0.0);
+    }
+
+    #[test]
+    fn sad_mood_keeps_the_eyes_lower() {
+        let (_, chill) = settled("chill", 10_000.0);
+        let (_, reflective) = settled("reflective", 10_000.0);
+        assert_ne!(Attention::for_mood("reflective"), Attention::for_mood("chill"));
+        assert!(reflective[0].abs() < chill[0].abs());
+        assert!(reflective[EYE_Y] < chill[EYE_Y]);
+    }
+}
@@ -0,0 +1,430 @@
+// src/wasm/src/idle.rs
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::f64::consts::TAU;
+use wasm_bindgen::prelude::*;
+
+use crate::rng::hash01;
+use crate::{to_js_object, MOODS};
+
+/// Parameters written by `IdleAnimator`, in the order `sample` returns them.
+pub const IDLE_PARAMETERS: [&str; 11] = [
+    "ParamEyeLOpen",
+    "ParamEyeROpen",
+    "ParamEyeBallX",
+    "ParamEyeBallY",
+    "ParamBrowLY",
+    "ParamBrowRY",
+    "ParamMouthForm",
+    "ParamAngleX",
+    "ParamAngleY",
+    "ParamAngleZ",
+    "ParamBreath",
+];
+
+pub const DEFAULT_ENERGY: f32 = 0.5;
+
+// Blink shape: fast close, short hold, slower open
+const BLINK_CLOSE_MS: f64 = 70.0;
+const BLINK_HOLD_MS: f64 = 40.0;
+const BLINK_OPEN_MS: f64 = 120.0;
+const BLINK_MS: f64 = BLINK_CLOSE_MS + BLINK_HOLD_MS + BLINK_OPEN_MS;
+/// Gap between the two blinks of a double blink.
+const DOUBLE_BLINK_GAP_MS: f64 = 60.0;
+const DOUBLE_BLINK_CHANCE: f64 = 0.15;
+
+/// Time constant for movement easing over to a new mood or energy.
+const MOVEMENT_EASE_MS: f64 = 600.0;
+
+const SACCADE_MS: f64 = 120.0;
+const GLANCE_RETURN_MS: f64 = 220.0;
+
+// Noise channels, so each curve gets its own independent stream
+const CH_BLINK: u64 = 1;
+const CH_DOUBLE: u64 = 2;
+const CH_GLANCE: u64 = 3;
+const CH_GLANCE_X: u64 = 4;
+const CH_GLANCE_Y: u64 = 5;
+const CH_GLANCE_HOLD: u64 = 6;
+const CH_BROW: u64 = 7;
+const CH_BROW_SKEW: u64 = 8;
+const CH_MOUTH: u64 = 9;
+const CH_ANGLE_X: u64 = 10;
+const CH_ANGLE_Y: u64 = 11;
+const CH_ANGLE_Z: u64 = 12;
+
+/// How a mood moves the face while nobody is talking.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct IdleProfile {
+    blink_interval_ms: f64,
+    breath_period_ms: f64,
+    glance_interval_ms: f64,
+    movement: f32,   // scale for micro-movements and glances
+    mouth_bias: f32, // resting ParamMouthForm
+    gaze_bias_y: f32,
+}
+
+impl IdleProfile {
+    fn for_mood(mood: &str) -> Self {
+        match mood {
+            "playful" => IdleProfile {
+                blink_interval_ms: 3000.0,
+                breath_period_ms: 3300.0,
+                glance_interval_ms: 4000.0,
+                movement: 1.5,
+                mouth_bias: 0.25,
+                gaze_bias_y: 0.05,
+            },
+            "reflective" => IdleProfile {
+                blink_interval_ms: 5500.0,
+                breath_period_ms: 5200.0,
+                glance_interval_ms: 9000.0,
+                movement: 0.6,
+                mouth_bias: -0.1,
+                gaze_bias_y: -0.3,
+            },
+            _ => IdleProfile {
+                blink_interval_ms: 4000.0,
+                breath_period_ms: 4200.0,
+                glance_interval_ms: 6500.0,
+                movement: 1.0,
+                mouth_bias: 0.05,
+                gaze_bias_y: 0.0,
+            },
+        }
+    }
+
+    /// Energy 0-1 speeds things up and widens movement around the mood's
+    /// own profile; 0.5 leaves it unchanged.
+    fn with_energy(self, energy: f32) -> Self {
+        let pace = 1.0 + (0.5 - energy as f64) * 0.6; // 1.3 (tired) .. 0.7 (wired)
+        IdleProfile {
+            blink_interval_ms: self.blink_interval_ms * pace,
+            breath_period_ms: self.breath_period_ms * pace,
+            glance_interval_ms: self.glance_interval_ms * pace,
+            movement: self.movement * (0.6 + 0.8 * energy),
+            ..self
+        }
+    }
+}
+
+// ========== NOISE ==========
+/// Smooth value noise in -1..1, one lattice point every `period_ms`.
+fn noise(seed: u64, channel: u64, t_ms: f64, period_ms: f64) -> f32 {
+    let x = t_ms.max(0.0) / period_ms;
+    let i = x.floor();
+    let f = x - i;
+    let f = f * f * (3.0 - 2.0 * f);
+    let a = hash01(seed, channel, i as u64) * 2.0 - 1.0;
+    let b = hash01(seed, channel, i as u64 + 1) * 2.0 - 1.0;
+    (a + (b - a) * f) as f32
+}
+
+/// Jittered interval around `mean`: 0.55x to 1.45x.
+fn jittered(mean: f64, roll: f64) -> f64 {
+    mean * (0.55 + 0.9 * roll)
+}
+
+/// Eyelid closure 0-1 at `t` ms into a single blink.
+fn blink_closure(t: f64) -> f32 {
+    if !(0.0..BLINK_MS).contains(&t) {
+        0.0
+    } else if t < BLINK_CLOSE_MS {
+        (t / BLINK_CLOSE_MS) as f32
+    } else if t < BLINK_CLOSE_MS + BLINK_HOLD_MS {
+        1.0
+    } else {
+        let t = (t - BLINK_CLOSE_MS - BLINK_HOLD_MS) / BLINK_OPEN_MS;
+        (1.0 - t * t * (3.0 - 2.0 * t)) as f32
+    }
+}
+
+/// A recurring event (blink, glance) on a jittered schedule. Only the
+/// previous and next occurrence are kept, so sampling is O(1) as time moves on.
+#[derive(Clone, Copy, Debug)]
+struct Schedule {
+    channel: u64,
+    count: u64,
+    last_ms: Option<f64>,
+    next_ms: f64,
+}
+
+impl Schedule {
+    fn new(channel: u64, seed: u64, start_ms: f64, mean_ms: f64) -> Self {
+        Self {
+            channel,
+            count: 0,
+            last_ms: None,
+            next_ms: start_ms + jittered(mean_ms, hash01(seed, channel, 0)),
+        }
+    }
+
+    fn advance(&mut self, seed: u64, now_ms: f64, mean_ms: f64) {
+        while self.next_ms <= now_ms {
+            self.last_ms = Some(self.next_ms);
+            self.count += 1;
+            self.next_ms += jittered(mean_ms, hash01(seed, self.channel, self.count));
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct IdleFrame {
+    pub time_ms: f64,
+    pub mood: String,
+    pub energy: f32,
+    pub blinking: bool,
+    pub glancing: bool,
+    pub parameters: BTreeMap<String, f32>,
+}
+
+// ========== IDLE ANIMATOR ==========
+/// Procedural idle motion between messages: blinks with jitter and the odd
+/// double blink, breathing, small brow/mouth/head drift and glances.
+/// Everything is derived from `seed` and the time passed to `sample`, so the
+/// same seed and calls give the same curves.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct IdleAnimator {
+    seed: u64,
+    start_ms: f64,
+    mood: String,
+    energy: f32,
+    profile: IdleProfile,
+    blinks: Schedule,
+    glances: Schedule,
+    last_ms: f64,
+    breath_cycles: f64, // accumulated, so a new breath period doesn't jump
+    movement: f32,      // eases toward `profile.movement`
+}
+
+#[wasm_bindgen]
+impl IdleAnimator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32, now_ms: f64) -> Self {
+        let seed = seed as u64;
+        let profile = IdleProfile::for_mood("chill").with_energy(DEFAULT_ENERGY);
+        Self {
+            seed,
+            start_ms: now_ms,
+            mood: "chill".to_string(),
+            energy: DEFAULT_ENERGY,
+            profile,
+            blinks: Schedule::new(CH_BLINK, seed, now_ms, profile.blink_interval_ms),
+            glances: Schedule::new(CH_GLANCE, seed, now_ms, profile.glance_interval_ms),
+            last_ms: now_ms,
+            breath_cycles: 0.0,
+            movement: profile.movement,
+        }
+    }
+
+    /// Blinks and glances pick up the new pace from the next one on;
+    /// breathing keeps its phase and movement eases over, so nothing jumps.
+    #[wasm_bindgen]
+    pub fn set_mood(&mut self, mood: &str) -> Result<(), JsError> {
+        if !MOODS.contains(&mood) {
+            return Err(JsError::new(&format!("unknown mood '{}'", mood)));
+        }
+        self.mood = mood.to_string();
+        self.refresh_profile();
+        Ok(())
+    }
+
+    /// 0 (drowsy) to 1 (energetic); clamped. Applied like `set_mood`.
+    #[wasm_bindgen]
+    pub fn set_energy(&mut self, energy: f32) {
+        self.energy = if energy.is_finite() {
+            energy.clamp(0.0, 1.0)
+        } else {
+            DEFAULT_ENERGY
+        };
+        self.refresh_profile();
+    }
+
+    #[wasm_bindgen]
+    pub fn parameter_ids() -> Vec<String> {
+        IDLE_PARAMETERS.iter().map(|p| p.to_string()).collect()
+    }
+
+    /// Parameter values at `now_ms`, laid out like `parameter_ids()`.
+    #[wasm_bindgen]
+    pub fn sample(&mut self, now_ms: f64) -> Vec<f32> {
+        self.values(now_ms).to_vec()
+    }
+
+    /// `{time_ms, mood, energy, blinking, glancing, parameters: {ParamId: value}}`
+    #[wasm_bindgen]
+    pub fn sample_frame(&mut self, now_ms: f64) -> JsValue {
+        to_js_object(&self.frame(now_ms))
+    }
+}
+
+impl IdleAnimator {
+    fn refresh_profile(&mut self) {
+        self.profile = IdleProfile::for_mood(&self.mood).with_energy(self.energy);
+    }
+
+    /// Going back in time replays the schedules from the start, which keeps
+    /// seeking (e.g. in a timeline scrubber) consistent.
+    fn advance(&mut self, now_ms: f64) {
+        let behind = |s: &Schedule| s.last_ms.is_some_and(|last| now_ms < last);
+        if behind(&self.blinks) || behind(&self.glances) {
+            let (seed, start) = (self.seed, self.start_ms);
+            self.blinks = Schedule::new(CH_BLINK, seed, start, self.profile.blink_interval_ms);
+            self.glances = Schedule::new(CH_GLANCE, seed, start, self.profile.glance_interval_ms);
+        }
+        self.blinks.advance(self.seed, now_ms, self.profile.blink_interval_ms);
+        self.glances.advance(self.seed, now_ms, self.profile.glance_interval_ms);
+
+        // Breathing and movement carry on from the previous sample at the
+        // current pace. Seeking back restarts them at the current profile.
+        let dt = now_ms - self.last_ms;
+        if dt < 0.0 {
+            self.breath_cycles = (now_ms - self.start_ms).max(0.0) / self.profile.breath_period_ms;
+            self.movement = self.profile.movement;
+        } else {
+            self.breath_cycles += dt / self.profile.breath_period_ms;
+            let ease = 1.0 - (-dt / MOVEMENT_EASE_MS).exp() as f32;
+            self.movement += (self.profile.movement - self.movement) * ease;
+        }
+        self.last_ms = now_ms;
+    }
+
+    fn eye_closure(&self, now_ms: f64) -> f32 {
+        let Some(start) = self.blinks.last_ms else {
+            return 0.0;
+        };
+        let t = now_ms - start;
+        let closure = blink_closure(t);
+        if hash01(self.seed, CH_DOUBLE, self.blinks.count) < DOUBLE_BLINK_CHANCE {
+            closure.max(blink_closure(t - BLINK_MS - DOUBLE_BLINK_GAP_MS))
+        } else {
+            closure
+        }
+    }
+
+    /// Eyeball offset (x, y) from the current glance, 0 when not glancing.
+    fn glance(&self, now_ms: f64) -> Option<(f32, f32)> {
+        let start = self.glances.last_ms?;
+        let n = self.glances.count;
+        let hold = 500.0 + 1000.0 * hash01(self.seed, CH_GLANCE_HOLD, n);
+        let t = now_ms - start;
+        if t >= SACCADE_MS + hold + GLANCE_RETURN_MS {
+            return None;
+        }
+
+        let reach = (0.45 * self.movement).min(0.9);
+        let x = (hash01(self.seed, CH_GLANCE_X, n) * 2.0 - 1.0) as f32 * reach;
+        let y = (hash01(self.seed, CH_GLANCE_Y, n) * 2.0 - 1.0) as f32 * reach * 0.5;
+
+        let amount = if t < SACCADE_MS {
+            let p = t / SACCADE_MS;
+            1.0 - (1.0 - p).powi(3) // eyes snap, then settle
+        } else if t < SACCADE_MS + hold {
+            1.0
+        } else {
+            let p = (t - SACCADE_MS - hold) / GLANCE_RETURN_MS;
+            1.0 - p * p * (3.0 - 2.0 * p)
+        } as f32;
+        Some((x * amount, y * amount))
+    }
+
+    pub fn values(&mut self, now_ms: f64) -> [f32; IDLE_PARAMETERS.len()] {
+        self.advance(now_ms);
+        let seed = self.seed;
+        let profile = self.profile;
+        let m = self.movement;
+        let t = now_ms - self.start_ms;
+
+        let eye_open = 1.0 - self.eye_closure(now_ms);
+        let (glance_x, glance_y) = self.glance(now_ms).unwrap_or((0.0, 0.0));
+
+        let brow = noise(seed, CH_BROW, t, 2600.0) * 0.12 * m;
+        let skew = noise(seed, CH_BROW_SKEW, t, 3900.0) * 0.04 * m;
+        let mouth = profile.mouth_bias + noise(seed, CH_MOUTH, t, 3100.0) * 0.08 * m;
+
+        let breath_phase = (self.breath_cycles * TAU).sin() as f32;
+        let breath = 0.5 + 0.5 * breath_phase;
+
+        // The head follows a glance a little, on top of its own drift
+        let angle_x = noise(seed, CH_ANGLE_X, t, 3400.0) * 3.0 * m + glance_x * 6.0;
+        let angle_y =
+            noise(seed, CH_ANGLE_Y, t, 3800.0) * 2.0 * m + breath_phase * 0.8 + glance_y * 4.0;
+        let angle_z = noise(seed, CH_ANGLE_Z, t, 4700.0) * 2.5 * m;
+
+        [
+            eye_open,
+            eye_open,
+            (glance_x).clamp(-1.0, 1.0),
+            (profile.gaze_bias_y + glance_y).clamp(-1.0, 1.0),
+            brow + skew,
+            brow - skew,
+            mouth.clamp(-1.0, 1.0),
+            angle_x,
+            angle_y,
+            angle_z,
+            breath,
+        ]
+    }
+
+    pub fn frame(&mut self, now_ms: f64) -> IdleFrame {
+        let values = self.values(now_ms);
+        IdleFrame {
+            time_ms: now_ms,
+            mood: self.mood.clone(),
+            energy: self.energy,
+            blinking: values[0] < 1.0,
+            glancing: self.glance(now_ms).is_some(),
+            parameters: IDLE_PARAMETERS
+                .iter()
+                .map(|id| id.to_string())
+                .zip(values)
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BREATH: usize = 10;
+    const ANGLE_Z: usize = 9;
+
+    #[test]
+    fn breathing_does_not_jump_on_a_mood_change() {
+        let mut idle = IdleAnimator::new(7, 0.0);
+        let before = idle.values(60_000.0)[BREATH];
+        idle.set_mood("playful").unwrap();
+        idle.set_energy(1.0);
+        let after = idle.values(60_016.0)[BREATH];
+        assert!((after - before).abs() < 0.05, "{} -> {}", before, after);
+    }
+
+    #[test]
+    fn movement_eases_over_to_the_new_scale() {
+        let mut calm = IdleAnimator::new(7, 0.0);
+        let mut wired = calm.clone();
+        calm.values(10_000.0);
+        wired.values(10_000.0);
+        wired.set_energy(1.0);
+
+        // Same noise, so the head angle only differs by the movement scale
+        let calm_z = calm.values(10_016.0)[ANGLE_Z];
+        let wired_z = wired.values(10_016.0)[ANGLE_Z];
+        assert!((wired_z - calm_z).abs() < 0.05 * calm_z.abs().max(0.1));
+
+        let wired_z = wired.values(15_000.0)[ANGLE_Z];
+        let mut settled = IdleAnimator::new(7, 0.0);
+        settled.set_energy(1.0);
+        assert!((wired_z - settled.values(15_000.0)[ANGLE_Z]).abs() < 1e-3);
+    }
+
+    #[test]
+    fn seeking_back_replays_the_same_curves() {
+        let mut idle = IdleAnimator::new(3, 0.0);
+        let first = idle.values(2_500.0);
+        idle.values(9_000.0);
+        assert_eq!(idle.values(2_500.0), first);
+    }
+}
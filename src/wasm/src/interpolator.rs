@@ -0,0 +1,286 @@
+// src/wasm/src/interpolator.rs
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use wasm_bindgen::prelude::*;
+
+use crate::easing::Easing;
+use crate::to_js_object;
+
+#[derive(Clone, Debug, PartialEq)]
+struct Transition {
+    to: String,
+    duration_ms: f64,
+    easing: Easing,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Running {
+    from: String,
+    transition: Transition,
+    start_ms: f64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct InterpolationFrame {
+    pub from: String,
+    pub to: String,
+    pub raw_progress: f32,
+    pub progress: f32, // eased, may overshoot for spring / elastic
+    pub complete: bool,
+    pub pending: usize,
+    pub weights: BTreeMap<String, f32>,
+}
+
+// ========== EXPRESSION INTERPOLATION ==========
+/// Crossfades between expressions. Time is always passed in (`now_ms`), so
+/// the same inputs give the same frames in tests, replays and the browser.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ExpressionInterpolator {
+    current: String,
+    running: Option<Running>,
+    chain: VecDeque<Transition>,
+}
+
+#[wasm_bindgen]
+impl ExpressionInterpolator {
+    #[wasm_bindgen(constructor)]
+    pub fn new(initial_expression: &str) -> Self {
+        Self {
+            current: initial_expression.to_string(),
+            running: None,
+            chain: VecDeque::new(),
+        }
+    }
+
+    /// Start a transition now, replacing anything running or chained. The
+    /// new one starts from whichever expression dominates at `now_ms`.
+    /// `easing` is anything `Easing::parse` accepts.
+    #[wasm_bindgen]
+    pub fn transition_to(
+        &mut self,
+        to: &str,
+        duration_ms: f64,
+        easing: &str,
+        now_ms: f64,
+    ) -> Result<(), JsError> {
+        let transition = Self::transition(to, duration_ms, easing)?;
+        self.cancel(now_ms);
+        self.start(transition, now_ms);
+        Ok(())
+    }
+
+    /// Queue a transition to run after the last one, or right away when idle.
+    #[wasm_bindgen]
+    pub fn then(
+        &mut self,
+        to: &str,
+        duration_ms: f64,
+        easing: &str,
+        now_ms: f64,
+    ) -> Result<(), JsError> {
+        let transition = Self::transition(to, duration_ms, easing)?;
+        self.push(transition, now_ms);
+        Ok(())
+    }
+
+    /// Drop the running and chained transitions and settle on the expression
+    /// that dominates at `now_ms`. Returns that expression.
+    #[wasm_bindgen]
+    pub fn cancel(&mut self, now_ms: f64) -> String {
+        self.advance(now_ms);
+        self.chain.clear();
+        if let Some(running) = self.running.take() {
+            let progress = Self::eased(&running, now_ms);
+            self.current = if progress >= 0.5 {
+                running.transition.to
+            } else {
+                running.from
+            };
+        }
+        self.current.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn is_animating(&self) -> bool {
+        self.running.is_some()
+    }
+
+    #[wasm_bindgen]
+    pub fn current_expression(&self) -> String {
+        self.current.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn sample(&mut self, now_ms: f64) -> JsValue {
+        to_js_object(&self.frame(now_ms))
+    }
+}
+
+impl ExpressionInterpolator {
+    fn transition(to: &str, duration_ms: f64, easing: &str) -> Result<Transition, JsError> {
+        let easing = Easing::parse(easing).map_err(|e| JsError::new(&e))?;
+        if !duration_ms.is_finite() || duration_ms < 0.0 {
+            return Err(JsError::new("duration_ms must be a non-negative number"));
+        }
+        Ok(Transition {
+            to: to.to_string(),
+            duration_ms,
+            easing,
+        })
+    }
+
+    fn push(&mut self, transition: Transition, now_ms: f64) {
+        self.advance(now_ms);
+        if self.running.is_none() {
+            self.start(transition, now_ms);
+        } else {
+            self.chain.push_back(transition);
+        }
+    }
+
+    fn start(&mut self, transition: Transition, at_ms: f64) {
+        self.running = Some(Running {
+            from: self.current.clone(),
+            transition,
+            start_ms: at_ms,
+        });
+    }
+
+    fn raw(running: &Running, now_ms: f64) -> f32 {
+        if running.transition.duration_ms == 0.0 {
+            return 1.0;
+        }
+        ((now_ms - running.start_ms) / running.transition.duration_ms).clamp(0.0, 1.0) as f32
+    }
+
+    fn eased(running: &Running, now_ms: f64) -> f32 {
+        running.transition.easing.apply(Self::raw(running, now_ms))
+    }
+
+    /// Finish every transition that ended by `now_ms`, starting chained ones
+    /// at the exact moment the previous one ended.
+    fn advance(&mut self, now_ms: f64) {
+        while let Some(running) = &self.running {
+            let end = running.start_ms + running.transition.duration_ms;
+            if end > now_ms {
+                break;
+            }
+            self.current = running.transition.to.clone();
+            self.running = None;
+            if let Some(next) = self.chain.pop_front() {
+                self.start(next, end);
+            }
+        }
+    }
+
+    pub fn frame(&mut self, now_ms: f64) -> InterpolationFrame {
+        self.advance(now_ms);
+
+        let mut weights = BTreeMap::new();
+        match &self.running {
+            Some(running) => {
+                let progress = Self::eased(running, now_ms);
+                weights.insert(running.from.clone(), 1.0 - progress);
+                *weights.entry(running.transition.to.clone()).or_insert(0.0) += progress;
+
+                InterpolationFrame {
+                    from: running.from.clone(),
+                    to: running.transition.to.clone(),
+                    raw_progress: Self::raw(running, now_ms),
+                    progress,
+                    complete: false,
+                    pending: self.chain.len(),
+                    weights,
+                }
+            }
+            None => {
+                weights.insert(self.current.clone(), 1.0);
+                InterpolationFrame {
+                    from: self.current.clone(),
+                    to: self.current.clone(),
+                    raw_progress: 1.0,
+                    progress: 1.0,
+                    complete: true,
+                    pending: 0,
+                    weights,
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight(frame: &InterpolationFrame, id: &str) -> f32 {
+        frame.weights.get(id).copied().unwrap_or(0.0)
+    }
+
+    #[test]
+    fn frames_depend_only_on_the_time_passed_in() {
+        let mut a = ExpressionInterpolator::new("f01");
+        a.transition_to("f02", 400.0, "linear", 1000.0).unwrap();
+        let mut b = a.clone();
+
+        // Sampling order and frequency don't change the result
+        let late = a.frame(1300.0);
+        b.frame(1100.0);
+        assert_eq!(b.frame(1300.0), late);
+        assert!((weight(&late, "f02") - 0.75).abs() < 1e-6);
+        assert!((weight(&late, "f01") - 0.25).abs() < 1e-6);
+        assert_eq!(a.frame(900.0).raw_progress, 0.0);
+    }
+
+    #[test]
+    fn chained_transitions_start_when_the_previous_one_ends() {
+        let mut interpolator = ExpressionInterpolator::new("f01");
+        interpolator.then("f02", 200.0, "linear", 0.0).unwrap();
+        interpolator.then("f03", 200.0, "linear", 50.0).unwrap();
+        assert_eq!(interpolator.frame(100.0).pending, 1);
+
+        // Skipping past the first end still starts the second at 200
+        let frame = interpolator.frame(300.0);
+        assert_eq!((frame.from.as_str(), frame.to.as_str()), ("f02", "f03"));
+        assert!((frame.raw_progress - 0.5).abs() < 1e-6);
+
+        let done = interpolator.frame(1000.0);
+        assert!(done.complete && !interpolator.is_animating());
+        assert_eq!(interpolator.current_expression(), "f03");
+    }
+
+    #[test]
+    fn cancel_settles_on_the_dominant_expression() {
+        let mut early = ExpressionInterpolator::new("f01");
+        early.transition_to("f02", 400.0, "linear", 0.0).unwrap();
+        early.then("f03", 400.0, "linear", 0.0).unwrap();
+        assert_eq!(early.cancel(100.0), "f01");
+        assert_eq!(early.frame(2000.0).weights, BTreeMap::from([("f01".to_string(), 1.0)]));
+
+        let mut late = ExpressionInterpolator::new("f01");
+        late.transition_to("f02", 400.0, "linear", 0.0).unwrap();
+        assert_eq!(late.cancel(300.0), "f02");
+    }
+
+    #[test]
+    fn transition_to_replaces_the_chain_from_the_dominant_expression() {
+        let mut interpolator = ExpressionInterpolator::new("f01");
+        interpolator.transition_to("f02", 400.0, "linear", 0.0).unwrap();
+        interpolator.then("f03", 400.0, "linear", 0.0).unwrap();
+        interpolator.transition_to("f04", 100.0, "linear", 300.0).unwrap();
+
+        let frame = interpolator.frame(350.0);
+        assert_eq!((frame.from.as_str(), frame.to.as_str(), frame.pending), ("f02", "f04", 0));
+        assert_eq!(interpolator.frame(400.0).weights, BTreeMap::from([("f04".to_string(), 1.0)]));
+    }
+
+    #[test]
+    fn zero_duration_completes_immediately() {
+        let mut interpolator = ExpressionInterpolator::new("f01");
+        interpolator.transition_to("f02", 0.0, "linear", 10.0).unwrap();
+        let frame = interpolator.frame(10.0);
+        assert!(frame.complete);
+        assert_eq!(frame.from, "f02");
+    }
+}
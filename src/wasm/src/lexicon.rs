@@ -0,0 +1,478 @@
+// src/wasm/src/lexicon.rs
+use std::collections::HashMap;
+
+use crate::register::RegisterModel;
+use crate::SentimentWord;
+
+/// (category, [(word, score, intensity level)])
+type ReplyGroup = (&'static str, &'static [(&'static str, i32, u8)]);
+
+/// Immutable word lists shared by every session. Building the dictionaries is
+/// the expensive part of `MiraCore::new`, so sessions share one `Rc<Lexicon>`.
+pub struct Lexicon {
+    pub sentiment_dict: HashMap<String, SentimentWord>,
+    pub reply_dict: HashMap<String, SentimentWord>, // how MIRA talks, see `Channel::Reply`
+    pub negation_words: Vec<String>,
+    pub intensifier_words: HashMap<String, f32>,
+    pub reflective_keywords: Vec<String>,
+    pub playful_keywords: Vec<String>,
+    pub sarcasm_markers: Vec<String>,
+    pub crisis_markers: Vec<String>, // grief, despair, self-harm: no playfulness
+    // Affect events: compliments and insults only count when aimed at MIRA
+    pub address_words: Vec<String>,
+    pub gratitude_words: Vec<String>,
+    pub compliment_words: Vec<String>,
+    pub insult_words: Vec<String>,
+    pub registers: RegisterModel, // formal / casual / gaul equivalences
+}
+
+impl Lexicon {
+    pub fn new() -> Self {
+        Self {
+            sentiment_dict: Self::build_sentiment_dictionary(),
+            reply_dict: Self::build_reply_dictionary(),
+            negation_words: Self::build_negations(),
+            intensifier_words: Self::build_intensifiers(),
+            reflective_keywords: [
+                "sedih", "galau", "stress", "capek", "lelah", "bingung", "takut", "khawatir",
+                "depresi", "putus", "gagal", "susah", "kecewa", "marah", "benci", "frustrasi",
+                "kesal", "dongkol", "keenakan", "penakut", "cemas", "resah", "gelisah",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            playful_keywords: [
+                "lucu", "haha", "wkwk", "joke", "bercanda", "main", "game", "seru", "asik",
+                "tebak", "cerita", "tantang", "ajari", "ajak", "ajaran", "mainan",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            sarcasm_markers: [
+                "ya kali", "yakali", "iya deh", "iyain aja", "makasih loh", "hebat ya",
+                "pinter banget sih", "terserah", "/s", "🙄",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            crisis_markers: [
+                "bunuh diri", "pengen mati", "ingin mati", "mau mati", "gak kuat lagi",
+                "nggak kuat lagi", "ga kuat lagi", "udah gak sanggup", "menyakiti diri",
+                "self harm", "meninggal", "berduka", "kehilangan dia", "pemakaman",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            address_words: ["kamu", "km", "kau", "lu", "lo", "elu", "mira", "dirimu"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            gratitude_words: ["makasih", "terima kasih", "thanks", "thank you", "thx", "tengkyu"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            compliment_words: [
+                "pintar", "pinter", "lucu", "cantik", "keren", "baik", "hebat", "asik", "sayang",
+                "gemes", "perhatian", "the best", "terbaik", "helpful",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            insult_words: [
+                "bodoh", "goblok", "tolol", "bego", "jelek", "payah", "nyebelin", "tai", "brengsek",
+                "sialan", "gak guna", "nggak guna", "useless", "stupid",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            registers: RegisterModel::default(),
+        }
+    }
+
+    // ========== SENTIMENT DICTIONARY BUILDER ==========
+    fn build_sentiment_dictionary() -> HashMap<String, SentimentWord> {
+        let mut dict = HashMap::new();
+
+        // POSITIVE EMOTIONS (HIGH INTENSITY)
+        let positive_high = vec![
+            ("senang", 3),
+            ("bahagia", 3),
+            ("gembira", 3),
+            ("cinta", 3),
+            ("love", 3),
+            ("suka", 2),
+            ("menyukai", 2),
+            ("hepi", 3),
+            ("happy", 3),
+            ("mantap", 2),
+            ("keren", 2),
+            ("awesome", 2),
+            ("amazing", 2),
+            ("gemilang", 2),
+        ];
+
+        // POSITIVE EMOTIONS (MEDIUM INTENSITY)
+        let positive_medium = vec![
+            ("bersyukur", 2),
+            ("grateful", 2),
+            ("makasih", 1),
+            ("terima kasih", 1),
+            ("thanks", 1),
+            ("asik", 1),
+            ("seru", 1),
+            ("fun", 1),
+            ("bagus", 1),
+            ("baik", 1),
+            ("lumayan", 1),
+            ("ok", 1),
+            ("oke", 1),
+            ("sip", 1),
+        ];
+
+        // NEGATIVE EMOTIONS (HIGH INTENSITY)
+        let negative_high = vec![
+            ("sedih", -3),
+            ("depresi", -3),
+            ("galau", -2),
+            ("takut", -3),
+            ("benci", -3),
+            ("marah", -2),
+            ("putus", -3),
+            ("hilang", -2),
+            ("down", -2),
+            ("nangis", -2),
+            ("kecewa", -2),
+            ("kesal", -2),
+            ("dongkol", -2),
+            ("nyesel", -2),
+            ("menyesal", -2),
+        ];
+
+        // NEGATIVE EMOTIONS (MEDIUM INTENSITY)
+        let negative_medium = vec![
+            ("stress", -2),
+            ("capek", -1),
+            ("lelah", -1),
+            ("bingung", -1),
+            ("khawatir", -1),
+            ("cemas", -1),
+            ("gagal", -2),
+            ("susah", -1),
+            ("sulit", -1),
+            ("jelek", -1),
+            ("buruk", -1),
+        ];
+
+        // CURIOUS/SURPRISED (NEUTRAL-POSITIVE)
+        let curious = vec![
+            ("wow", 2),
+            ("gila", 1),
+            ("astaga", 1),
+            ("serius", 1),
+            ("beneran", 1),
+            ("penasaran", 1),
+            ("tertarik", 1),
+            ("ingin", 1),
+            ("pengin", 1),
+            ("mau", 1),
+            ("tanya", 1),
+            ("kenapa", 0),
+            ("gimana", 0),
+            ("apa", 0),
+        ];
+
+        // Build dictionary
+        for (word, score) in positive_high {
+            dict.insert(
+                word.to_string(),
+                SentimentWord {
+                    base_score: score,
+                    category: "positive_high".to_string(),
+                    intensity_level: 5,
+                },
+            );
+        }
+
+        for (word, score) in positive_medium {
+            dict.insert(
+                word.to_string(),
+                SentimentWord {
+                    base_score: score,
+                    category: "positive_medium".to_string(),
+                    intensity_level: 3,
+                },
+            );
+        }
+
+        for (word, score) in negative_high {
+            dict.insert(
+                word.to_string(),
+                SentimentWord {
+                    base_score: score,
+                    category: "negative_high".to_string(),
+                    intensity_level: 5,
+                },
+            );
+        }
+
+        for (word, score) in negative_medium {
+            dict.insert(
+                word.to_string(),
+                SentimentWord {
+                    base_score: score,
+                    category: "negative_medium".to_string(),
+                    intensity_level: 3,
+                },
+            );
+        }
+
+        for (word, score) in curious {
+            dict.insert(
+                word.to_string(),
+                SentimentWord {
+                    base_score: score,
+                    category: "curious".to_string(),
+                    intensity_level: 2,
+                },
+            );
+        }
+
+        // ========== GEN-Z INDONESIAN SLANG (HIGH IMPACT) ==========
+        let gen_z_slang = vec![
+            // Amazed/Shocked Positive
+            ("bangsat", 2, 3),     // "gila banget, amazed"
+            ("gokil", 2, 3),       // "Amazing, wow"
+            ("buset", 1, 2),       // "Wow, surprised"
+            ("jir", 1, 2),         // "Wow, shocked"
+            ("duh", 1, 1),         // Mild surprise
+            ("wah", 1, 2),         // Surprise/amazement
+
+            // Intensifiers
+            ("sumpah", 1, 2),      // "I swear, emphasis"
+            ("asli", 1, 1),        // "Really, for real"
+            ("demi", 1, 1),        // Emphasis "by"
+
+            // Negative Slang
+            ("tai", -2, 3),        // Harsh negative
+            ("najis", -1, 2),      // Disgusting
+            ("brengsek", -2, 3),   // Harsh negative
+            ("sialan", -2, 3),     // Damn it
+            ("sial", -1, 2),       // Unlucky/bad
+            ("ngeri", -1, 2),      // Scary/worried
+            ("baper", -1, 3),      // Oversensitive/hurt
+            ("jebakan", -1, 2),    // Trap/tricked
+
+            // Casual Approvals
+            ("mantul", 2, 2),      // "Cool, awesome" (slang)
+            ("oke sih", 1, 1),     // Okay fine
+            ("yekan", 2, 2),       // "Yeah alright"
+
+            // Casual Disapprovals
+            ("gitu aja", -1, 1),   // "That's it? Disappointment"
+            ("biasa aja", -1, 1),  // "Just ordinary"
+            ("males", -1, 1),      // Lazy/unmotivated
+            ("bosnan", -1, 1),     // Boring
+        ];
+
+        for (word, score, intensity) in gen_z_slang {
+            dict.insert(
+                word.to_string(),
+                SentimentWord {
+                    base_score: score,
+                    category: "slang_genZ".to_string(),
+                    intensity_level: intensity,
+                },
+            );
+        }
+
+        // ========== CONTEXT-AWARE EXPRESSIONS ==========
+        let context_expressions = vec![
+            // Questions/Curious
+            ("emang", 0, 1),       // "Really? Questioning"
+            ("terus", 0, 1),       // "Then? Continuation"
+            ("gimana sih", 0, 2),  // "How come?" - curious
+            ("kok gitu", 0, 1),    // "Why like that?"
+            ("huh", -1, 1),        // Confused/confused
+
+            // Affirmations
+            ("iyalah", 1, 1),      // "Obviously dude"
+            ("tentu", 1, 1),       // "Of course"
+            ("pastinya", 1, 1),    // "Of course"
+            ("emang ya", 1, 1),    // "Right?"
+        ];
+
+        for (word, score, intensity) in context_expressions {
+            dict.insert(
+                word.to_string(),
+                SentimentWord {
+                    base_score: score,
+                    category: "context_expression".to_string(),
+                    intensity_level: intensity,
+                },
+            );
+        }
+
+        dict
+    }
+
+    // ========== REPLY DICTIONARY BUILDER ==========
+    /// Words MIRA's own replies use. Categories here name the face she makes
+    /// saying them, which isn't the same as the user's feeling behind them.
+    fn build_reply_dictionary() -> HashMap<String, SentimentWord> {
+        let groups: [ReplyGroup; 5] = [
+            (
+                "reply_concern",
+                &[
+                    ("turut sedih", -2, 4),
+                    ("ikut sedih", -2, 4),
+                    ("peluk", -1, 4),
+                    ("sabar ya", -1, 3),
+                    ("kasihan", -1, 3),
+                    ("pelan-pelan", -1, 2),
+                    ("maaf", -1, 2),
+                    ("wajar", -1, 2),
+                    ("berat", -1, 3),
+                    ("semoga", -1, 2),
+                ],
+            ),
+            (
+                "reply_warm",
+                &[
+                    ("hehe", 1, 3),
+                    ("semangat", 2, 4),
+                    ("pasti bisa", 2, 4),
+                    ("gapapa", 1, 3),
+                    ("tenang", 1, 3),
+                    ("aman", 1, 2),
+                    ("sip", 1, 2),
+                    ("makasih", 1, 2),
+                    ("bangga", 2, 4),
+                ],
+            ),
+            (
+                "reply_cheer",
+                &[
+                    ("haha", 2, 4),
+                    ("wkwk", 2, 4),
+                    ("wkwkwk", 2, 4),
+                    ("yeay", 2, 5),
+                    ("hore", 2, 5),
+                    ("asik", 1, 3),
+                    ("seru", 1, 3),
+                    ("keren", 2, 4),
+                    ("mantap", 2, 4),
+                ],
+            ),
+            (
+                "reply_curious",
+                &[
+                    ("hmm", 0, 2),
+                    ("coba", 0, 1),
+                    ("cerita", 0, 2),
+                    ("penasaran", 1, 3),
+                    ("gimana", 0, 2),
+                    ("kenapa", 0, 2),
+                ],
+            ),
+            (
+                "reply_surprise",
+                &[
+                    ("wah", 1, 3),
+                    ("wow", 2, 4),
+                    ("serius", 1, 3),
+                    ("beneran", 1, 3),
+                    ("astaga", 1, 4),
+                ],
+            ),
+        ];
+
+        let mut dict = HashMap::new();
+        for (category, words) in groups {
+            for &(word, score, intensity) in words {
+                dict.insert(
+                    word.to_string(),
+                    SentimentWord {
+                        base_score: score,
+                        category: category.to_string(),
+                        intensity_level: intensity,
+                    },
+                );
+            }
+        }
+        dict
+    }
+
+    fn build_intensifiers() -> HashMap<String, f32> {
+        let mut intensifiers = HashMap::new();
+
+        // Strong intensifiers
+        intensifiers.insert("banget".to_string(), 1.8);
+        intensifiers.insert("bgt".to_string(), 1.8);
+        intensifiers.insert("banget-banget".to_string(), 2.0);
+        intensifiers.insert("sangat".to_string(), 1.7);
+        intensifiers.insert("sekali".to_string(), 1.6);
+        intensifiers.insert("bet".to_string(), 1.5);
+        intensifiers.insert("luar".to_string(), 1.5);
+
+        // Weak intensifiers
+        intensifiers.insert("agak".to_string(), 0.6);
+        intensifiers.insert("sedikit".to_string(), 0.6);
+        intensifiers.insert("lumayan".to_string(), 0.7);
+        intensifiers.insert("cukup".to_string(), 0.7);
+        intensifiers.insert("kurang".to_string(), 0.5);
+        intensifiers.insert("gak terlalu".to_string(), 0.4);
+        intensifiers.insert("nggak terlalu".to_string(), 0.4);
+
+        intensifiers
+    }
+
+    fn build_negations() -> Vec<String> {
+        [
+            "tidak", "nggak", "gak", "bukan", "engga", "enggak", "no", "nope",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect()
+    }
+
+    /// Whether `text` contains a crisis marker as whole words: "mau mati"
+    /// counts, "mau matiin lampu" doesn't.
+    pub fn signals_crisis(&self, text: &str) -> bool {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .collect();
+        self.crisis_markers.iter().any(|marker| {
+            let marker: Vec<&str> = marker.split_whitespace().collect();
+            !marker.is_empty() && words.windows(marker.len()).any(|w| w == marker.as_slice())
+        })
+    }
+}
+
+impl Default for Lexicon {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crisis_markers_match_whole_words() {
+        let lexicon = Lexicon::new();
+        assert!(lexicon.signals_crisis("Aku udah gak kuat lagi, pengen mati aja."));
+        assert!(lexicon.signals_crisis("Nenekku meninggal kemarin"));
+        assert!(lexicon.signals_crisis("BUNUH DIRI"));
+    }
+
+    #[test]
+    fn crisis_markers_ignore_longer_words() {
+        let lexicon = Lexicon::new();
+        assert!(!lexicon.signals_crisis("aku mau matiin lampu"));
+        assert!(!lexicon.signals_crisis("jangan meninggalkan aku"));
+        assert!(!lexicon.signals_crisis("mau matikan komputer dulu"));
+    }
+}
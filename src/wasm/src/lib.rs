@@ -1,9 +1,68 @@
 // src/wasm/src/lib.rs
 use wasm_bindgen::prelude::*;
-use regex::Regex;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::rc::Rc;
 use serde::{Serialize, Deserialize};
 
+mod affect;
+mod baseline;
+mod bubbles;
+mod easing;
+mod emoji;
+mod empathy;
+mod evidence;
+mod exp3;
+mod expressions;
+mod gaze;
+mod idle;
+mod interpolator;
+mod lipsync;
+mod lexicon;
+mod mirror;
+mod mood;
+mod motions;
+mod persona;
+mod profiles;
+mod register;
+mod rng;
+mod scheduler;
+mod seal;
+mod session;
+mod snapshot;
+mod style;
+mod truncate;
+
+use affect::{AffectDynamics, AffectEvent, AffectReport};
+use baseline::SentimentBaseline;
+use bubbles::BubbleOptions;
+use easing::Easing;
+use emoji::{EmojiOutput, EmojiRules};
+use empathy::DisplayedExpression;
+use evidence::{Channel, EmotionRanking, ExpressionEvidence};
+use expressions::ExpressionRegistry;
+pub use gaze::GazeSolver;
+pub use idle::IdleAnimator;
+pub use interpolator::ExpressionInterpolator;
+pub use lipsync::LipSyncTrack;
+use lexicon::Lexicon;
+use mood::{MoodExplanation, MoodRule, MoodTransition, WordHit};
+use motions::MotionTable;
+use persona::PersonaRules;
+use profiles::{Decorations, HumanizeProfiles};
+use register::{Person, Register};
+use rng::Rng;
+use scheduler::{ExpressionRequest, ExpressionScheduler, IdleMode, DEFAULT_FADE_MS};
+use seal::{KdfParams, SealError};
+pub use session::SessionManager;
+use session::SessionState;
+use snapshot::{SessionSnapshot, SNAPSHOT_VERSION};
+use style::{HumanizeReport, StyleEngine, StyleOutput};
+use truncate::TruncateOptions;
+
+/// Moods MIRA can be in; `detect_mood` only ever returns one of these.
+pub const MOODS: [&str; 3] = ["chill", "playful", "reflective"];
+
 #[derive(Serialize, Deserialize, Clone)]
 pub struct SentimentWord {
     pub base_score: i32,
@@ -27,6 +86,17 @@ pub struct SentimentAnalysis {
     pub context_factors: Vec<String>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ExpressionDetection {
+    pub primary: String, // expression ID
+    pub secondary: String,
+    pub primary_emotion: String, // emotion label behind `primary`
+    pub secondary_emotion: String,
+    pub intensity: f32,
+    pub confidence: f32,
+    pub evidence: ExpressionEvidence,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct ExpressionBlend {
     pub primary_expression: String,
@@ -34,313 +104,26 @@ pub struct ExpressionBlend {
     pub blend_strength: f32, // 0.0-1.0
 }
 
+/// One conversation. Cheap to create from a shared lexicon; handles returned
+/// by `SessionManager` share their state with the manager.
 #[wasm_bindgen]
 pub struct MiraCore {
-    sentiment_dict: HashMap<String, SentimentWord>,
-    negation_words: Vec<String>,
-    intensifier_words: HashMap<String, f32>,
-    reflective_keywords: Vec<String>,
-    playful_keywords: Vec<String>,
-    mood_cache: HashMap<String, String>,
-    mood_counts: HashMap<String, u32>,
-    expression_history: Vec<(String, i32)>, // (expression, timestamp)
+    lexicon: Rc<Lexicon>,
+    registry: Rc<RefCell<ExpressionRegistry>>,
+    style: Rc<RefCell<StyleEngine>>,
+    state: Rc<RefCell<SessionState>>,
 }
 
 #[wasm_bindgen]
 impl MiraCore {
     #[wasm_bindgen(constructor)]
     pub fn new() -> Self {
-        let sentiment_dict = Self::build_sentiment_dictionary();
-        let intensifier_words = Self::build_intensifiers();
-        let negation_words = Self::build_negations();
-
-        let mut mood_counts = HashMap::new();
-        mood_counts.insert("chill".to_string(), 0);
-        mood_counts.insert("playful".to_string(), 0);
-        mood_counts.insert("reflective".to_string(), 0);
-
-        Self {
-            sentiment_dict,
-            negation_words,
-            intensifier_words,
-            reflective_keywords: vec![
-                "sedih", "galau", "stress", "capek", "lelah", "bingung", "takut", "khawatir",
-                "depresi", "putus", "gagal", "susah", "kecewa", "marah", "benci", "frustrasi",
-                "kesal", "dongkol", "keenakan", "penakut", "cemas", "resah", "gelisah",
-            ]
-            .iter()
-            .map(|s| s.to_string())
-            .collect(),
-            playful_keywords: vec![
-                "lucu", "haha", "wkwk", "joke", "bercanda", "main", "game", "seru", "asik",
-                "tebak", "cerita", "tantang", "ajari", "ajak", "ajaran", "mainan",
-            ]
-            .iter()
-            .map(|s| s.to_string())
-            .collect(),
-            mood_cache: HashMap::new(),
-            mood_counts,
-            expression_history: Vec::new(),
-        }
-    }
-
-    // ========== SENTIMENT DICTIONARY BUILDER ==========
-    fn build_sentiment_dictionary() -> HashMap<String, SentimentWord> {
-        let mut dict = HashMap::new();
-
-        // POSITIVE EMOTIONS (HIGH INTENSITY)
-        let positive_high = vec![
-            ("senang", 3),
-            ("bahagia", 3),
-            ("gembira", 3),
-            ("cinta", 3),
-            ("love", 3),
-            ("suka", 2),
-            ("menyukai", 2),
-            ("hepi", 3),
-            ("happy", 3),
-            ("mantap", 2),
-            ("keren", 2),
-            ("awesome", 2),
-            ("amazing", 2),
-            ("gemilang", 2),
-        ];
-
-        // POSITIVE EMOTIONS (MEDIUM INTENSITY)
-        let positive_medium = vec![
-            ("bersyukur", 2),
-            ("grateful", 2),
-            ("makasih", 1),
-            ("terima kasih", 1),
-            ("thanks", 1),
-            ("asik", 1),
-            ("seru", 1),
-            ("fun", 1),
-            ("bagus", 1),
-            ("baik", 1),
-            ("lumayan", 1),
-            ("ok", 1),
-            ("oke", 1),
-            ("sip", 1),
-        ];
-
-        // NEGATIVE EMOTIONS (HIGH INTENSITY)
-        let negative_high = vec![
-            ("sedih", -3),
-            ("depresi", -3),
-            ("galau", -2),
-            ("takut", -3),
-            ("benci", -3),
-            ("marah", -2),
-            ("putus", -3),
-            ("hilang", -2),
-            ("down", -2),
-            ("nangis", -2),
-            ("kecewa", -2),
-            ("kesal", -2),
-            ("dongkol", -2),
-            ("nyesel", -2),
-            ("menyesal", -2),
-        ];
-
-        // NEGATIVE EMOTIONS (MEDIUM INTENSITY)
-        let negative_medium = vec![
-            ("stress", -2),
-            ("capek", -1),
-            ("lelah", -1),
-            ("bingung", -1),
-            ("khawatir", -1),
-            ("cemas", -1),
-            ("gagal", -2),
-            ("susah", -1),
-            ("sulit", -1),
-            ("jelek", -1),
-            ("buruk", -1),
-        ];
-
-        // CURIOUS/SURPRISED (NEUTRAL-POSITIVE)
-        let curious = vec![
-            ("wow", 2),
-            ("gila", 1),
-            ("astaga", 1),
-            ("serius", 1),
-            ("beneran", 1),
-            ("penasaran", 1),
-            ("tertarik", 1),
-            ("ingin", 1),
-            ("pengin", 1),
-            ("mau", 1),
-            ("tanya", 1),
-            ("kenapa", 0),
-            ("gimana", 0),
-            ("apa", 0),
-        ];
-
-        // Build dictionary
-        for (word, score) in positive_high {
-            dict.insert(
-                word.to_string(),
-                SentimentWord {
-                    base_score: score,
-                    category: "positive_high".to_string(),
-                    intensity_level: 5,
-                },
-            );
-        }
-
-        for (word, score) in positive_medium {
-            dict.insert(
-                word.to_string(),
-                SentimentWord {
-                    base_score: score,
-                    category: "positive_medium".to_string(),
-                    intensity_level: 3,
-                },
-            );
-        }
-
-        for (word, score) in negative_high {
-            dict.insert(
-                word.to_string(),
-                SentimentWord {
-                    base_score: score,
-                    category: "negative_high".to_string(),
-                    intensity_level: 5,
-                },
-            );
-        }
-
-        for (word, score) in negative_medium {
-            dict.insert(
-                word.to_string(),
-                SentimentWord {
-                    base_score: score,
-                    category: "negative_medium".to_string(),
-                    intensity_level: 3,
-                },
-            );
-        }
-
-        for (word, score) in curious {
-            dict.insert(
-                word.to_string(),
-                SentimentWord {
-                    base_score: score,
-                    category: "curious".to_string(),
-                    intensity_level: 2,
-                },
-            );
-        }
-
-        // ========== GEN-Z INDONESIAN SLANG (HIGH IMPACT) ==========
-        let gen_z_slang = vec![
-            // Amazed/Shocked Positive
-            ("bangsat", 2, 3),     // "gila banget, amazed"
-            ("gokil", 2, 3),       // "Amazing, wow"
-            ("buset", 1, 2),       // "Wow, surprised"
-            ("jir", 1, 2),         // "Wow, shocked"
-            ("duh", 1, 1),         // Mild surprise
-            ("wah", 1, 2),         // Surprise/amazement
-
-            // Intensifiers
-            ("sumpah", 1, 2),      // "I swear, emphasis"
-            ("asli", 1, 1),        // "Really, for real"
-            ("demi", 1, 1),        // Emphasis "by"
-
-            // Negative Slang
-            ("tai", -2, 3),        // Harsh negative
-            ("najis", -1, 2),      // Disgusting
-            ("brengsek", -2, 3),   // Harsh negative
-            ("sialan", -2, 3),     // Damn it
-            ("sial", -1, 2),       // Unlucky/bad
-            ("ngeri", -1, 2),      // Scary/worried
-            ("baper", -1, 3),      // Oversensitive/hurt
-            ("jebakan", -1, 2),    // Trap/tricked
-
-            // Casual Approvals
-            ("mantul", 2, 2),      // "Cool, awesome" (slang)
-            ("oke sih", 1, 1),     // Okay fine
-            ("yekan", 2, 2),       // "Yeah alright"
-
-            // Casual Disapprovals
-            ("gitu aja", -1, 1),   // "That's it? Disappointment"
-            ("biasa aja", -1, 1),  // "Just ordinary"
-            ("males", -1, 1),      // Lazy/unmotivated
-            ("bosnan", -1, 1),     // Boring
-        ];
-
-        for (word, score, intensity) in gen_z_slang {
-            dict.insert(
-                word.to_string(),
-                SentimentWord {
-                    base_score: score,
-                    category: "slang_genZ".to_string(),
-                    intensity_level: intensity,
-                },
-            );
-        }
-
-        // ========== CONTEXT-AWARE EXPRESSIONS ==========
-        let context_expressions = vec![
-            // Questions/Curious
-            ("emang", 0, 1),       // "Really? Questioning"
-            ("terus", 0, 1),       // "Then? Continuation"
-            ("gimana sih", 0, 2),  // "How come?" - curious
-            ("kok gitu", 0, 1),    // "Why like that?"
-            ("huh", -1, 1),        // Confused/confused
-
-            // Affirmations
-            ("iyalah", 1, 1),      // "Obviously dude"
-            ("tentu", 1, 1),       // "Of course"
-            ("pastinya", 1, 1),    // "Of course"
-            ("emang ya", 1, 1),    // "Right?"
-        ];
-
-        for (word, score, intensity) in context_expressions {
-            dict.insert(
-                word.to_string(),
-                SentimentWord {
-                    base_score: score,
-                    category: "context_expression".to_string(),
-                    intensity_level: intensity,
-                },
-            );
-        }
-
-        dict
-    }
-
-    fn build_intensifiers() -> HashMap<String, f32> {
-        let mut intensifiers = HashMap::new();
-
-        // Strong intensifiers
-        intensifiers.insert("banget".to_string(), 1.8);
-        intensifiers.insert("bgt".to_string(), 1.8);
-        intensifiers.insert("banget-banget".to_string(), 2.0);
-        intensifiers.insert("sangat".to_string(), 1.7);
-        intensifiers.insert("sekali".to_string(), 1.6);
-        intensifiers.insert("bet".to_string(), 1.5);
-        intensifiers.insert("luar".to_string(), 1.5);
-
-        // Weak intensifiers
-        intensifiers.insert("agak".to_string(), 0.6);
-        intensifiers.insert("sedikit".to_string(), 0.6);
-        intensifiers.insert("lumayan".to_string(), 0.7);
-        intensifiers.insert("cukup".to_string(), 0.7);
-        intensifiers.insert("kurang".to_string(), 0.5);
-        intensifiers.insert("gak terlalu".to_string(), 0.4);
-        intensifiers.insert("nggak terlalu".to_string(), 0.4);
-
-        intensifiers
-    }
-
-    fn build_negations() -> Vec<String> {
-        vec![
-            "tidak", "nggak", "gak", "bukan", "engga", "enggak", "no", "nope",
-        ]
-        .iter()
-        .map(|s| s.to_string())
-        .collect()
+        Self::from_parts(
+            Rc::new(Lexicon::new()),
+            Rc::new(RefCell::new(ExpressionRegistry::default())),
+            Rc::new(RefCell::new(StyleEngine::default())),
+            Rc::new(RefCell::new(SessionState::new())),
+        )
     }
 
     // ========== PREPROCESSING ==========
@@ -371,65 +154,7 @@ impl MiraCore {
     // ========== ADVANCED SENTIMENT ANALYSIS ==========
     #[wasm_bindgen]
     pub fn calculate_sentiment_advanced(&self, text: &str) -> JsValue {
-        let lower_text = text.to_lowercase();
-        let words: Vec<&str> = lower_text.split_whitespace().collect();
-
-        let mut base_score = 0;
-        let mut final_score = 0;
-        let mut primary_emotion = "neutral".to_string();
-        let mut max_intensity = 0u8;
-        let mut context_factors = Vec::new();
-        let mut negation_active = false;
-        let mut intensifier_multiplier = 1.0f32;
-
-        for (_i, word) in words.iter().enumerate() {
-            // Check for negation
-            if self.negation_words.contains(&word.to_string()) {
-                negation_active = true;
-                context_factors.push(format!("negation: {}", word));
-                continue;
-            }
-
-            // Check for intensifiers
-            if let Some(&multiplier) = self.intensifier_words.get(*word) {
-                intensifier_multiplier = multiplier;
-                context_factors.push(format!("intensifier: {} ({}x)", word, multiplier));
-                continue;
-            }
-
-            // Check sentiment dictionary
-            if let Some(sentiment_word) = self.sentiment_dict.get(*word) {
-                let mut word_score = sentiment_word.base_score as f32 * intensifier_multiplier;
-
-                if negation_active {
-                    word_score = -word_score;
-                    context_factors.push(format!("negated: {}", word));
-                    negation_active = false;
-                }
-
-                base_score += sentiment_word.base_score;
-                final_score += word_score as i32;
-
-                if sentiment_word.intensity_level > max_intensity {
-                    max_intensity = sentiment_word.intensity_level;
-                    primary_emotion = sentiment_word.category.clone();
-                }
-
-                intensifier_multiplier = 1.0;
-            }
-        }
-
-        let intensity = (max_intensity as f32) / 5.0;
-
-        let analysis = SentimentAnalysis {
-            base_score,
-            final_score,
-            primary_emotion,
-            intensity,
-            context_factors,
-        };
-
-        serde_wasm_bindgen::to_value(&analysis).unwrap()
+        serde_wasm_bindgen::to_value(&self.analyze_sentiment(text)).unwrap()
     }
 
     /// Legacy method for backward compatibility
@@ -440,7 +165,7 @@ impl MiraCore {
 
         let mut score = 0;
         for word in words {
-            if let Some(sentiment_word) = self.sentiment_dict.get(word) {
+            if let Some(sentiment_word) = self.lexicon.sentiment_dict.get(word) {
                 score += sentiment_word.base_score;
             }
         }
@@ -452,125 +177,141 @@ impl MiraCore {
     #[wasm_bindgen]
     pub fn detect_mood(&mut self, user_input: &str) -> String {
         let lower_input = user_input.to_lowercase();
+        let explanation = self.decide_mood(&lower_input, &self.state.borrow());
+        let mood = explanation.mood.clone();
+        let crisis = self.lexicon.signals_crisis(user_input);
+
+        let mut state = self.state.borrow_mut();
+        state.crisis = crisis;
+        if !explanation.cached {
+            let was_active = state.baseline.is_active();
+            state.baseline.observe(explanation.raw_score);
+            if state.baseline.is_active() != was_active {
+                // Cached moods were decided on the raw scale
+                state.mood_cache.clear();
+            }
 
-        if let Some(cached_mood) = self.mood_cache.get(&lower_input) {
-            return cached_mood.clone();
-        }
-
-        let sentiment_score = self.calculate_sentiment(&lower_input);
-
-        let mood = if sentiment_score < -4 {
-            "reflective" // Deep negative emotions
-        } else if sentiment_score > 4 {
-            "playful" // Strong positive emotions
-        } else if sentiment_score > 0 {
-            "playful" // Mild positive
-        } else if sentiment_score < -1 {
-            "reflective" // Mild negative
-        } else if self.reflective_keywords.iter().any(|kw| lower_input.contains(kw)) {
-            "reflective"
-        } else if self.playful_keywords.iter().any(|kw| lower_input.contains(kw)) {
-            "playful"
-        } else {
-            "chill"
+            *state.mood_counts.entry(mood.clone()).or_insert(0) += 1;
+            state.mood_cache.insert(lower_input, mood.clone());
         }
-        .to_string();
 
-        *self.mood_counts.entry(mood.clone()).or_insert(0) += 1;
-        self.mood_cache.insert(lower_input, mood.clone());
+        state.last_mood = Some(mood.clone());
+        state.last_explanation = Some(explanation);
 
         mood
     }
 
+    /// Dry run of `detect_mood`: the full decision path for `user_input`
+    /// against the current session, without recording anything.
     #[wasm_bindgen]
-    pub fn get_dominant_mood(&self) -> String {
-        self.mood_counts
-            .iter()
-            .max_by_key(|&(_, count)| count)
-            .map(|(mood, _)| mood.clone())
-            .unwrap_or_else(|| "chill".to_string())
+    pub fn explain_mood(&self, user_input: &str) -> JsValue {
+        let explanation = self.decide_mood(&user_input.to_lowercase(), &self.state.borrow());
+        serde_wasm_bindgen::to_value(&explanation).unwrap()
     }
 
+    /// Decision path of the most recent `detect_mood` call, or null.
     #[wasm_bindgen]
-    pub fn calculate_mood_transition(&self, current_mood: &str, sentiment_score: i32) -> String {
-        match (current_mood, sentiment_score) {
-            ("chill", s) if s < -4 => "reflective".to_string(),
-            ("chill", s) if s > 4 => "playful".to_string(),
-            ("playful", s) if s < -3 => "chill".to_string(),
-            ("reflective", s) if s > 3 => "chill".to_string(),
-            _ => current_mood.to_string(),
-        }
+    pub fn explain_last_mood(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.state.borrow().last_explanation).unwrap()
     }
 
-    // ========== EXPRESSION DETECTION & BLENDING ==========
-#[wasm_bindgen]
-pub fn detect_expression(&self, text: &str) -> String {
-    #[derive(Deserialize)]
-    struct ExpressionDetection {
-        primary: String,
-        secondary: String,
-        intensity: f32,
-        confidence: f32,
-    }
-
-    let result = self.detect_expression_with_intensity(text);
-    let detection: ExpressionDetection = serde_wasm_bindgen::from_value(result).unwrap();
-    detection.primary
-}
-
+    /// Learned sentiment baseline of this user (samples, mean, std dev, warm-up).
+    #[wasm_bindgen]
+    pub fn get_baseline(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.state.borrow().baseline.info()).unwrap()
+    }
 
-    /// Advanced expression detection dengan secondary emotion
     #[wasm_bindgen]
-    pub fn detect_expression_with_intensity(&self, text: &str) -> JsValue {
-        let sentiment_score = self.calculate_sentiment(text);
-        let lower_text = text.to_lowercase();
+    pub fn reset_baseline(&mut self) {
+        let mut state = self.state.borrow_mut();
+        let enabled = state.baseline.enabled;
+        state.baseline = SentimentBaseline::new();
+        state.baseline.enabled = enabled;
+        state.mood_cache.clear();
+    }
 
-        let primary = if sentiment_score >= 3 {
-            "f02" // happy
-        } else if sentiment_score <= -3 {
-            "f03" // sad
-        } else if sentiment_score >= 1 {
-            if lower_text.contains("wow") || lower_text.contains("gila") {
-                "f04" // surprised
-            } else {
-                "f02" // happy (mild)
-            }
-        } else if sentiment_score <= -1 {
-            "f03" // sad (mild)
-        } else if lower_text.contains("bingung") || lower_text.contains("gimana") {
-            "f04" // confused/surprised
-        } else {
-            "f01" // default
+    /// Turn per-user calibration on or off. The learned stats are kept either way.
+    #[wasm_bindgen]
+    pub fn set_baseline_enabled(&mut self, enabled: bool) {
+        let mut state = self.state.borrow_mut();
+        if state.baseline.enabled != enabled {
+            state.baseline.enabled = enabled;
+            state.mood_cache.clear();
         }
-        .to_string();
+    }
 
-        // Determine secondary emotion for blending
-        let secondary = if lower_text.contains("penasaran") || lower_text.contains("tertarik") {
-            "f04".to_string() // curious
-        } else if lower_text.contains("nangis") {
-            "f03".to_string() // sad
-        } else {
-            "f01".to_string() // default
-        };
+    #[wasm_bindgen]
+    pub fn get_dominant_mood(&self) -> String {
+        self.state.borrow().dominant_mood()
+    }
 
-        let intensity = (sentiment_score.abs() as f32 / 5.0).min(1.0);
+    #[wasm_bindgen]
+    pub fn calculate_mood_transition(&self, current_mood: &str, sentiment_score: i32) -> String {
+        mood::transition_rule(current_mood, sentiment_score)
+            .map(|rule| rule.to)
+            .unwrap_or(current_mood)
+            .to_string()
+    }
 
-        #[derive(Serialize)]
-        struct ExpressionDetection {
-            primary: String,
-            secondary: String,
-            intensity: f32,
-            confidence: f32,
-        }
+    // ========== EXPRESSION DETECTION & BLENDING ==========
+    #[wasm_bindgen]
+    pub fn detect_expression(&self, text: &str) -> String {
+        self.expression_detection(text, Channel::User).primary
+    }
 
-        let detection = ExpressionDetection {
-            primary,
-            secondary,
-            intensity,
-            confidence: (1.0 - intensity * 0.2).max(0.6), // Confidence degrades with ambiguity
-        };
+    /// Advanced expression detection dengan secondary emotion. `confidence`
+    /// comes from the lexicon evidence (hits, agreement, margin, negation,
+    /// sarcasm), which is returned under `evidence`.
+    #[wasm_bindgen]
+    pub fn detect_expression_with_intensity(&self, text: &str) -> JsValue {
+        to_js_object(&self.expression_detection(text, Channel::User))
+    }
 
-        serde_wasm_bindgen::to_value(&detection).unwrap()
+    /// Read what the user wrote and remember it as the user channel for
+    /// `resolve_display_expression`.
+    #[wasm_bindgen]
+    pub fn detect_user_emotion(&mut self, text: &str) -> JsValue {
+        let detection = self.expression_detection(text, Channel::User);
+        let result = to_js_object(&detection);
+        let crisis = self.lexicon.signals_crisis(text);
+        let mut state = self.state.borrow_mut();
+        state.user_emotion = Some(detection);
+        state.crisis = crisis;
+        result
+    }
+
+    /// Read MIRA's own reply with the bot-speech lexicon and remember it as
+    /// the reply channel.
+    #[wasm_bindgen]
+    pub fn detect_reply_emotion(&mut self, text: &str) -> JsValue {
+        let detection = self.expression_detection(text, Channel::Reply);
+        let result = to_js_object(&detection);
+        self.state.borrow_mut().reply_emotion = Some(detection);
+        result
+    }
+
+    /// The expression to show, from both channels: the reply's emotion when
+    /// it has a clear one, otherwise MIRA's empathic answer to the user's.
+    /// MIRA's affect scales the intensity.
+    #[wasm_bindgen]
+    pub fn resolve_display_expression(&self) -> JsValue {
+        let state = self.state.borrow();
+        let mut decision =
+            empathy::resolve(state.user_emotion.as_ref(), state.reply_emotion.as_ref());
+        state.affect.shade(&mut decision);
+
+        let registry = self.registry.borrow();
+        to_js_object(&DisplayedExpression {
+            expression: registry.emotion(&decision.emotion).to_string(),
+            secondary: registry.emotion(&decision.secondary_emotion).to_string(),
+            emotion: decision.emotion,
+            secondary_emotion: decision.secondary_emotion,
+            source: decision.source,
+            intensity: decision.intensity,
+            confidence: decision.confidence,
+            user: state.user_emotion.as_ref(),
+            reply: state.reply_emotion.as_ref(),
+        })
     }
 
     /// Map emotion to Live2D expression with blending
@@ -579,69 +320,612 @@ pub fn detect_expression(&self, text: &str) -> String {
         let lower_context = context.to_lowercase();
 
         let (primary, secondary) = match emotion_score {
-            s if s >= 3 => ("f02", "f04"), // happy + surprised blend
-            s if s <= -3 => ("f03", "f01"), // sad + neutral blend
+            s if s >= 3 => ("happy", "surprised"),
+            s if s <= -3 => ("sad", "neutral"),
             s if s > 0 => {
                 if lower_context.contains("joke") || lower_context.contains("game") {
-                    ("f02", "f04")
+                    ("happy", "surprised")
                 } else {
-                    ("f02", "f01")
+                    ("happy", "neutral")
                 }
             }
-            s if s < 0 => ("f03", "f01"),
-            _ => ("f01", "f01"),
+            s if s < 0 => ("sad", "neutral"),
+            _ => ("neutral", "neutral"),
         };
 
         let blend_strength = (emotion_score.abs() as f32 / 5.0).min(1.0);
 
+        let registry = self.registry.borrow();
         let blend = ExpressionBlend {
-            primary_expression: primary.to_string(),
-            secondary_expression: secondary.to_string(),
+            primary_expression: registry.emotion(primary).to_string(),
+            secondary_expression: registry.emotion(secondary).to_string(),
             blend_strength,
         };
 
         serde_wasm_bindgen::to_value(&blend).unwrap()
     }
 
-    // ========== HUMANIZATION ==========
+    // ========== EXPRESSION REGISTRY ==========
+    /// Replace the expression set with the one from a Live2D `model3.json`,
+    /// mapped through `mapping_json` (`{"emotions": {...}, "moods": {...}}`).
+    /// Every mapped ID must exist in the model. Shared with other sessions of
+    /// the same `SessionManager`.
     #[wasm_bindgen]
-    pub fn humanize_response(&self, response: &str) -> String {
-        let mut humanized = response.to_string();
-
-        let replacements = [
-            ("Saya memahami", "Aku ngerti"),
-            ("Saya mengerti", "Iya paham"),
-            ("Saya merasa", "Aku rasa"),
-            ("Apakah ada", "Ada"),
-            ("Terima kasih telah", "Oke sip"),
-            ("Saya akan", "Aku bakal"),
-            ("Saya bisa", "Aku bisa"),
-            ("Maaf jika", "Sorry kalau"),
-            ("Silakan", "Coba"),
-            ("Mohon", "Tolong"),
-        ];
+    pub fn load_expression_registry(
+        &mut self,
+        model3_json: &str,
+        mapping_json: &str,
+    ) -> Result<(), JsError> {
+        let registry = ExpressionRegistry::from_json(model3_json, mapping_json)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        *self.registry.borrow_mut() = registry;
+        Ok(())
+    }
 
-        for (formal, casual) in &replacements {
-            let re = Regex::new(&format!("(?i){}", regex::escape(formal))).unwrap();
-            humanized = re.replace_all(&humanized, *casual).to_string();
-        }
+    #[wasm_bindgen]
+    pub fn get_expression_registry(&self) -> JsValue {
+        to_js_object(&self.registry.borrow().info())
+    }
 
-        let sentences: Vec<&str> = humanized
-            .split(|c| c == '.' || c == '!' || c == '?')
-            .filter(|s| !s.trim().is_empty())
-            .collect();
+    /// Attach a Live2D `exp3.json` to a registered expression ID.
+    #[wasm_bindgen]
+    pub fn load_expression_parameters(&mut self, id: &str, exp3_json: &str) -> Result<(), JsError> {
+        self.registry
+            .borrow_mut()
+            .load_parameters(id, exp3_json)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Resting values of the model's parameters (`{ParamId: value}`); the
+    /// starting point for Overwrite blending. Unset parameters rest at 0.
+    #[wasm_bindgen]
+    pub fn set_parameter_base_values(&mut self, base_values: JsValue) -> Result<(), JsError> {
+        let base_values: HashMap<String, f32> = serde_wasm_bindgen::from_value(base_values)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        self.registry.borrow_mut().set_base_values(base_values);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn expression_parameter_ids(&self) -> Vec<String> {
+        self.registry.borrow().parameter_ids()
+    }
+
+    /// Blend `{expressionId: weight}` (weights 0-1) into `{ParamId: value}`
+    /// using each parameter's Add / Multiply / Overwrite mode.
+    #[wasm_bindgen]
+    pub fn blend_expression_parameters(&self, weights: JsValue) -> Result<JsValue, JsError> {
+        let weights = parse_weights(weights)?;
+        let values = self
+            .registry
+            .borrow()
+            .blend_parameters(&weights)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(to_js_object(&values))
+    }
+
+    /// Same as `blend_expression_parameters`, as a Float32Array laid out like
+    /// `expression_parameter_ids()`. Untouched parameters hold their base value.
+    #[wasm_bindgen]
+    pub fn blend_expression_parameters_array(&self, weights: JsValue) -> Result<Vec<f32>, JsError> {
+        let weights = parse_weights(weights)?;
+        let registry = self.registry.borrow();
+        let values = registry
+            .blend_parameters(&weights)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+
+        Ok(registry
+            .parameter_ids()
+            .iter()
+            .map(|id| values.get(id).copied().unwrap_or_else(|| registry.base_value(id)))
+            .collect())
+    }
+
+    /// Blended parameters `progress` (0-1) of the way from `current` to `target`.
+    #[wasm_bindgen]
+    pub fn interpolate_expression_parameters(
+        &self,
+        current: &str,
+        target: &str,
+        progress: f32,
+    ) -> Result<JsValue, JsError> {
+        let progress = progress.clamp(0.0, 1.0);
+        let mut weights = HashMap::new();
+        weights.insert(current.to_string(), 1.0 - progress);
+        *weights.entry(target.to_string()).or_insert(0.0) += progress;
+
+        let values = self
+            .registry
+            .borrow()
+            .blend_parameters(&weights)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        Ok(to_js_object(&values))
+    }
+
+    #[wasm_bindgen]
+    pub fn list_expressions(&self) -> Vec<String> {
+        self.registry.borrow().ids()
+    }
+
+    #[wasm_bindgen]
+    pub fn expression_for_emotion(&self, emotion: &str) -> Result<String, JsError> {
+        self.registry
+            .borrow()
+            .for_emotion(emotion)
+            .map(str::to_string)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
 
-        if sentences.len() > 3 {
-            humanized = sentences[..3].join(". ") + ".";
+    #[wasm_bindgen]
+    pub fn expression_for_mood(&self, mood: &str) -> Result<String, JsError> {
+        self.registry
+            .borrow()
+            .for_mood(mood)
+            .map(str::to_string)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    #[wasm_bindgen]
+    pub fn has_expression(&self, id: &str) -> bool {
+        self.registry.borrow().contains(id)
+    }
+
+    // ========== EXPRESSION TIMELINE ==========
+    /// Schedule an expression to be held for `hold_ms`. It starts right away
+    /// if the face is idle, or if the current expression is interruptible and
+    /// has a lower priority; otherwise it waits in priority order. Returns
+    /// whether it started immediately.
+    #[wasm_bindgen]
+    pub fn queue_expression(
+        &mut self,
+        id: &str,
+        priority: u8,
+        hold_ms: f64,
+        interruptible: bool,
+        now_ms: f64,
+    ) -> Result<bool, JsError> {
+        if !self.registry.borrow().contains(id) {
+            return Err(JsError::new(&format!("unknown expression '{}'", id)));
+        }
+        if !hold_ms.is_finite() || hold_ms < 0.0 {
+            return Err(JsError::new("hold_ms must be a non-negative number"));
         }
 
-        if js_sys::Math::random() < 0.1 {
-            let fillers = ["hmm... ", "eh iya, ", "btw, ", "oh iya, "];
-            let idx = (js_sys::Math::random() * fillers.len() as f64) as usize;
-            humanized = format!("{}{}", fillers[idx], humanized);
+        let request = ExpressionRequest {
+            id: id.to_string(),
+            priority,
+            hold_ms,
+            fade_ms: DEFAULT_FADE_MS,
+            interruptible,
+        };
+        let registry = self.registry.borrow();
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let idle_id = Self::idle_expression(&registry, state);
+        Ok(state
+            .scheduler
+            .enqueue(request, now_ms, idle_id, &mut state.expression_history))
+    }
+
+    /// Advance the expression timeline and get what to render: the current
+    /// expression, crossfade weights and, when exp3 parameters are loaded,
+    /// the blended parameter values.
+    #[wasm_bindgen]
+    pub fn tick(&mut self, now_ms: f64) -> JsValue {
+        let registry = self.registry.borrow();
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+
+        let idle_id = Self::idle_expression(&registry, state);
+        let mut frame = state
+            .scheduler
+            .tick(now_ms, idle_id, &mut state.expression_history);
+        let weights = frame.weights.iter().map(|(id, w)| (id.clone(), *w)).collect();
+        frame.parameters = registry.blend_parameters(&weights).ok();
+
+        to_js_object(&frame)
+    }
+
+    /// "neutral" rests on the neutral expression, "mood" on the one mapped
+    /// to the current mood.
+    #[wasm_bindgen]
+    pub fn set_idle_mode(&mut self, mode: &str) -> Result<(), JsError> {
+        let mode = IdleMode::parse(mode)
+            .ok_or_else(|| JsError::new(&format!("unknown idle mode '{}'", mode)))?;
+        self.state.borrow_mut().scheduler.idle_mode = mode;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn clear_expression_queue(&mut self) {
+        self.state.borrow_mut().scheduler.clear();
+    }
+
+    /// `[[expressionId, startedAtMs], ...]`, oldest first.
+    #[wasm_bindgen]
+    pub fn get_expression_history(&self) -> JsValue {
+        to_js_object(&self.state.borrow().expression_history)
+    }
+
+    // ========== MOTIONS ==========
+    /// Replace the gesture table (`{"expressions": {...}, "moods": {...}}`,
+    /// each a list of `{group, index, weight, cooldown_ms}`). Shared with
+    /// other sessions of the same `SessionManager`.
+    #[wasm_bindgen]
+    pub fn load_motion_table(&mut self, table_json: &str) -> Result<(), JsError> {
+        let table = MotionTable::from_json(table_json).map_err(|e| JsError::new(&e.to_string()))?;
+        self.registry
+            .borrow_mut()
+            .set_motions(table)
+            .map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    #[wasm_bindgen]
+    pub fn get_motion_table(&self) -> JsValue {
+        to_js_object(self.registry.borrow().motions())
+    }
+
+    /// Pick a gesture for `expression_id` and the current mood: weighted
+    /// random among motions that are off cooldown and weren't played last.
+    /// Returns `{group, index, expression, mood}`, or null when none fit.
+    #[wasm_bindgen]
+    pub fn select_motion(&mut self, expression_id: &str, now_ms: f64) -> Result<JsValue, JsError> {
+        let registry = self.registry.borrow();
+        if !registry.contains(expression_id) {
+            return Err(JsError::new(&format!("unknown expression '{}'", expression_id)));
         }
 
-        humanized.trim().to_string()
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let choice = state.motions.select(
+            registry.motions(),
+            expression_id,
+            state.last_mood.as_deref(),
+            now_ms,
+            state.rng.next_f64(),
+        );
+        Ok(choice.map_or(JsValue::NULL, |c| to_js_object(&c)))
+    }
+
+    #[wasm_bindgen]
+    pub fn reset_motion_cooldowns(&mut self) {
+        self.state.borrow_mut().motions.reset();
+    }
+
+    // ========== AFFECT ==========
+    /// Update MIRA's affect from a user message: every message counts, and
+    /// compliments or insults aimed at her move warmth and rapport. Returns
+    /// `{events, affect}`.
+    #[wasm_bindgen]
+    pub fn observe_user_message(&mut self, text: &str, now_ms: f64) -> JsValue {
+        let events = affect::classify(&self.lexicon, text);
+        self.apply_affect(&events, now_ms)
+    }
+
+    /// Report an event directly: "message", "compliment" or "insult".
+    #[wasm_bindgen]
+    pub fn apply_affect_event(&mut self, event: &str, now_ms: f64) -> Result<JsValue, JsError> {
+        let event = AffectEvent::parse(event)
+            .ok_or_else(|| JsError::new(&format!("unknown affect event '{}'", event)))?;
+        Ok(self.apply_affect(&[event], now_ms))
+    }
+
+    /// `{energy, warmth, rapport, ...}` as of `now_ms`, including the drift
+    /// since the last event. Doesn't change the state.
+    #[wasm_bindgen]
+    pub fn get_affect(&self, now_ms: f64) -> JsValue {
+        let state = self.state.borrow();
+        to_js_object(&state.affect.at(now_ms, &state.affect_dynamics))
+    }
+
+    /// Override the affect dynamics with a (partial) JSON object; missing
+    /// fields keep their defaults.
+    #[wasm_bindgen]
+    pub fn set_affect_dynamics(&mut self, dynamics_json: &str) -> Result<(), JsError> {
+        let dynamics = AffectDynamics::from_json(dynamics_json)
+            .map_err(|e| JsError::new(&format!("invalid affect dynamics: {}", e)))?;
+        self.state.borrow_mut().affect_dynamics = dynamics;
+        Ok(())
+    }
+
+    /// One line describing MIRA's state, for the LLM system prompt.
+    #[wasm_bindgen]
+    pub fn affect_prompt_context(&self, now_ms: f64) -> String {
+        let state = self.state.borrow();
+        state
+            .affect
+            .at(now_ms, &state.affect_dynamics)
+            .prompt_context()
+    }
+
+    // ========== PERSONA ==========
+    /// Clean a raw model reply before humanizing it: strips `<think>`
+    /// blocks, markdown headings and "Sebagai AI" style disclaimers, fixes
+    /// pronouns and length. Returns `{text, compliant, regenerate,
+    /// violations: [{kind, detail, fixed}]}`; `regenerate` is set when a
+    /// problem couldn't be fixed (English reply, nothing left).
+    #[wasm_bindgen]
+    pub fn enforce_persona(&self, response: &str) -> JsValue {
+        let state = self.state.borrow();
+        let pronouns = (
+            state.mirror.person_target(Person::First, state.register),
+            state.mirror.person_target(Person::Second, state.register),
+        );
+        to_js_object(&persona::enforce(
+            &state.persona,
+            &self.lexicon.registers,
+            response,
+            pronouns,
+        ))
+    }
+
+    /// Override the persona rules with a (partial) JSON object:
+    /// `{banned_phrases, max_chars, english_threshold,
+    /// min_words_for_language}`.
+    #[wasm_bindgen]
+    pub fn set_persona_rules(&mut self, rules_json: &str) -> Result<(), JsError> {
+        let rules = PersonaRules::from_json(rules_json).map_err(|e| JsError::new(&e))?;
+        self.state.borrow_mut().persona = rules;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_persona_rules(&self) -> JsValue {
+        to_js_object(&self.state.borrow().persona)
+    }
+
+    // ========== HUMANIZATION ==========
+    #[wasm_bindgen]
+    pub fn humanize_response(&self, response: &str) -> String {
+        self.humanize(response).text
+    }
+
+    /// Same as `humanize_response`, plus which style rules fired, whether it
+    /// was shortened, the profile used and what it added: `{text, fired,
+    /// truncated, profile, filler, particle, laughter, emoji: [{emoji,
+    /// kaomoji, placement, keyword}], emoji_skipped}`.
+    #[wasm_bindgen]
+    pub fn humanize_response_report(&self, response: &str) -> JsValue {
+        to_js_object(&self.humanize(response))
+    }
+
+    /// Replace the humanization profiles (`{"profiles": {"<mood>" or
+    /// "crisis": [{min_intensity, playful, fillers, filler_chance, particles,
+    /// particle_chance, laughter, laughter_chance, emoji_chance,
+    /// max_sentences}]}}`). A "chill" profile is required as the fallback.
+    /// Emoji themselves come from the emoji rules; unknown fields, such as
+    /// an `emoji` list on a tier, are an error.
+    #[wasm_bindgen]
+    pub fn load_humanize_profiles(&mut self, json: &str) -> Result<(), JsError> {
+        let profiles = HumanizeProfiles::from_json(json).map_err(|e| JsError::new(&e))?;
+        self.state.borrow_mut().profiles = profiles;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_humanize_profiles(&self) -> JsValue {
+        to_js_object(&self.state.borrow().profiles)
+    }
+
+    /// Run only the emoji stage of `humanize_response` on `text`: `{text,
+    /// placed: [{emoji, kaomoji, placement, keyword}], skipped}`. Replies
+    /// that read sad or concerned, crisis replies and serious profiles get
+    /// none.
+    #[wasm_bindgen]
+    pub fn inject_emoji(&self, text: &str) -> JsValue {
+        let reply = self.expression_detection(text, Channel::Reply);
+        let mut state = self.state.borrow_mut();
+        let formal = state.mirror.target(state.register) == Register::Formal;
+        to_js_object(&Self::add_emoji(&mut state, text, &reply.primary_emotion, formal))
+    }
+
+    /// Replace the emoji rules (`{emotions: {"<emotion>": {emoji, kaomoji}},
+    /// keywords: [{words, emoji}], moods: {"<mood>": {chance,
+    /// kaomoji_chance, max_per_reply}}, serious_emotions, chars_per_emoji,
+    /// repeat_window}`). "neutral" and "chill" are required as fallbacks.
+    #[wasm_bindgen]
+    pub fn load_emoji_rules(&mut self, json: &str) -> Result<(), JsError> {
+        let rules = EmojiRules::from_json(json).map_err(|e| JsError::new(&e))?;
+        self.state.borrow_mut().emoji = rules;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_emoji_rules(&self) -> JsValue {
+        to_js_object(&self.state.borrow().emoji)
+    }
+
+    /// Register `humanize_response` writes in: "formal", "casual" (the
+    /// default) or "gaul". While mirroring, the user's register wins once
+    /// they have shown one.
+    #[wasm_bindgen]
+    pub fn set_register(&mut self, register: &str) -> Result<(), JsError> {
+        let register = Register::parse(register)
+            .ok_or_else(|| JsError::new(&format!("unknown register '{}'", register)))?;
+        self.state.borrow_mut().register = register;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_register(&self) -> String {
+        self.state.borrow().register.as_str().to_string()
+    }
+
+    /// Learn how the user talks from one of their messages: register,
+    /// pronouns ("aku/kamu", "gue/lo", "saya/anda") and the honorific they
+    /// call MIRA ("kak", "min", "sayang"). Returns the tracked state.
+    #[wasm_bindgen]
+    pub fn observe_user_register(&mut self, text: &str) -> JsValue {
+        let mut state = self.state.borrow_mut();
+        state.mirror.observe(&self.lexicon.registers, text);
+        to_js_object(&state.mirror)
+    }
+
+    #[wasm_bindgen]
+    pub fn get_register_mirror(&self) -> JsValue {
+        to_js_object(&self.state.borrow().mirror)
+    }
+
+    /// Turn mirroring off to always answer in the `set_register` register.
+    #[wasm_bindgen]
+    pub fn set_register_mirroring(&mut self, enabled: bool) {
+        self.state.borrow_mut().mirror.enabled = enabled;
+    }
+
+    /// How formal `text` is: `{register, formality, hits, scores}`, with
+    /// formality from 0 (gaul) to 1 (formal).
+    #[wasm_bindgen]
+    pub fn detect_register(&self, text: &str) -> JsValue {
+        to_js_object(&self.lexicon.registers.detect(text))
+    }
+
+    /// Rewrite `text` into another register (pronouns, verbs, particles and
+    /// spelling), in either direction.
+    #[wasm_bindgen]
+    pub fn convert_register(&self, text: &str, register: &str) -> Result<String, JsError> {
+        let register = Register::parse(register)
+            .ok_or_else(|| JsError::new(&format!("unknown register '{}'", register)))?;
+        Ok(self.lexicon.registers.convert(text, register).text)
+    }
+
+    /// Force the crisis profile on or off. It is also set (and cleared) by
+    /// every `detect_mood` / `detect_user_emotion` call.
+    #[wasm_bindgen]
+    pub fn set_crisis(&mut self, active: bool) {
+        self.state.borrow_mut().crisis = active;
+    }
+
+    #[wasm_bindgen]
+    pub fn is_crisis(&self) -> bool {
+        self.state.borrow().crisis
+    }
+
+    /// Limits for `humanize_response`: sentences (list items and code
+    /// blocks count as one each), characters and approximate tokens. Zero
+    /// means no limit. A tired MIRA keeps one sentence fewer.
+    #[wasm_bindgen]
+    pub fn set_truncation_budget(&mut self, max_sentences: u32, max_chars: u32, max_tokens: u32) {
+        self.state.borrow_mut().truncation =
+            TruncateOptions::from_limits(max_sentences, max_chars, max_tokens);
+    }
+
+    /// Shorten `text` to the given limits (zero = no limit) without cutting
+    /// inside code, links or list items: `{text, truncated, units_kept,
+    /// units_total}`.
+    #[wasm_bindgen]
+    pub fn truncate_response(
+        &self,
+        text: &str,
+        max_sentences: u32,
+        max_chars: u32,
+        max_tokens: u32,
+    ) -> JsValue {
+        let options = TruncateOptions::from_limits(max_sentences, max_chars, max_tokens);
+        to_js_object(&truncate::truncate(text, &options))
+    }
+
+    /// Replace the style rules (`{"rules": [{id, from, to, priority, regex,
+    /// whole_word, case_sensitive}]}`). They are compiled here, once; a bad
+    /// rule set leaves the current one in place.
+    #[wasm_bindgen]
+    pub fn load_style_rules(&mut self, rules_json: &str) -> Result<(), JsError> {
+        let engine = StyleEngine::from_json(rules_json).map_err(|e| JsError::new(&e.to_string()))?;
+        *self.style.borrow_mut() = engine;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_style_rules(&self) -> JsValue {
+        to_js_object(&self.style.borrow().rules())
+    }
+
+    // ========== CHAT BUBBLES ==========
+    /// Split a (humanized) reply into chat bubbles with typing delays:
+    /// `{bubbles: [{text, typing_ms, at_ms}], total_ms, mood, pace}`. Play
+    /// each bubble at its `at_ms`, with the typing indicator on before it.
+    #[wasm_bindgen]
+    pub fn split_bubbles(&self, text: &str) -> JsValue {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let mood = Self::reply_mood(state).to_string();
+        to_js_object(&bubbles::plan(text, &state.bubbles, &mood, &mut state.rng))
+    }
+
+    /// Override the bubble options with a (partial) JSON object:
+    /// `{min_chars, max_chars, max_bubbles, words_per_minute, mood_pace,
+    /// jitter, min_delay_ms, max_delay_ms, pause_ms}`.
+    #[wasm_bindgen]
+    pub fn set_bubble_options(&mut self, options_json: &str) -> Result<(), JsError> {
+        let options = BubbleOptions::from_json(options_json).map_err(|e| JsError::new(&e))?;
+        self.state.borrow_mut().bubbles = options;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn get_bubble_options(&self) -> JsValue {
+        to_js_object(&self.state.borrow().bubbles)
+    }
+
+    // ========== RANDOMNESS ==========
+    /// Reseed the session RNG. Every random choice (humanization, motions,
+    /// riddles, offline replies) goes through it, so the same seed and the
+    /// same calls give the same output. Sessions start from a random seed.
+    #[wasm_bindgen]
+    pub fn set_seed(&mut self, seed: u32) {
+        self.state.borrow_mut().rng = Rng::new(seed);
+    }
+
+    #[wasm_bindgen]
+    pub fn get_seed(&self) -> u32 {
+        self.state.borrow().rng.seed
+    }
+
+    /// `{seed, draws}`; exported snapshots carry the same and resume from
+    /// the exact draw.
+    #[wasm_bindgen]
+    pub fn get_rng_state(&self) -> JsValue {
+        to_js_object(&self.state.borrow().rng)
+    }
+
+    // ========== SESSION STATE ==========
+    /// Serialize the conversation state (moods, cache, history) to a compact,
+    /// versioned JSON snapshot that can be persisted and restored later.
+    #[wasm_bindgen]
+    pub fn export_state(&self) -> String {
+        self.snapshot().to_json()
+    }
+
+    /// Restore a snapshot produced by `export_state`. Older versions are
+    /// migrated; invalid snapshots are rejected and leave the state untouched.
+    #[wasm_bindgen]
+    pub fn import_state(&mut self, json: &str) -> Result<(), JsError> {
+        let snapshot = SessionSnapshot::from_json(json).map_err(|e| JsError::new(&e.to_string()))?;
+        self.restore(snapshot);
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn state_version() -> u32 {
+        SNAPSHOT_VERSION
+    }
+
+    /// Same as `export_state`, but encrypted under `passphrase`
+    /// (Argon2id + ChaCha20-Poly1305).
+    #[wasm_bindgen]
+    pub fn export_sealed_state(&self, passphrase: &str) -> Result<String, JsValue> {
+        seal::seal(&self.export_state(), passphrase, KdfParams::default())
+            .map_err(seal_error_to_js)
+    }
+
+    /// Decrypt and restore a sealed snapshot. Throws `WrongPassphraseError`
+    /// or `TamperedError` (see `error.name`) without touching the state.
+    #[wasm_bindgen]
+    pub fn import_sealed_state(&mut self, sealed: &str, passphrase: &str) -> Result<(), JsValue> {
+        let json = seal::unseal(sealed, passphrase).map_err(seal_error_to_js)?;
+        let snapshot = SessionSnapshot::from_json(&json)
+            .map_err(|e| JsValue::from(JsError::new(&e.to_string())))?;
+        self.restore(snapshot);
+        Ok(())
     }
 
     // ========== UTILITY METHODS ==========
@@ -669,7 +953,7 @@ pub fn detect_expression(&self, text: &str) -> String {
         }
 
         let mut freq_vec: Vec<_> = word_freq.into_iter().collect();
-        freq_vec.sort_by(|a, b| b.1.cmp(&a.1));
+        freq_vec.sort_by_key(|b| std::cmp::Reverse(b.1));
 
         freq_vec
             .into_iter()
@@ -680,7 +964,7 @@ pub fn detect_expression(&self, text: &str) -> String {
 
     #[wasm_bindgen]
     pub fn generate_riddle(&self) -> JsValue {
-        let riddles = vec![
+        let riddles = [
             ("Aku selalu ada di depan, tapi tak pernah jadi yang pertama. Apa aku?", "hidung"),
             ("Aku bulat, bisa nyanyi, tapi bukan penyanyi. Apa aku?", "cd"),
             ("Apa yang naik tapi gak pernah turun?", "umur"),
@@ -688,8 +972,7 @@ pub fn detect_expression(&self, text: &str) -> String {
             ("Dibanting ga marah, malah seneng. Apa itu?", "bola"),
         ];
 
-        let idx = (js_sys::Math::random() * riddles.len() as f64) as usize;
-        let (question, answer) = riddles[idx];
+        let (question, answer) = riddles[self.state.borrow_mut().rng.below(riddles.len())];
 
         #[derive(Serialize)]
         struct Riddle {
@@ -707,7 +990,7 @@ pub fn detect_expression(&self, text: &str) -> String {
 
     #[wasm_bindgen]
     pub fn get_offline_response(&self) -> String {
-        let responses = vec![
+        let responses = [
             "Haha, jaringan lagi lelet nih. Ceritain apa aja dulu deh!",
             "Ups, APIku ngambek. Ngobrol apa lagi ya?",
             "Aduh, offline mode dulu ya. Kamu lagi apa?",
@@ -715,82 +998,448 @@ pub fn detect_expression(&self, text: &str) -> String {
             "Error nih, tapi gas aja cerita. Ntar aku sambung lagi",
         ];
 
-        let idx = (js_sys::Math::random() * responses.len() as f64) as usize;
-        responses[idx].to_string()
+        responses[self.state.borrow_mut().rng.below(responses.len())].to_string()
     }
 
     #[wasm_bindgen]
-    pub fn interpolate_expression(&self, _current: &str, target: &str, _progress: f32) -> String {
-        target.to_string()
+    /// Dominant expression of a crossfade. Use
+    /// `interpolate_expression_parameters` for the actual blended values.
+    pub fn interpolate_expression(&self, current: &str, target: &str, progress: f32) -> String {
+        if progress < 0.5 { current } else { target }.to_string()
     }
 }
 
-// ========== EXPRESSION INTERPOLATION ==========
-#[derive(Serialize, Deserialize, Clone)]
-pub struct ExpressionInterpolator {
-    pub from_expression: String,
-    pub to_expression: String,
-    pub start_time: i64,
-    pub duration_ms: i64,
+impl Default for MiraCore {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl ExpressionInterpolator {
-    pub fn new(from: String, to: String, duration_ms: i64) -> Self {
+impl MiraCore {
+    pub(crate) fn from_parts(
+        lexicon: Rc<Lexicon>,
+        registry: Rc<RefCell<ExpressionRegistry>>,
+        style: Rc<RefCell<StyleEngine>>,
+        state: Rc<RefCell<SessionState>>,
+    ) -> Self {
         Self {
-            from_expression: from,
-            to_expression: to,
-            start_time: js_sys::Date::now() as i64,
-            duration_ms,
+            lexicon,
+            registry,
+            style,
+            state,
         }
     }
 
-    /// Cubic easing function (ease-in-out)
-    fn ease_in_out_cubic(t: f32) -> f32 {
-        if t < 0.5 {
-            4.0 * t * t * t
+    pub(crate) fn analyze_sentiment(&self, text: &str) -> SentimentAnalysis {
+        let lower_text = text.to_lowercase();
+        let words: Vec<&str> = lower_text.split_whitespace().collect();
+
+        let mut base_score = 0;
+        let mut final_score = 0;
+        let mut primary_emotion = "neutral".to_string();
+        let mut max_intensity = 0u8;
+        let mut context_factors = Vec::new();
+        let mut negation_active = false;
+        let mut intensifier_multiplier = 1.0f32;
+
+        for word in words.iter() {
+            // Check for negation
+            if self.lexicon.negation_words.contains(&word.to_string()) {
+                negation_active = true;
+                context_factors.push(format!("negation: {}", word));
+                continue;
+            }
+
+            // Check for intensifiers
+            if let Some(&multiplier) = self.lexicon.intensifier_words.get(*word) {
+                intensifier_multiplier = multiplier;
+                context_factors.push(format!("intensifier: {} ({}x)", word, multiplier));
+                continue;
+            }
+
+            // Check sentiment dictionary
+            if let Some(sentiment_word) = self.lexicon.sentiment_dict.get(*word) {
+                let mut word_score = sentiment_word.base_score as f32 * intensifier_multiplier;
+
+                if negation_active {
+                    word_score = -word_score;
+                    context_factors.push(format!("negated: {}", word));
+                    negation_active = false;
+                }
+
+                base_score += sentiment_word.base_score;
+                final_score += word_score as i32;
+
+                if sentiment_word.intensity_level > max_intensity {
+                    max_intensity = sentiment_word.intensity_level;
+                    primary_emotion = sentiment_word.category.clone();
+                }
+
+                intensifier_multiplier = 1.0;
+            }
+        }
+
+        let intensity = (max_intensity as f32) / 5.0;
+
+        SentimentAnalysis {
+            base_score,
+            final_score,
+            primary_emotion,
+            intensity,
+            context_factors,
+        }
+    }
+
+    /// Primary is the best-supported emotion and secondary the runner-up.
+    /// When the evidence is too thin, falls back to neutral with the weak
+    /// guess kept as secondary.
+    pub(crate) fn expression_detection(&self, text: &str, channel: Channel) -> ExpressionDetection {
+        let ranking = EmotionRanking::gather(&self.lexicon, text, channel);
+        let (primary, secondary) = ranking.labels();
+
+        let registry = self.registry.borrow();
+        ExpressionDetection {
+            primary: registry.emotion(primary).to_string(),
+            secondary: registry.emotion(secondary).to_string(),
+            primary_emotion: primary.to_string(),
+            secondary_emotion: secondary.to_string(),
+            intensity: ranking.intensity(),
+            confidence: ranking.confidence,
+            evidence: ranking.evidence,
+        }
+    }
+
+    /// Shared by `detect_mood` and `explain_mood` so the explanation can't
+    /// drift from the real decision.
+    fn decide_mood(&self, lower_input: &str, state: &SessionState) -> MoodExplanation {
+        let raw_score = self.calculate_sentiment(lower_input);
+        // Judge against what is normal for this user, learned from earlier messages
+        let score = state.baseline.adjust(raw_score);
+
+        let matching = |keywords: &[String]| -> Vec<String> {
+            keywords
+                .iter()
+                .filter(|kw| lower_input.contains(kw.as_str()))
+                .cloned()
+                .collect()
+        };
+        let reflective_hits = matching(&self.lexicon.reflective_keywords);
+        let playful_hits = matching(&self.lexicon.playful_keywords);
+
+        // Strong (> 4 / < -4) and mild (> 0 / < -1) sentiment map to the same moods
+        let (decided, rule) = if score > 0 {
+            ("playful", MoodRule::PositiveScore)
+        } else if score < -1 {
+            ("reflective", MoodRule::NegativeScore)
+        } else if !reflective_hits.is_empty() {
+            ("reflective", MoodRule::ReflectiveKeyword)
+        } else if !playful_hits.is_empty() {
+            ("playful", MoodRule::PlayfulKeyword)
         } else {
-            1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+            ("chill", MoodRule::Default)
+        };
+
+        let cached_mood = state.mood_cache.get(lower_input).cloned();
+        let mood = cached_mood.clone().unwrap_or_else(|| decided.to_string());
+
+        let sentiment_words = lower_input
+            .split_whitespace()
+            .filter_map(|word| {
+                self.lexicon.sentiment_dict.get(word).map(|w| WordHit {
+                    word: word.to_string(),
+                    score: w.base_score,
+                })
+            })
+            .collect();
+
+        // The store follows up with `calculate_mood_transition` on the advanced score
+        let final_score = self.analyze_sentiment(lower_input).final_score;
+        let transition_rule = mood::transition_rule(&mood, final_score);
+        let transition = MoodTransition {
+            rule: transition_rule,
+            score: final_score,
+            result: transition_rule.map(|r| r.to).unwrap_or(&mood).to_string(),
+        };
+
+        MoodExplanation {
+            input: lower_input.to_string(),
+            mood,
+            cached: cached_mood.is_some(),
+            cached_mood,
+            raw_score,
+            score,
+            baseline_applied: state.baseline.is_active(),
+            rule,
+            threshold: rule.threshold(),
+            sentiment_words,
+            matched_keywords: reflective_hits.into_iter().chain(playful_hits).collect(),
+            prior_mood: state.last_mood.clone(),
+            transition,
         }
     }
 
-    pub fn get_progress(&self) -> (String, f32, bool) {
-        let now = js_sys::Date::now() as i64;
-        let elapsed = (now - self.start_time) as f32;
-        let progress = (elapsed / self.duration_ms as f32).min(1.0);
+    /// Style rules, then truncation, then the casual touches of the profile
+    /// for the current mood (or crisis) and the user's emotional intensity,
+    /// and finally the register: the user's when mirroring, else the
+    /// session's. Formal skips the casual steps. The char and token budgets
+    /// are checked again at the end, since everything after the cut adds
+    /// text.
+    fn humanize(&self, response: &str) -> HumanizeReport {
+        let reply = self.expression_detection(response, Channel::Reply);
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let register = state.mirror.target(state.register);
+        let formal = register == Register::Formal;
+        let key = Self::reply_mood(state);
+        let intensity = state.user_emotion.as_ref().map_or(0.0, |d| d.intensity);
+        let (key, profile) = state.profiles.select(key, intensity);
+        let StyleOutput { text, mut fired } = if formal {
+            StyleOutput {
+                text: response.to_string(),
+                fired: Vec::new(),
+            }
+        } else {
+            self.style.borrow().apply(response)
+        };
+
+        let mut budget = state.truncation;
+        let max_sentences = budget
+            .max_sentences
+            .map_or(profile.max_sentences, |n| n.min(profile.max_sentences));
+        budget.max_sentences = Some(state.affect.sentence_budget(max_sentences));
+        let truncation = truncate::truncate(&text, &budget);
+
+        let mut humanized = truncation.text;
+        let added = if formal {
+            Decorations::default()
+        } else {
+            profile.decorate(&mut humanized, state.affect.casualness(), &mut state.rng)
+        };
 
-        let eased_progress = Self::ease_in_out_cubic(progress);
-        let is_complete = progress >= 1.0;
+        let converted = state
+            .mirror
+            .apply(&self.lexicon.registers, &humanized, state.register);
+        fired.extend(converted.fired);
+        let key = key.to_string();
+        let mut emoji = Self::add_emoji(state, &converted.text, &reply.primary_emotion, formal);
+
+        // Decorations, vocatives and emoji come after the cut, so check the
+        // char / token budget once more on what is actually sent
+        let fitted = truncate::truncate(
+            &emoji.text,
+            &TruncateOptions {
+                max_sentences: None,
+                ..state.truncation
+            },
+        );
+        emoji.placed.retain(|p| fitted.text.contains(&p.emoji));
+        HumanizeReport {
+            text: fitted.text,
+            fired,
+            truncated: truncation.truncated || fitted.truncated,
+            register,
+            profile: key,
+            added,
+            emoji: emoji.placed,
+            emoji_skipped: emoji.skipped,
+        }
+    }
 
-        (self.to_expression.clone(), eased_progress, is_complete)
+    /// The emoji stage, skipped for formal replies, a user in crisis and
+    /// profiles that aren't playful (the serious reflective tier). The
+    /// profile's `emoji_chance` stands in for the mood's, and like the other
+    /// profile chances it is scaled by affect.
+    fn add_emoji(state: &mut SessionState, text: &str, emotion: &str, formal: bool) -> EmojiOutput {
+        let mood = Self::reply_mood(state);
+        let intensity = state.user_emotion.as_ref().map_or(0.0, |d| d.intensity);
+        let profile = state.profiles.select(mood, intensity).1;
+        let playful = profile.playful;
+        let mut style = state.emoji.mood(mood);
+        style.chance = (profile.emoji_chance.unwrap_or(style.chance) * state.affect.casualness()).min(1.0);
+        let skip = if formal {
+            Some("formal")
+        } else if state.crisis {
+            Some("crisis")
+        } else if !playful {
+            Some("serious_profile")
+        } else {
+            None
+        };
+        match skip {
+            Some(reason) => EmojiOutput::skipped(text, reason),
+            None => state
+                .emoji
+                .inject(text, emotion, style, &mut state.recent_emoji, &mut state.rng),
+        }
+    }
+
+    /// Expression the timeline rests on, per the idle mode.
+    fn idle_expression<'a>(registry: &'a ExpressionRegistry, state: &SessionState) -> &'a str {
+        match state.scheduler.idle_mode {
+            IdleMode::Neutral => registry.emotion("neutral"),
+            IdleMode::Mood => {
+                let mood = state.last_mood.as_deref().unwrap_or("chill");
+                registry.for_mood(mood).unwrap_or_else(|_| registry.emotion("neutral"))
+            }
+        }
+    }
+
+    /// Mood the reply is shaped for: "crisis" overrides the user's mood.
+    fn reply_mood(state: &SessionState) -> &str {
+        if state.crisis {
+            profiles::CRISIS
+        } else {
+            state.last_mood.as_deref().unwrap_or("chill")
+        }
+    }
+
+    fn apply_affect(&mut self, events: &[AffectEvent], now_ms: f64) -> JsValue {
+        let mut state = self.state.borrow_mut();
+        let state = &mut *state;
+        let applied: Vec<AffectEvent> = events
+            .iter()
+            .flat_map(|&event| state.affect.apply(event, now_ms, &state.affect_dynamics))
+            .collect();
+        to_js_object(&AffectReport {
+            events: applied,
+            affect: &state.affect,
+        })
+    }
+
+    fn snapshot(&self) -> SessionSnapshot {
+        let state = self.state.borrow();
+        SessionSnapshot {
+            version: SNAPSHOT_VERSION,
+            mood_counts: state.mood_counts.clone(),
+            mood_cache: state.mood_cache.clone(),
+            expression_history: state.expression_history.clone(),
+            baseline: state.baseline.clone(),
+            last_mood: state.last_mood.clone(),
+            affect: state.affect.clone(),
+            rng: state.rng,
+            mirror: state.mirror.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: SessionSnapshot) {
+        let mut mood_counts = snapshot.mood_counts;
+        for mood in MOODS {
+            mood_counts.entry(mood.to_string()).or_insert(0);
+        }
+
+        let mut state = self.state.borrow_mut();
+        state.mood_counts = mood_counts;
+        state.mood_cache = snapshot.mood_cache;
+        state.expression_history = snapshot.expression_history;
+        state.scheduler = ExpressionScheduler::new();
+        state.motions.reset();
+        state.baseline = snapshot.baseline;
+        state.last_mood = snapshot.last_mood;
+        state.affect = snapshot.affect;
+        state.rng = snapshot.rng;
+        state.last_explanation = None;
+        state.user_emotion = None;
+        state.reply_emotion = None;
+        state.crisis = false;
+        state.mirror.restore(&self.lexicon.registers, snapshot.mirror);
+        state.recent_emoji.clear();
     }
 }
 
 #[wasm_bindgen]
 impl MiraCore {
-    // Add method baru (di akhir impl block, sebelum closing brace)
     #[wasm_bindgen]
     pub fn create_expression_interpolator(
         &self,
-        from: String,
-        to: String,
-        duration_ms: i64,
-    ) -> JsValue {
-        let interpolator = ExpressionInterpolator::new(from, to, duration_ms);
-        serde_wasm_bindgen::to_value(&interpolator).unwrap()
+        from: &str,
+        to: &str,
+        duration_ms: f64,
+        now_ms: f64,
+    ) -> Result<ExpressionInterpolator, JsError> {
+        let mut interpolator = ExpressionInterpolator::new(from);
+        interpolator.transition_to(to, duration_ms, "cubic-in-out", now_ms)?;
+        Ok(interpolator)
+    }
+
+    /// Idle animator already tuned to the session's current mood and
+    /// MIRA's energy.
+    #[wasm_bindgen]
+    pub fn create_idle_animator(&self, seed: u32, now_ms: f64) -> IdleAnimator {
+        let state = self.state.borrow();
+        let mut animator = IdleAnimator::new(seed, now_ms);
+        if let Some(mood) = &state.last_mood {
+            animator.set_mood(mood).unwrap();
+        }
+        animator.set_energy(state.affect.at(now_ms, &state.affect_dynamics).energy);
+        animator
+    }
+
+    /// Gaze solver already tuned to the session's current mood.
+    #[wasm_bindgen]
+    pub fn create_gaze_solver(&self, canvas_width: f32, canvas_height: f32) -> GazeSolver {
+        let mut solver = GazeSolver::new(canvas_width, canvas_height);
+        if let Some(mood) = &self.state.borrow().last_mood {
+            solver.set_mood(mood).unwrap();
+        }
+        solver
+    }
+
+    /// Ease `progress` (0.0-1.0) with a preset name, `"cubic-bezier(...)"`
+    /// or a JSON spring/elastic spec (see `Easing::parse`).
+    #[wasm_bindgen]
+    pub fn ease(&self, easing: &str, progress: f32) -> Result<f32, JsError> {
+        Easing::parse(easing)
+            .map(|e| e.apply(progress))
+            .map_err(|e| JsError::new(&e))
     }
 
     #[wasm_bindgen]
     pub fn interpolate_expression_smooth(&self, _from: &str, _to: &str, progress: f32) -> f32 {
         // Return eased progress (0.0-1.0)
-        if progress < 0.5 {
-            4.0 * progress * progress * progress
-        } else {
-            1.0 - (-2.0 * progress + 2.0).powi(3) / 2.0
-        }
+        Easing::CubicInOut.apply(progress)
     }
 }
 
 // Standalone functions
+/// Like `serde_wasm_bindgen::to_value`, but maps become plain objects
+/// instead of `Map`s so the frontend can index them directly.
+pub(crate) fn to_js_object<T: Serialize + ?Sized>(value: &T) -> JsValue {
+    value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .unwrap()
+}
+
+fn parse_weights(weights: JsValue) -> Result<HashMap<String, f32>, JsError> {
+    serde_wasm_bindgen::from_value(weights).map_err(|e| JsError::new(&e.to_string()))
+}
+
+fn seal_error_to_js(err: SealError) -> JsValue {
+    let js_err = js_sys::Error::new(&err.to_string());
+    js_err.set_name(err.name());
+    js_err.into()
+}
+
+#[wasm_bindgen]
+pub fn seal_state(state: &str, passphrase: &str) -> Result<String, JsValue> {
+    seal::seal(state, passphrase, KdfParams::default()).map_err(seal_error_to_js)
+}
+
+#[wasm_bindgen]
+pub fn unseal_state(sealed: &str, passphrase: &str) -> Result<String, JsValue> {
+    seal::unseal(sealed, passphrase).map_err(seal_error_to_js)
+}
+
+/// Re-encrypt a sealed snapshot under a new passphrase (fresh salt and nonce).
+#[wasm_bindgen]
+pub fn rotate_sealed_state(
+    sealed: &str,
+    old_passphrase: &str,
+    new_passphrase: &str,
+) -> Result<String, JsValue> {
+    seal::rotate(sealed, old_passphrase, new_passphrase, KdfParams::default())
+        .map_err(seal_error_to_js)
+}
+
 #[wasm_bindgen]
 pub fn quick_mood_check(input: &str) -> String {
     let mut core = MiraCore::new();
@@ -801,4 +1450,80 @@ pub fn quick_mood_check(input: &str) -> String {
 pub fn polish_text(text: &str) -> String {
     let core = MiraCore::new();
     core.humanize_response(text)
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decorated_reply_stays_within_the_char_budget() {
+        let mut core = MiraCore::new();
+        core.set_truncation_budget(0, 48, 0);
+        core.state.borrow_mut().last_mood = Some("playful".to_string());
+        for seed in 0..64 {
+            core.set_seed(seed);
+            let report = core.humanize("Wah seru banget, aku juga suka main game. Nanti kita makan bareng ya!");
+            assert!(report.text.chars().count() <= 48, "{}", report.text);
+        }
+    }
+
+    #[test]
+    fn set_seed_restarts_the_sequence_and_survives_a_snapshot() {
+        let mut core = MiraCore::new();
+        core.set_seed(1234);
+        let first: Vec<String> = (0..6).map(|_| core.get_offline_response()).collect();
+        core.set_seed(1234);
+        let again: Vec<String> = (0..6).map(|_| core.get_offline_response()).collect();
+        assert_eq!(first, again);
+
+        core.set_seed(99);
+        core.get_offline_response();
+        let saved = core.export_state();
+        let expected: Vec<String> = (0..6).map(|_| core.get_offline_response()).collect();
+
+        let mut restored = MiraCore::new();
+        restored.import_state(&saved).unwrap();
+        assert_eq!(restored.get_seed(), 99);
+        let resumed: Vec<String> = (0..6).map(|_| restored.get_offline_response()).collect();
+        assert_eq!(resumed, expected);
+    }
+
+    #[test]
+    fn decision_path_names_the_branch_taken() {
+        let core = MiraCore::new();
+        let decide = |input: &str| core.decide_mood(input, &core.state.borrow());
+
+        let happy = decide("aku senang");
+        assert_eq!((happy.mood.as_str(), happy.rule, happy.raw_score), ("playful", MoodRule::PositiveScore, 3));
+        assert_eq!(happy.sentiment_words, [WordHit { word: "senang".to_string(), score: 3 }]);
+
+        let sad = decide("aku sedih");
+        assert_eq!((sad.mood.as_str(), sad.rule), ("reflective", MoodRule::NegativeScore));
+
+        // -1 is above the negative threshold, so the keyword decides
+        let tired = decide("lagi capek");
+        assert_eq!((tired.score, tired.rule), (-1, MoodRule::ReflectiveKeyword));
+        assert_eq!(tired.matched_keywords, ["capek"]);
+
+        let game = decide("ayo main game");
+        assert_eq!((game.mood.as_str(), game.rule), ("playful", MoodRule::PlayfulKeyword));
+
+        let plain = decide("halo");
+        assert_eq!((plain.mood.as_str(), plain.rule), ("chill", MoodRule::Default));
+    }
+
+    #[test]
+    fn repeated_input_is_served_from_the_cache() {
+        let mut core = MiraCore::new();
+        core.detect_mood("Aku Senang");
+        core.detect_mood("aku senang");
+
+        let state = core.state.borrow();
+        let last = state.last_explanation.as_ref().unwrap();
+        assert!(last.cached);
+        assert_eq!(last.cached_mood.as_deref(), Some("playful"));
+        assert_eq!(last.prior_mood.as_deref(), Some("playful"));
+        assert_eq!(state.mood_counts["playful"], 1);
+        assert_eq!(state.baseline.samples, 1);
+    }
+}
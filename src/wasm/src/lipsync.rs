@@ -0,0 +1,321 @@
+// src/wasm/src/lipsync.rs
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use crate::to_js_object;
+
+/// Typical conversational Indonesian, in syllables per second.
+pub const DEFAULT_SYLLABLES_PER_SECOND: f32 = 5.5;
+
+// Share of one syllable slot given to each segment type
+const VOWEL_SHARE: f32 = 0.6;
+const CONSONANT_SHARE: f32 = 0.2;
+
+// Pauses at the default rate, scaled with the speaking rate
+const CLAUSE_PAUSE_MS: f32 = 220.0;
+const SENTENCE_PAUSE_MS: f32 = 450.0;
+
+/// Time to reach a mouth shape, capped at half the segment.
+const ATTACK_MS: f32 = 45.0;
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Viseme {
+    A,
+    I,
+    U,
+    E,
+    O,
+    Closed,    // m, b, p
+    Consonant, // everything else, mouth slightly open
+    Rest,
+}
+
+impl Viseme {
+    /// (`ParamMouthOpenY`, `ParamMouthForm`). Form +1 spreads into a smile,
+    /// -1 rounds the lips.
+    pub fn params(&self) -> (f32, f32) {
+        match self {
+            Viseme::A => (1.0, 0.0),
+            Viseme::I => (0.3, 1.0),
+            Viseme::U => (0.35, -1.0),
+            Viseme::E => (0.55, 0.6),
+            Viseme::O => (0.75, -0.7),
+            Viseme::Closed => (0.0, 0.0),
+            Viseme::Consonant => (0.2, 0.0),
+            Viseme::Rest => (0.0, 0.0),
+        }
+    }
+
+    fn is_vowel(&self) -> bool {
+        matches!(self, Viseme::A | Viseme::I | Viseme::U | Viseme::E | Viseme::O)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Token {
+    Sound(Viseme),
+    Pause(f32), // ms at the default rate
+    WordBreak,
+}
+
+/// Indonesian spelling is close to phonemic, so graphemes map almost
+/// one-to-one. Digraphs (ng, ny, kh, sy) are single consonants.
+fn tokenize(text: &str) -> Vec<(Token, String)> {
+    let chars: Vec<char> = text.to_lowercase().chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+
+        if let Some(n) = next {
+            let digraph = matches!((c, n), ('n', 'g') | ('n', 'y') | ('k', 'h') | ('s', 'y'));
+            if digraph {
+                tokens.push((Token::Sound(Viseme::Consonant), format!("{}{}", c, n)));
+                i += 2;
+                continue;
+            }
+        }
+
+        let token = match c {
+            'a' => Token::Sound(Viseme::A),
+            'i' | 'y' => Token::Sound(Viseme::I),
+            'u' | 'w' => Token::Sound(Viseme::U),
+            'e' | 'é' | 'è' => Token::Sound(Viseme::E),
+            'o' => Token::Sound(Viseme::O),
+            'm' | 'b' | 'p' => Token::Sound(Viseme::Closed),
+            c if c.is_alphabetic() => Token::Sound(Viseme::Consonant),
+            ',' | ';' | ':' | '-' | '(' | ')' => Token::Pause(CLAUSE_PAUSE_MS),
+            '.' | '!' | '?' | '\n' | '…' => Token::Pause(SENTENCE_PAUSE_MS),
+            c if c.is_whitespace() => Token::WordBreak,
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        tokens.push((token, c.to_string()));
+        i += 1;
+    }
+
+    tokens
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct VisemeSegment {
+    pub viseme: Viseme,
+    pub grapheme: String,
+    pub start_ms: f32,
+    pub duration_ms: f32,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct MouthKeyframe {
+    pub time_ms: f32,
+    pub mouth_open_y: f32,
+    pub mouth_form: f32,
+}
+
+// ========== LIP-SYNC TRACK ==========
+/// Timed `ParamMouthOpenY` / `ParamMouthForm` keyframes for a piece of text,
+/// for lip-sync when there's no TTS audio to analyse.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct LipSyncTrack {
+    segments: Vec<VisemeSegment>,
+    keyframes: Vec<MouthKeyframe>,
+    duration_ms: f32,
+}
+
+#[wasm_bindgen]
+impl LipSyncTrack {
+    /// `syllables_per_second` of 0 or less uses the default rate.
+    #[wasm_bindgen(constructor)]
+    pub fn new(text: &str, syllables_per_second: f32) -> Self {
+        let rate = if syllables_per_second > 0.0 {
+            syllables_per_second
+        } else {
+            DEFAULT_SYLLABLES_PER_SECOND
+        };
+        let syllable_ms = 1000.0 / rate;
+        let pause_scale = DEFAULT_SYLLABLES_PER_SECOND / rate;
+
+        let mut segments: Vec<VisemeSegment> = Vec::new();
+        let mut cursor = 0.0f32;
+
+        for (token, grapheme) in tokenize(text) {
+            let (viseme, duration) = match token {
+                Token::Sound(v) if v.is_vowel() => (v, syllable_ms * VOWEL_SHARE),
+                Token::Sound(v) => (v, syllable_ms * CONSONANT_SHARE),
+                Token::Pause(ms) => (Viseme::Rest, ms * pause_scale),
+                Token::WordBreak => continue,
+            };
+
+            // Same shape twice in a row (e.g. "mb", "aa", ". ") is one segment
+            match segments.last_mut() {
+                Some(last) if last.viseme == viseme => {
+                    last.duration_ms += duration;
+                    last.grapheme.push_str(&grapheme);
+                }
+                _ => segments.push(VisemeSegment {
+                    viseme,
+                    grapheme,
+                    start_ms: cursor,
+                    duration_ms: duration,
+                }),
+            }
+            cursor += duration;
+        }
+
+        // A trailing pause is just silence; don't make the speech longer
+        if segments.last().is_some_and(|s| s.viseme == Viseme::Rest) {
+            let last = segments.pop().unwrap();
+            cursor -= last.duration_ms;
+        }
+
+        let keyframes = Self::build_keyframes(&segments, cursor);
+        Self {
+            segments,
+            keyframes,
+            duration_ms: cursor,
+        }
+    }
+
+    #[wasm_bindgen]
+    pub fn duration_ms(&self) -> f32 {
+        self.duration_ms
+    }
+
+    #[wasm_bindgen]
+    pub fn keyframes(&self) -> JsValue {
+        to_js_object(&self.keyframes)
+    }
+
+    #[wasm_bindgen]
+    pub fn segments(&self) -> JsValue {
+        to_js_object(&self.segments)
+    }
+
+    /// `[ParamMouthOpenY, ParamMouthForm]` at `time_ms` into the track.
+    #[wasm_bindgen]
+    pub fn sample(&self, time_ms: f32) -> Vec<f32> {
+        let (open, form) = self.sample_at(time_ms);
+        vec![open, form]
+    }
+}
+
+impl LipSyncTrack {
+    fn build_keyframes(segments: &[VisemeSegment], end_ms: f32) -> Vec<MouthKeyframe> {
+        let frame = |time_ms: f32, viseme: Viseme| {
+            let (mouth_open_y, mouth_form) = viseme.params();
+            MouthKeyframe {
+                time_ms,
+                mouth_open_y,
+                mouth_form,
+            }
+        };
+
+        let mut keyframes = vec![frame(0.0, Viseme::Rest)];
+        for segment in segments {
+            // Reach the shape shortly after the segment starts, hold it to the end
+            let attack = ATTACK_MS.min(segment.duration_ms / 2.0);
+            keyframes.push(frame(segment.start_ms + attack, segment.viseme));
+            keyframes.push(frame(segment.start_ms + segment.duration_ms, segment.viseme));
+        }
+        keyframes.push(frame(end_ms + ATTACK_MS, Viseme::Rest));
+        keyframes
+    }
+
+    pub fn sample_at(&self, time_ms: f32) -> (f32, f32) {
+        let after = self.keyframes.iter().position(|k| k.time_ms > time_ms);
+        match after {
+            None => {
+                let last = self.keyframes.last().unwrap();
+                (last.mouth_open_y, last.mouth_form)
+            }
+            Some(0) => (0.0, 0.0),
+            Some(i) => {
+                let a = self.keyframes[i - 1];
+                let b = self.keyframes[i];
+                let span = (b.time_ms - a.time_ms).max(f32::EPSILON);
+                let t = ((time_ms - a.time_ms) / span).clamp(0.0, 1.0);
+                let t = t * t * (3.0 - 2.0 * t); // smoothstep
+                (
+                    a.mouth_open_y + (b.mouth_open_y - a.mouth_open_y) * t,
+                    a.mouth_form + (b.mouth_form - a.mouth_form) * t,
+                )
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graphemes(text: &str) -> Vec<String> {
+        tokenize(text).into_iter().map(|(_, g)| g).filter(|g| g != " ").collect()
+    }
+
+    fn shapes(track: &LipSyncTrack) -> Vec<(Viseme, &str)> {
+        track.segments.iter().map(|s| (s.viseme, s.grapheme.as_str())).collect()
+    }
+
+    #[test]
+    fn digraphs_are_one_consonant() {
+        assert_eq!(graphemes("nyanyi"), ["ny", "a", "ny", "i"]);
+        assert_eq!(graphemes("ngantuk"), ["ng", "a", "n", "t", "u", "k"]);
+        assert_eq!(graphemes("khas syukur"), ["kh", "a", "s", "sy", "u", "k", "u", "r"]);
+        assert!(tokenize("ny")
+            .iter()
+            .all(|(token, _)| *token == Token::Sound(Viseme::Consonant)));
+    }
+
+    #[test]
+    fn bilabials_close_the_mouth() {
+        let track = LipSyncTrack::new("apa", 0.0);
+        assert_eq!(shapes(&track), [(Viseme::A, "a"), (Viseme::Closed, "p"), (Viseme::A, "a")]);
+        assert_eq!(Viseme::Closed.params(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn repeated_shapes_merge_into_one_segment() {
+        let track = LipSyncTrack::new("mbaak", 0.0);
+        assert_eq!(
+            shapes(&track),
+            [(Viseme::Closed, "mb"), (Viseme::A, "aa"), (Viseme::Consonant, "k")]
+        );
+        let syllable_ms = 1000.0 / DEFAULT_SYLLABLES_PER_SECOND;
+        assert_eq!(track.segments[1].duration_ms, 2.0 * syllable_ms * VOWEL_SHARE);
+        assert_eq!(track.segments[2].start_ms, syllable_ms * (2.0 * CONSONANT_SHARE + 2.0 * VOWEL_SHARE));
+    }
+
+    #[test]
+    fn pauses_scale_with_the_rate() {
+        let pause = |rate: f32| {
+            let track = LipSyncTrack::new("a, a", rate);
+            track.segments.iter().find(|s| s.viseme == Viseme::Rest).unwrap().duration_ms
+        };
+        assert_eq!(pause(DEFAULT_SYLLABLES_PER_SECOND), CLAUSE_PAUSE_MS);
+        assert_eq!(pause(DEFAULT_SYLLABLES_PER_SECOND * 2.0), CLAUSE_PAUSE_MS / 2.0);
+        assert_eq!(pause(-1.0), CLAUSE_PAUSE_MS);
+    }
+
+    #[test]
+    fn trailing_pause_is_trimmed() {
+        let plain = LipSyncTrack::new("halo", 0.0);
+        let ended = LipSyncTrack::new("halo...", 0.0);
+        assert!((ended.duration_ms() - plain.duration_ms()).abs() < 1e-3);
+        assert_ne!(ended.segments.last().unwrap().viseme, Viseme::Rest);
+    }
+
+    #[test]
+    fn sampling_outside_the_track_rests() {
+        let track = LipSyncTrack::new("a", 0.0);
+        assert_eq!(track.sample_at(-10.0), (0.0, 0.0));
+        assert_eq!(track.sample_at(track.duration_ms() + 1000.0), (0.0, 0.0));
+        // Held at full shape between the attack and the end of the segment
+        assert_eq!(track.sample_at(ATTACK_MS + 10.0), Viseme::A.params());
+    }
+}
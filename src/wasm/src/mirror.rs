@@ -0,0 +1,307 @@
+// src/wasm/src/mirror.rs
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::register::{Person, Register, RegisterModel, RegisterOutput, REGISTERS};
+use crate::style::{verbatim_spans, FiredRule, StyleEngine, StyleRule};
+use crate::truncate;
+
+/// (what the user calls MIRA, what she calls them back, whether it stands
+/// in for "kamu"). "Kak, mau nanya" is answered with "kak" as the pronoun;
+/// "sayang" or "bro" are added as a vocative instead.
+const HONORIFICS: [(&str, &str, bool); 13] = [
+    ("kak", "kak", true),
+    ("kakak", "kak", true),
+    ("ka", "kak", true),
+    ("min", "kak", true),
+    ("mimin", "kak", true),
+    ("mbak", "kak", true),
+    ("sayang", "sayang", false),
+    ("say", "sayang", false),
+    ("beb", "beb", false),
+    ("bro", "bro", false),
+    ("bang", "bro", false),
+    ("sis", "sis", false),
+    ("bestie", "bestie", false),
+];
+
+/// Weight older messages keep at each new one; recent style counts most.
+const DECAY: f32 = 0.7;
+
+/// Replies that go without a vocative after one gets it. "sayang" on every
+/// reply stops sounding affectionate fast.
+const VOCATIVE_GAP: usize = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Honorific {
+    pub heard: String,
+    pub reply: String,
+    pub as_pronoun: bool,
+}
+
+/// An honorific used as a vocative: first or last word, or set off by a
+/// comma. "sayang banget" in the middle of a sentence is not one.
+fn find_honorific(text: &str) -> Option<Honorific> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let mut found = None;
+    for (i, token) in tokens.iter().enumerate() {
+        let word = token.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+        let Some((heard, reply, as_pronoun)) = HONORIFICS.iter().find(|(h, _, _)| *h == word) else {
+            continue;
+        };
+        let vocative = i == 0
+            || i == tokens.len() - 1
+            || token.ends_with([',', '!', '?', '.'])
+            || tokens[i - 1].ends_with(',');
+        if vocative {
+            found = Some(Honorific {
+                heard: heard.to_string(),
+                reply: reply.to_string(),
+                as_pronoun: *as_pronoun,
+            });
+        }
+    }
+    found
+}
+
+// ========== MIRROR ==========
+/// How the user talks, tracked across the session: the register (decayed
+/// evidence per message), the pronoun for each person and the honorific
+/// they address MIRA with.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Mirror {
+    pub enabled: bool,
+    pub scores: HashMap<Register, f32>,
+    pub first_person: Option<Register>,
+    pub second_person: Option<Register>,
+    pub honorific: Option<Honorific>,
+    pub vocative_cooldown: usize, // replies left before the next vocative
+    #[serde(skip)]
+    address: Option<StyleEngine>, // "kamu"/"anda"/"lo" -> the honorific
+}
+
+impl Mirror {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            scores: REGISTERS.iter().map(|&r| (r, 0.0)).collect(),
+            first_person: None,
+            second_person: None,
+            honorific: None,
+            vocative_cooldown: 0,
+            address: None,
+        }
+    }
+
+    /// Take over what `restored` learned about the user, e.g. from a
+    /// session snapshot. Whether mirroring is on stays as it is.
+    pub fn restore(&mut self, model: &RegisterModel, restored: Mirror) {
+        *self = Self {
+            enabled: self.enabled,
+            address: restored.honorific.as_ref().and_then(|h| address_engine(model, h)),
+            ..restored
+        };
+    }
+
+    /// Every register scored with a finite, non-negative value.
+    pub fn is_valid(&self) -> bool {
+        REGISTERS
+            .iter()
+            .all(|r| self.scores.get(r).is_some_and(|s| s.is_finite() && *s >= 0.0))
+    }
+
+    pub fn observe(&mut self, model: &RegisterModel, text: &str) {
+        let detected = model.detect(text);
+        for (register, score) in self.scores.iter_mut() {
+            *score = *score * DECAY + detected.scores[register] as f32;
+        }
+
+        let (first, second) = model.detect_pronouns(text);
+        self.first_person = first.or(self.first_person);
+        self.second_person = second.or(self.second_person);
+
+        if let Some(honorific) = find_honorific(text) {
+            if self.honorific.as_ref() != Some(&honorific) {
+                self.address = address_engine(model, &honorific);
+                self.honorific = Some(honorific);
+            }
+        }
+    }
+
+    /// The user's register, once there is any evidence. Ties go to casual.
+    pub fn register(&self) -> Option<Register> {
+        let best = [Register::Formal, Register::Gaul, Register::Casual]
+            .into_iter()
+            .max_by(|a, b| self.scores[a].total_cmp(&self.scores[b]))?;
+        (self.scores[&best] > 0.0).then_some(best)
+    }
+
+    /// Register replies are written in: the user's when mirroring, else
+    /// `fallback`.
+    pub fn target(&self, fallback: Register) -> Register {
+        if self.enabled {
+            self.register().unwrap_or(fallback)
+        } else {
+            fallback
+        }
+    }
+
+    /// Register for `person`'s pronouns in replies: the user's own choice
+    /// for that person when mirroring, else `target(fallback)`.
+    pub fn person_target(&self, person: Person, fallback: Register) -> Register {
+        let heard = match person {
+            Person::First => self.first_person,
+            Person::Second => self.second_person,
+        };
+        heard
+            .filter(|_| self.enabled)
+            .unwrap_or_else(|| self.target(fallback))
+    }
+
+    /// Rewrite a reply to match: register, then each person's pronouns,
+    /// then the honorific. A vocative honorific is added at most once every
+    /// `VOCATIVE_GAP + 1` replies. Code and quotations are left alone.
+    pub fn apply(&mut self, model: &RegisterModel, text: &str, fallback: Register) -> RegisterOutput {
+        let mut output = model.convert(text, self.target(fallback));
+        if !self.enabled {
+            return output;
+        }
+
+        // MIRA's "I" follows the user's "I", her "you" the user's "you"
+        let persons = [
+            (Person::First, self.first_person),
+            (Person::Second, self.second_person),
+        ];
+        for (person, register) in persons {
+            if let Some(register) = register {
+                let converted = model.convert_person(&output.text, person, register);
+                output.text = converted.text;
+                output.fired.extend(converted.fired);
+            }
+        }
+
+        match (&self.honorific, &self.address) {
+            (Some(_), Some(address)) => {
+                let converted = address.apply(&output.text);
+                output.text = converted.text;
+                output.fired.extend(converted.fired);
+            }
+            (Some(_), None) if self.vocative_cooldown > 0 => self.vocative_cooldown -= 1,
+            (Some(honorific), None) if add_vocative(&mut output.text, &honorific.reply) => {
+                output.fired.push(FiredRule {
+                    id: format!("vocative:{}", honorific.reply),
+                    count: 1,
+                });
+                self.vocative_cooldown = VOCATIVE_GAP;
+            }
+            _ => {}
+        }
+        output
+    }
+}
+
+/// `address` is compiled from `honorific`, so it is left out.
+impl PartialEq for Mirror {
+    fn eq(&self, other: &Self) -> bool {
+        self.enabled == other.enabled
+            && self.scores == other.scores
+            && self.first_person == other.first_person
+            && self.second_person == other.second_person
+            && self.honorific == other.honorific
+            && self.vocative_cooldown == other.vocative_cooldown
+    }
+}
+
+impl Default for Mirror {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Rewrites the second-person pronouns to an honorific that stands in for
+/// "kamu"; `None` for one that is only used as a vocative.
+fn address_engine(model: &RegisterModel, honorific: &Honorific) -> Option<StyleEngine> {
+    honorific.as_pronoun.then(|| {
+        let rules = model
+            .pronoun_words(Person::Second)
+            .into_iter()
+            .map(|word| StyleRule {
+                id: format!("address:{}", word),
+                from: word.to_string(),
+                to: honorific.reply.clone(),
+                priority: 0,
+                regex: false,
+                whole_word: true,
+                case_sensitive: false,
+            })
+            .collect();
+        StyleEngine::compile(rules).unwrap()
+    })
+}
+
+/// "Iya, aku ngerti." -> "Iya, aku ngerti, sayang." at the end of the first
+/// sentence, unless the reply already uses the word.
+fn add_vocative(text: &mut String, vocative: &str) -> bool {
+    let already = text
+        .split_whitespace()
+        .any(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).eq_ignore_ascii_case(vocative));
+    if already {
+        return false;
+    }
+    let Some(range) = truncate::sentence_ranges(text).into_iter().next() else {
+        return false;
+    };
+    let Some(end) = truncate::last_word_end(&text[range.clone()]) else {
+        return false;
+    };
+    let at = range.start + end;
+    if verbatim_spans(text).iter().any(|span| span.start < at && at < span.end) {
+        return false;
+    }
+    text.insert_str(at, &format!(", {}", vocative));
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn vocative_is_not_added_to_every_reply() {
+        let model = RegisterModel::default();
+        let mut mirror = Mirror::new();
+        mirror.observe(&model, "makasih ya, sayang");
+
+        let with_vocative: Vec<bool> = (0..8)
+            .map(|_| {
+                let reply = mirror.apply(&model, "Iya, aku ngerti.", Register::Casual);
+                reply.text.contains("sayang")
+            })
+            .collect();
+        assert_eq!(
+            with_vocative,
+            [true, false, false, false, true, false, false, false]
+        );
+    }
+
+    #[test]
+    fn restore_keeps_what_was_learned() {
+        let model = RegisterModel::default();
+        let mut mirror = Mirror::new();
+        mirror.observe(&model, "kak, gue mau nanya dong");
+        mirror.observe(&model, "lo tau ga caranya?");
+        let json = serde_json::to_string(&mirror).unwrap();
+
+        let mut restored = Mirror::new();
+        restored.restore(&model, serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.scores, mirror.scores);
+        assert_eq!(restored.honorific, mirror.honorific);
+
+        let reply = "Aku bisa bantu kamu kok.";
+        assert_eq!(
+            restored.apply(&model, reply, Register::Casual),
+            mirror.apply(&model, reply, Register::Casual)
+        );
+        assert!(restored.apply(&model, reply, Register::Casual).text.contains("kak"));
+    }
+}
@@ -0,0 +1,121 @@
+// src/wasm/src/mood.rs
+use serde::Serialize;
+
+/// Which branch of `detect_mood` produced the mood.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MoodRule {
+    PositiveScore,
+    NegativeScore,
+    ReflectiveKeyword,
+    PlayfulKeyword,
+    Default,
+}
+
+impl MoodRule {
+    /// The threshold the score crossed, for the score-based branches.
+    pub fn threshold(&self) -> Option<&'static str> {
+        match self {
+            MoodRule::PositiveScore => Some("score > 0"),
+            MoodRule::NegativeScore => Some("score < -1"),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+pub struct TransitionRule {
+    pub from: &'static str,
+    pub to: &'static str,
+    pub condition: &'static str,
+}
+
+type ScoreCheck = fn(i32) -> bool;
+
+/// Rules behind `calculate_mood_transition`, checked in order.
+const TRANSITIONS: [(TransitionRule, ScoreCheck); 4] = [
+    (
+        TransitionRule { from: "chill", to: "reflective", condition: "score < -4" },
+        |s| s < -4,
+    ),
+    (
+        TransitionRule { from: "chill", to: "playful", condition: "score > 4" },
+        |s| s > 4,
+    ),
+    (
+        TransitionRule { from: "playful", to: "chill", condition: "score < -3" },
+        |s| s < -3,
+    ),
+    (
+        TransitionRule { from: "reflective", to: "chill", condition: "score > 3" },
+        |s| s > 3,
+    ),
+];
+
+pub fn transition_rule(current_mood: &str, sentiment_score: i32) -> Option<TransitionRule> {
+    TRANSITIONS
+        .iter()
+        .find(|(rule, applies)| rule.from == current_mood && applies(sentiment_score))
+        .map(|(rule, _)| *rule)
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct WordHit {
+    pub word: String,
+    pub score: i32,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MoodTransition {
+    pub rule: Option<TransitionRule>,
+    pub score: i32, // final_score from the advanced analysis
+    pub result: String,
+}
+
+/// Full decision path of one `detect_mood` call.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MoodExplanation {
+    pub input: String,
+    pub mood: String,
+    pub cached: bool,
+    pub cached_mood: Option<String>,
+    pub raw_score: i32,
+    pub score: i32, // after per-user baseline adjustment
+    pub baseline_applied: bool,
+    pub rule: MoodRule,
+    pub threshold: Option<&'static str>,
+    pub sentiment_words: Vec<WordHit>,
+    pub matched_keywords: Vec<String>,
+    pub prior_mood: Option<String>,
+    pub transition: MoodTransition,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to(mood: &str, score: i32) -> Option<&'static str> {
+        transition_rule(mood, score).map(|rule| rule.to)
+    }
+
+    #[test]
+    fn transitions_fire_past_their_thresholds_only() {
+        assert_eq!(to("chill", -5), Some("reflective"));
+        assert_eq!(to("chill", -4), None);
+        assert_eq!(to("chill", 5), Some("playful"));
+        assert_eq!(to("chill", 4), None);
+        assert_eq!(to("playful", -4), Some("chill"));
+        assert_eq!(to("playful", 10), None);
+        assert_eq!(to("reflective", 4), Some("chill"));
+        assert_eq!(to("reflective", -10), None);
+        assert_eq!(to("unknown", 10), None);
+    }
+
+    #[test]
+    fn only_score_rules_report_a_threshold() {
+        assert_eq!(MoodRule::PositiveScore.threshold(), Some("score > 0"));
+        assert_eq!(MoodRule::NegativeScore.threshold(), Some("score < -1"));
+        assert_eq!(MoodRule::ReflectiveKeyword.threshold(), None);
+        assert_eq!(MoodRule::Default.threshold(), None);
+    }
+}
@@ -0,0 +1,229 @@
+// src/wasm/src/motions.rs
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+use crate::expressions::RegistryError;
+
+/// Used when a table entry doesn't set `cooldown_ms`.
+const DEFAULT_COOLDOWN_MS: f64 = 4000.0;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MotionEntry {
+    pub group: String,
+    #[serde(default)]
+    pub index: u32,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+    #[serde(default = "default_cooldown")]
+    pub cooldown_ms: f64,
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+fn default_cooldown() -> f64 {
+    DEFAULT_COOLDOWN_MS
+}
+
+impl MotionEntry {
+    fn new(group: &str, index: u32, weight: f32) -> Self {
+        Self {
+            group: group.to_string(),
+            index,
+            weight,
+            cooldown_ms: DEFAULT_COOLDOWN_MS,
+        }
+    }
+
+    fn key(&self) -> (String, u32) {
+        (self.group.clone(), self.index)
+    }
+}
+
+/// `{ "expressions": { "f02": [{ "group": "tap_body", "index": 0,
+/// "weight": 0.7, "cooldown_ms": 4000 }] }, "moods": { "playful": [...] } }`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct MotionTable {
+    #[serde(default)]
+    pub expressions: BTreeMap<String, Vec<MotionEntry>>,
+    #[serde(default)]
+    pub moods: BTreeMap<String, Vec<MotionEntry>>,
+}
+
+impl MotionTable {
+    /// Gestures for the bundled Shizuku expressions (f01-f04).
+    pub fn shizuku() -> Self {
+        let expressions = [
+            ("f01", vec![MotionEntry::new("idle", 0, 1.0)]),
+            (
+                "f02",
+                vec![MotionEntry::new("tap_body", 0, 0.7), MotionEntry::new("idle", 0, 0.3)],
+            ),
+            ("f03", vec![MotionEntry::new("idle", 0, 1.0)]),
+            (
+                "f04",
+                vec![MotionEntry::new("flick_head", 0, 0.6), MotionEntry::new("shake", 0, 0.4)],
+            ),
+        ];
+
+        Self {
+            expressions: expressions
+                .into_iter()
+                .map(|(id, entries)| (id.to_string(), entries))
+                .collect(),
+            moods: BTreeMap::new(),
+        }
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, RegistryError> {
+        let table: MotionTable = serde_json::from_str(json)
+            .map_err(|e| RegistryError::Malformed(format!("motion table: {}", e)))?;
+
+        let entries = table.expressions.values().chain(table.moods.values()).flatten();
+        for entry in entries {
+            if !entry.weight.is_finite() || entry.weight < 0.0 {
+                return Err(RegistryError::Malformed(format!(
+                    "motion table: weight of {}[{}] must be a non-negative number",
+                    entry.group, entry.index
+                )));
+            }
+            if !entry.cooldown_ms.is_finite() || entry.cooldown_ms < 0.0 {
+                return Err(RegistryError::Malformed(format!(
+                    "motion table: cooldown_ms of {}[{}] must be a non-negative number",
+                    entry.group, entry.index
+                )));
+            }
+        }
+        Ok(table)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &MotionEntry> {
+        self.expressions.values().chain(self.moods.values()).flatten()
+    }
+
+    /// Entries for `expression` followed by those for `mood`. The same motion
+    /// listed under both counts once, with the weights added.
+    fn candidates(&self, expression: &str, mood: Option<&str>) -> Vec<MotionEntry> {
+        let by_expression = self.expressions.get(expression).into_iter().flatten();
+        let by_mood = mood.and_then(|m| self.moods.get(m)).into_iter().flatten();
+
+        let mut merged: Vec<MotionEntry> = Vec::new();
+        for entry in by_expression.chain(by_mood) {
+            match merged.iter_mut().find(|m| m.key() == entry.key()) {
+                Some(existing) => {
+                    existing.weight += entry.weight;
+                    existing.cooldown_ms = existing.cooldown_ms.max(entry.cooldown_ms);
+                }
+                None => merged.push(entry.clone()),
+            }
+        }
+        merged
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MotionChoice {
+    pub group: String,
+    pub index: u32,
+    pub expression: String,
+    pub mood: Option<String>,
+}
+
+// ========== MOTION SELECTION ==========
+/// Per-session memory of what was played, so cooldowns and the no-repeat
+/// rule hold across calls.
+#[derive(Clone, Debug, Default)]
+pub struct MotionSelector {
+    last: Option<(String, u32)>,
+    ready_at: HashMap<(String, u32), f64>,
+}
+
+impl MotionSelector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Weighted pick among motions for `expression` (and `mood`) that are off
+    /// cooldown and weren't the last one played; the last one is only
+    /// repeated when nothing else is ready. `roll` is a uniform number in
+    /// 0..1. Returns `None` when nothing is eligible.
+    pub fn select(
+        &mut self,
+        table: &MotionTable,
+        expression: &str,
+        mood: Option<&str>,
+        now_ms: f64,
+        roll: f64,
+    ) -> Option<MotionChoice> {
+        let mut eligible: Vec<MotionEntry> = table
+            .candidates(expression, mood)
+            .into_iter()
+            .filter(|entry| entry.weight > 0.0)
+            .filter(|entry| self.ready_at.get(&entry.key()).is_none_or(|&t| t <= now_ms))
+            .collect();
+        if eligible.iter().any(|entry| self.last.as_ref() != Some(&entry.key())) {
+            eligible.retain(|entry| self.last.as_ref() != Some(&entry.key()));
+        }
+
+        let total: f64 = eligible.iter().map(|e| e.weight as f64).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut target = roll.clamp(0.0, 1.0) * total;
+        let picked = eligible
+            .iter()
+            .find(|entry| {
+                target -= entry.weight as f64;
+                target < 0.0
+            })
+            .unwrap_or_else(|| eligible.last().unwrap());
+
+        self.last = Some(picked.key());
+        self.ready_at.insert(picked.key(), now_ms + picked.cooldown_ms);
+
+        Some(MotionChoice {
+            group: picked.group.clone(),
+            index: picked.index,
+            expression: expression.to_string(),
+            mood: mood.map(str::to_string),
+        })
+    }
+
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.ready_at.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_motion_replays_once_off_cooldown() {
+        let table = MotionTable::shizuku();
+        let mut selector = MotionSelector::new();
+
+        let first = selector.select(&table, "f01", None, 0.0, 0.5);
+        assert_eq!(first.as_ref().map(|c| c.group.as_str()), Some("idle"));
+        assert_eq!(selector.select(&table, "f01", None, 1000.0, 0.5), None, "still cooling down");
+        let again = selector.select(&table, "f01", None, DEFAULT_COOLDOWN_MS, 0.5);
+        assert_eq!(again, first);
+    }
+
+    #[test]
+    fn last_motion_is_skipped_while_another_is_ready() {
+        let table = MotionTable::shizuku();
+        let mut selector = MotionSelector::new();
+
+        let first = selector.select(&table, "f04", None, 0.0, 0.0).unwrap();
+        let later = DEFAULT_COOLDOWN_MS * 2.0;
+        for roll in [0.0, 0.5, 1.0] {
+            let mut copy = selector.clone();
+            let next = copy.select(&table, "f04", None, later, roll).unwrap();
+            assert_ne!((next.group, next.index), (first.group.clone(), first.index));
+        }
+    }
+}
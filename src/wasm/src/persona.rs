@@ -0,0 +1,372 @@
+// src/wasm/src/persona.rs
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use crate::register::{Person, Register, RegisterModel};
+use crate::style::verbatim_spans;
+use crate::truncate::{self, TruncateOptions};
+
+/// Common English function words; Indonesian replies barely use any.
+const ENGLISH_WORDS: [&str; 40] = [
+    "the", "and", "is", "are", "was", "were", "you", "your", "i", "i'm", "me", "my", "we",
+    "they", "it", "it's", "to", "of", "that", "this", "for", "with", "what", "have", "has",
+    "be", "can", "do", "don't", "not", "but", "so", "how", "about", "will", "would", "if",
+    "or", "there", "here",
+];
+
+/// What MIRA must and mustn't say, and how long and how English a reply
+/// may get before it is cut or sent back.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct PersonaRules {
+    /// Lowercase phrases MIRA never says ("sebagai AI"). A leading clause
+    /// that contains one is cut; otherwise the whole sentence goes.
+    pub banned_phrases: Vec<String>,
+    /// 0 means no limit.
+    pub max_chars: usize,
+    /// Share of English function words from which a reply counts as
+    /// English, once it has `min_words_for_language` words.
+    pub english_threshold: f32,
+    pub min_words_for_language: usize,
+}
+
+impl Default for PersonaRules {
+    fn default() -> Self {
+        Self {
+            banned_phrases: [
+                "as an ai", "as a language model", "i'm just an ai", "i am an ai",
+                "large language model", "sebagai ai", "sebagai model bahasa",
+                "sebagai asisten ai", "sebagai kecerdasan buatan", "saya adalah ai",
+                "aku hanyalah ai", "aku cuma ai", "model bahasa", "openai", "chatgpt",
+            ]
+            .iter()
+            .map(|s| s.to_string())
+            .collect(),
+            max_chars: 600,
+            english_threshold: 0.2,
+            min_words_for_language: 6,
+        }
+    }
+}
+
+impl PersonaRules {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let mut rules: PersonaRules = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        if !(0.0..=1.0).contains(&rules.english_threshold) {
+            return Err("english_threshold must be between 0 and 1".to_string());
+        }
+        rules.banned_phrases = rules
+            .banned_phrases
+            .iter()
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| !p.is_empty())
+            .collect();
+        Ok(rules)
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationKind {
+    ThinkBlock,
+    MarkdownHeading,
+    BannedPhrase,
+    Pronoun,
+    English,
+    TooLong,
+    Empty,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    pub detail: String,
+    /// False when cleanup couldn't repair it; the reply should be
+    /// regenerated.
+    pub fixed: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ComplianceReport {
+    pub text: String,
+    pub compliant: bool,
+    pub regenerate: bool,
+    pub violations: Vec<Violation>,
+}
+
+// ========== CLEANUP STAGES ==========
+/// Remove `<think>` blocks, including one the model never closed.
+fn strip_think(text: &str) -> (String, usize) {
+    static THINK: OnceLock<Regex> = OnceLock::new();
+    let re = THINK.get_or_init(|| Regex::new(r"(?is)<think>.*?(?:</think>|\z)").unwrap());
+    let count = re.find_iter(text).count();
+    (re.replace_all(text, "").into_owned(), count)
+}
+
+/// "## Tips" -> "Tips" as a paragraph of its own, outside code fences.
+fn strip_headings(text: &str) -> (String, usize) {
+    static HEADING: OnceLock<Regex> = OnceLock::new();
+    let re = HEADING.get_or_init(|| Regex::new(r"^[ \t]*#{1,6}[ \t]+").unwrap());
+
+    let mut output = String::with_capacity(text.len());
+    let mut in_fence = false;
+    let mut count = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        match re.find(line) {
+            Some(m) if !in_fence => {
+                output.push_str(&line[m.end()..]);
+                if line.ends_with('\n') {
+                    output.push('\n');
+                }
+                count += 1;
+            }
+            _ => output.push_str(line),
+        }
+    }
+    (output, count)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Byte offset of `phrase` in `text`, ignoring case. Searched on `text`
+/// itself: lowercasing can change byte lengths, so offsets into a
+/// lowercased copy don't line up with the original.
+fn find_phrase(text: &str, phrase: &str) -> Option<usize> {
+    Regex::new(&format!("(?i){}", regex::escape(phrase)))
+        .unwrap()
+        .find(text)
+        .map(|m| m.start())
+}
+
+/// Drop banned phrases: "Sebagai AI, aku ngerti." -> "Aku ngerti.", and
+/// any other sentence with one in it goes entirely.
+fn strip_banned(text: &str, banned: &[String]) -> (String, Vec<String>) {
+    let mut edits: Vec<(Range<usize>, String)> = Vec::new();
+    let mut found = Vec::new();
+
+    for range in truncate::sentence_ranges(text) {
+        let sentence = &text[range.clone()];
+        let Some((phrase, at)) = banned.iter().find_map(|p| Some((p, find_phrase(sentence, p)?))) else {
+            continue;
+        };
+        found.push(phrase.clone());
+
+        let comma = sentence.find(',');
+        let in_leading_clause = comma.is_some_and(|c| at < c);
+        let rest = comma.map(|c| sentence[c + 1..].trim_start()).unwrap_or("");
+        let clean_rest = !banned.iter().any(|p| find_phrase(rest, p).is_some());
+        if in_leading_clause && clean_rest && rest.chars().any(char::is_alphanumeric) {
+            edits.push((range, capitalize(rest)));
+        } else {
+            edits.push((range, String::new()));
+        }
+    }
+
+    let mut output = text.to_string();
+    for (range, replacement) in edits.into_iter().rev() {
+        output.replace_range(range, &replacement);
+    }
+    (output, found)
+}
+
+/// Collapse the gaps removals leave behind; code keeps its indentation.
+fn tidy(text: &str) -> String {
+    static GAPS: OnceLock<(Regex, Regex)> = OnceLock::new();
+    let (spaces, blank_lines) = GAPS.get_or_init(|| {
+        (
+            Regex::new(r"(\S)[ \t]{2,}").unwrap(),
+            Regex::new(r"\n[ \t]*\n(?:[ \t]*\n)+").unwrap(),
+        )
+    });
+    let tidy_gap = |gap: &str| {
+        let gap = blank_lines.replace_all(gap, "\n\n");
+        spaces.replace_all(&gap, "$1 ").into_owned()
+    };
+
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for span in verbatim_spans(text) {
+        output.push_str(&tidy_gap(&text[cursor..span.start]));
+        output.push_str(&text[span.clone()]);
+        cursor = span.end;
+    }
+    output.push_str(&tidy_gap(&text[cursor..]));
+    output.trim().to_string()
+}
+
+fn english_share(text: &str) -> (f32, usize) {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'')
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .collect();
+    if words.is_empty() {
+        return (0.0, 0);
+    }
+    let english = words.iter().filter(|w| ENGLISH_WORDS.contains(&w.as_str())).count();
+    (english as f32 / words.len() as f32, words.len())
+}
+
+// ========== ENFORCEMENT ==========
+/// Clean a raw model reply and check it against the persona. `pronouns`
+/// gives the register MIRA's "I" and "you" must be in. Fixable problems
+/// are fixed; the rest set `regenerate`.
+pub fn enforce(
+    rules: &PersonaRules,
+    model: &RegisterModel,
+    text: &str,
+    pronouns: (Register, Register),
+) -> ComplianceReport {
+    let mut violations = Vec::new();
+    let mut flag = |kind, detail: String, fixed| violations.push(Violation { kind, detail, fixed });
+
+    let (text, think) = strip_think(text);
+    if think > 0 {
+        flag(ViolationKind::ThinkBlock, format!("{} block(s) removed", think), true);
+    }
+    let (text, headings) = strip_headings(&text);
+    if headings > 0 {
+        flag(ViolationKind::MarkdownHeading, format!("{} heading(s) flattened", headings), true);
+    }
+    let (text, banned) = strip_banned(&text, &rules.banned_phrases);
+    for phrase in banned {
+        flag(ViolationKind::BannedPhrase, phrase, true);
+    }
+    let mut text = tidy(&text);
+
+    for (person, target) in [(Person::First, pronouns.0), (Person::Second, pronouns.1)] {
+        let mut wrong: Vec<String> = text
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()))
+            .filter(|w| matches!(model.pronoun(w), Some((p, r)) if p == person && r != target))
+            .map(str::to_lowercase)
+            .collect();
+        wrong.sort();
+        wrong.dedup();
+        if !wrong.is_empty() {
+            text = model.convert_person(&text, person, target).text;
+            flag(ViolationKind::Pronoun, format!("{} (want {})", wrong.join(", "), target.as_str()), true);
+        }
+    }
+
+    let (share, words) = english_share(&text);
+    if words >= rules.min_words_for_language && share >= rules.english_threshold {
+        flag(ViolationKind::English, format!("{:.0}% English function words", share * 100.0), false);
+    }
+
+    if rules.max_chars > 0 && text.chars().count() > rules.max_chars {
+        let options = TruncateOptions {
+            max_chars: Some(rules.max_chars),
+            ..TruncateOptions::default()
+        };
+        let length = text.chars().count();
+        text = truncate::truncate(&text, &options).text;
+        flag(ViolationKind::TooLong, format!("{} chars (limit {})", length, rules.max_chars), true);
+    }
+
+    if text.is_empty() {
+        flag(ViolationKind::Empty, "nothing left after cleanup".to_string(), false);
+    }
+
+    ComplianceReport {
+        text,
+        compliant: violations.is_empty(),
+        regenerate: violations.iter().any(|v| !v.fixed),
+        violations,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASUAL: (Register, Register) = (Register::Casual, Register::Casual);
+
+    fn check(text: &str) -> ComplianceReport {
+        enforce(&PersonaRules::default(), &RegisterModel::default(), text, CASUAL)
+    }
+
+    fn kinds(report: &ComplianceReport) -> Vec<ViolationKind> {
+        report.violations.iter().map(|v| v.kind).collect()
+    }
+
+    #[test]
+    fn clean_reply_passes_untouched() {
+        let report = check("Iya, aku ngerti kok. Kamu mau cerita?");
+        assert!(report.compliant && !report.regenerate);
+        assert_eq!(report.text, "Iya, aku ngerti kok. Kamu mau cerita?");
+    }
+
+    #[test]
+    fn think_blocks_are_removed_even_unclosed() {
+        let report = check("<think>user sedih</think>Aku di sini kok.");
+        assert_eq!(report.text, "Aku di sini kok.");
+        assert_eq!(kinds(&report), [ViolationKind::ThinkBlock]);
+
+        let report = check("Aku di sini kok. <think>lanjut mikir tanpa penutup");
+        assert_eq!(report.text, "Aku di sini kok.");
+    }
+
+    #[test]
+    fn headings_are_flattened_outside_fences_only() {
+        let report = check("## Tips\nMinum air dulu.\n```\n# komentar\n```");
+        assert_eq!(report.text, "Tips\n\nMinum air dulu.\n```\n# komentar\n```");
+        assert_eq!(report.violations[0].detail, "1 heading(s) flattened");
+    }
+
+    #[test]
+    fn banned_leading_clause_is_cut_and_other_sentences_dropped() {
+        let report = check("Sebagai AI, aku ngerti kok. Aku cuma AI yang bisa dengerin. Cerita aja.");
+        assert_eq!(report.text, "Aku ngerti kok. Cerita aja.");
+        assert_eq!(kinds(&report), [ViolationKind::BannedPhrase, ViolationKind::BannedPhrase]);
+        assert!(!report.regenerate);
+    }
+
+    #[test]
+    fn leading_clause_offset_survives_case_folding() {
+        // "İ" lowercases to three bytes, which used to push the match past the comma
+        let report = check("İİİİİİİİİİİİ sebagai AI, aku ngerti.");
+        assert_eq!(report.text, "Aku ngerti.");
+    }
+
+    #[test]
+    fn pronouns_follow_the_target_register() {
+        let report = check("Saya bisa bantu kamu kok.");
+        assert_eq!(report.text, "Aku bisa bantu kamu kok.");
+        assert_eq!(kinds(&report), [ViolationKind::Pronoun]);
+        assert!(report.violations[0].fixed);
+    }
+
+    #[test]
+    fn english_reply_asks_for_a_regenerate() {
+        let report = check("I think you should talk to them about it, and then see how it goes.");
+        assert_eq!(kinds(&report), [ViolationKind::English]);
+        assert!(report.regenerate);
+
+        // Short replies don't have enough words to judge
+        assert!(check("Oke, sip!").compliant);
+    }
+
+    #[test]
+    fn empty_reply_asks_for_a_regenerate() {
+        let report = check("<think>hmm</think>");
+        assert_eq!(report.text, "");
+        assert!(report.regenerate);
+        assert_eq!(kinds(&report), [ViolationKind::ThinkBlock, ViolationKind::Empty]);
+    }
+}
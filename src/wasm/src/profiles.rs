@@ -0,0 +1,245 @@
+// src/wasm/src/profiles.rs
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::rng::Rng;
+use crate::truncate;
+
+/// Profiles `humanize_response` starts with.
+const DEFAULT_PROFILES: &str = include_str!("../data/humanize_profiles.json");
+
+/// Profile key used while the user is in crisis, whatever the mood.
+pub const CRISIS: &str = "crisis";
+/// Particles that read naturally inside a question.
+const QUESTION_PARTICLES: [&str; 2] = ["sih", "kok"];
+
+/// How casual MIRA sounds at one mood and intensity. Chances are 0-1 and
+/// are scaled by affect before rolling. Unknown fields are rejected, so an
+/// old tier with its own `emoji` list fails to load instead of being
+/// quietly ignored; emoji come from `EmojiRules`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct HumanizeProfile {
+    /// The profile applies from this user-emotion intensity up.
+    pub min_intensity: f32,
+    /// Off turns fillers, particles, laughter and emoji off entirely.
+    pub playful: bool,
+    pub fillers: Vec<String>,
+    pub filler_chance: f64,
+    pub particles: Vec<String>,
+    pub particle_chance: f64,
+    pub laughter: Vec<String>,
+    pub laughter_chance: f64,
+    /// Per-sentence emoji chance for this tier, in place of the mood's in
+    /// the emoji rules. Which emoji comes from the reply's emotion.
+    pub emoji_chance: Option<f64>,
+    pub max_sentences: usize,
+}
+
+impl Default for HumanizeProfile {
+    fn default() -> Self {
+        Self {
+            min_intensity: 0.0,
+            playful: true,
+            fillers: Vec::new(),
+            filler_chance: 0.0,
+            particles: Vec::new(),
+            particle_chance: 0.0,
+            laughter: Vec::new(),
+            laughter_chance: 0.0,
+            emoji_chance: None,
+            max_sentences: 3,
+        }
+    }
+}
+
+/// What a profile added to one response.
+#[derive(Serialize, Clone, Debug, PartialEq, Default)]
+pub struct Decorations {
+    pub filler: Option<String>,
+    pub particle: Option<String>,
+    pub laughter: Option<String>,
+}
+
+/// Draw from `options` when a roll under `chance` succeeds.
+fn pick<'a>(options: &'a [String], chance: f64, rng: &mut Rng) -> Option<&'a str> {
+    if options.is_empty() || rng.next_f64() >= chance {
+        return None;
+    }
+    rng.pick(options).map(String::as_str)
+}
+
+impl HumanizeProfile {
+    /// Add the profile's casual touches to `text`. `casualness` scales every
+    /// chance; the order is particle, laughter, then filler. Emoji are a
+    /// separate stage (see `EmojiRules`).
+    pub fn decorate(
+        &self,
+        text: &mut String,
+        casualness: f64,
+        rng: &mut Rng,
+    ) -> Decorations {
+        let mut added = Decorations::default();
+        if !self.playful || text.trim().is_empty() {
+            return added;
+        }
+
+        if let Some(particle) = pick(&self.particles, self.particle_chance * casualness, rng) {
+            if insert_particle(text, particle) {
+                added.particle = Some(particle.to_string());
+            }
+        }
+        if let Some(laughter) = pick(&self.laughter, self.laughter_chance * casualness, rng) {
+            text.push(' ');
+            text.push_str(laughter);
+            added.laughter = Some(laughter.to_string());
+        }
+        if let Some(filler) = pick(&self.fillers, self.filler_chance * casualness, rng) {
+            *text = format!("{}{}", filler, lower_first(text, filler));
+            added.filler = Some(filler.trim().to_string());
+        }
+        added
+    }
+}
+
+/// "oh iya, iya paham" rather than "oh iya, Iya paham". Only a capitalized
+/// word is lowered; "AI" or "MIRA" keep their case.
+fn lower_first(text: &str, filler: &str) -> String {
+    let mut chars = text.chars();
+    let (Some(first), Some(second)) = (chars.next(), chars.next()) else {
+        return text.to_string();
+    };
+    if filler.trim_end().ends_with(',') && first.is_uppercase() && second.is_lowercase() {
+        first.to_lowercase().chain(text.chars().skip(1)).collect()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Put `particle` after the last word of the last sentence, before its
+/// punctuation: "itu bagus." -> "itu bagus sih.". Skips sentences that
+/// already carry a particle, end in a link or code, or are questions the
+/// particle doesn't fit.
+fn insert_particle(text: &mut String, particle: &str) -> bool {
+    let Some(range) = truncate::sentence_ranges(text).pop() else {
+        return false;
+    };
+    let sentence = &text[range.clone()];
+    let Some(last_word_end) = truncate::last_word_end(sentence) else {
+        return false;
+    };
+    let head = &sentence[..last_word_end];
+    let last_word = head.rsplit(char::is_whitespace).next().unwrap_or("");
+    if !last_word.chars().all(char::is_alphabetic) {
+        return false;
+    }
+
+    let words: Vec<String> = head
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase())
+        .collect();
+    let has_particle = ["sih", "deh", "kok", "dong", "kan", "lho", "loh"]
+        .iter()
+        .any(|p| words.iter().any(|w| w == p));
+    let question = sentence[last_word_end..].contains('?');
+    if has_particle || (question && !QUESTION_PARTICLES.contains(&particle)) {
+        return false;
+    }
+
+    text.insert_str(range.start + last_word_end, &format!(" {}", particle));
+    true
+}
+
+// ========== PROFILE SET ==========
+#[derive(Deserialize)]
+struct ProfileFile {
+    profiles: BTreeMap<String, Vec<HumanizeProfile>>,
+}
+
+/// Profiles per mood (plus `crisis`), each a list of intensity tiers.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct HumanizeProfiles {
+    profiles: BTreeMap<String, Vec<HumanizeProfile>>,
+}
+
+impl HumanizeProfiles {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let file: ProfileFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let mut profiles = file.profiles;
+        if !profiles.contains_key("chill") {
+            return Err("a 'chill' profile is required as the fallback".to_string());
+        }
+
+        for (key, tiers) in profiles.iter_mut() {
+            if tiers.is_empty() {
+                return Err(format!("profile '{}' has no tiers", key));
+            }
+            for tier in tiers.iter() {
+                let chances = [
+                    tier.filler_chance,
+                    tier.particle_chance,
+                    tier.laughter_chance,
+                    tier.emoji_chance.unwrap_or(0.0),
+                ];
+                if chances.iter().any(|c| !(0.0..=1.0).contains(c)) {
+                    return Err(format!("profile '{}' has a chance outside 0-1", key));
+                }
+                if !(0.0..=1.0).contains(&tier.min_intensity) {
+                    return Err(format!("profile '{}' has min_intensity outside 0-1", key));
+                }
+                if tier.max_sentences == 0 {
+                    return Err(format!("profile '{}' must keep at least one sentence", key));
+                }
+            }
+            tiers.sort_by(|a, b| a.min_intensity.total_cmp(&b.min_intensity));
+        }
+        Ok(Self { profiles })
+    }
+
+    /// The tier for `key` at `intensity`, falling back to chill for moods
+    /// without a profile. Returns the key actually used.
+    pub fn select(&self, key: &str, intensity: f32) -> (&str, &HumanizeProfile) {
+        let (key, tiers) = self
+            .profiles
+            .get_key_value(key)
+            .or_else(|| self.profiles.get_key_value("chill"))
+            .unwrap();
+        let tier = tiers
+            .iter()
+            .rev()
+            .find(|t| t.min_intensity <= intensity)
+            .unwrap_or(&tiers[0]);
+        (key.as_str(), tier)
+    }
+}
+
+impl Default for HumanizeProfiles {
+    fn default() -> Self {
+        Self::from_json(DEFAULT_PROFILES).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_profiles_load() {
+        let profiles = HumanizeProfiles::default();
+        assert_eq!(profiles.select("playful", 0.9).1.emoji_chance, Some(0.75));
+        assert_eq!(profiles.select("unknown", 0.0).0, "chill");
+    }
+
+    #[test]
+    fn tier_with_an_emoji_list_is_rejected() {
+        let json = r#"{"profiles": {"chill": [{"emoji": ["🙂"], "emoji_chance": 0.1}]}}"#;
+        let error = HumanizeProfiles::from_json(json).unwrap_err();
+        assert!(error.contains("emoji"), "{}", error);
+    }
+
+    #[test]
+    fn emoji_chance_outside_0_1_is_rejected() {
+        let json = r#"{"profiles": {"chill": [{"emoji_chance": 1.5}]}}"#;
+        assert!(HumanizeProfiles::from_json(json).is_err());
+    }
+}
@@ -0,0 +1,449 @@
+// src/wasm/src/register.rs
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::style::{verbatim_spans, FiredRule, StyleEngine, StyleRule};
+use crate::truncate;
+
+/// Equivalences `convert_register` and `detect_register` start with.
+const DEFAULT_ENTRIES: &str = include_str!("../data/register_rules.json");
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Register {
+    Formal, // saya/anda, B2B
+    Casual, // aku/kamu, MIRA's default
+    Gaul,   // gue/lo, heavy slang
+}
+
+pub const REGISTERS: [Register; 3] = [Register::Formal, Register::Casual, Register::Gaul];
+
+impl Register {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "formal" => Some(Register::Formal),
+            "casual" => Some(Register::Casual),
+            "gaul" => Some(Register::Gaul),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Register::Formal => "formal",
+            Register::Casual => "casual",
+            Register::Gaul => "gaul",
+        }
+    }
+
+    /// How formal the register is, 0-1.
+    fn formality(self) -> f32 {
+        match self {
+            Register::Formal => 1.0,
+            Register::Casual => 0.5,
+            Register::Gaul => 0.0,
+        }
+    }
+}
+
+/// Whose pronoun: the entries with these ids are the pronoun sets.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum Person {
+    First,  // entry "i": saya / aku / gue
+    Second, // entry "you": anda / kamu / lo
+}
+
+impl Person {
+    fn entry_id(self) -> &'static str {
+        match self {
+            Person::First => "i",
+            Person::Second => "you",
+        }
+    }
+}
+
+/// One concept in every register. The first variant of a register is what
+/// conversion writes; an empty one (particles, laughter in formal) deletes.
+#[derive(Deserialize, Clone, Debug)]
+struct RegisterEntry {
+    id: String,
+    formal: Vec<String>,
+    casual: Vec<String>,
+    gaul: Vec<String>,
+    /// Registers this entry rewrites into; ambiguous words ("sekali" is
+    /// also "once") only go one way, and "kok" ("how come") none at all.
+    #[serde(default = "all_registers")]
+    targets: Vec<Register>,
+    #[serde(default = "default_true")]
+    detect: bool,
+}
+
+fn all_registers() -> Vec<Register> {
+    REGISTERS.to_vec()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl RegisterEntry {
+    fn variants(&self, register: Register) -> &[String] {
+        match register {
+            Register::Formal => &self.formal,
+            Register::Casual => &self.casual,
+            Register::Gaul => &self.gaul,
+        }
+    }
+
+    /// Style rules turning this entry's other-register words into
+    /// `target`'s, plus the ids of those that delete.
+    fn rules_for(&self, target: Register) -> (Vec<StyleRule>, Vec<String>) {
+        let mut rules: Vec<StyleRule> = Vec::new();
+        let mut deletes = Vec::new();
+        let keep = self.variants(target);
+        let to = &keep[0];
+        let sources = REGISTERS
+            .iter()
+            .filter(|&&r| r != target)
+            .flat_map(|&r| self.variants(r))
+            .filter(|w| !w.is_empty() && !keep.iter().any(|k| k.eq_ignore_ascii_case(w)));
+        for from in sources {
+            let id = format!("{}:{}", self.id, from);
+            if rules.iter().any(|r| r.id == id) {
+                continue;
+            }
+            if to.is_empty() {
+                deletes.push(id.clone());
+            }
+            rules.push(StyleRule {
+                id,
+                from: from.clone(),
+                to: to.clone(),
+                // Longer phrases win: "tidak apa-apa" over "tidak"
+                priority: from.chars().count() as i32,
+                regex: false,
+                whole_word: true,
+                case_sensitive: false,
+            });
+        }
+        (rules, deletes)
+    }
+}
+
+#[derive(Deserialize)]
+struct EntryFile {
+    entries: Vec<RegisterEntry>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RegisterScore {
+    pub register: Register,
+    /// 1 is fully formal, 0 fully gaul; 0.5 when nothing gave it away.
+    pub formality: f32,
+    pub hits: usize,
+    pub scores: HashMap<Register, usize>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct RegisterOutput {
+    pub text: String,
+    pub fired: Vec<FiredRule>,
+}
+
+/// Drop the gaps left by deleted words: "bagus sih ." -> "bagus.". Code
+/// and quotations keep their spacing.
+fn tidy_deletions(text: &str) -> String {
+    static GAPS: OnceLock<(Regex, Regex)> = OnceLock::new();
+    let (spaces, before_punct) = GAPS.get_or_init(|| {
+        (
+            Regex::new(r"[ \t]{2,}").unwrap(),
+            Regex::new(r"[ \t]+([.,!?;:])").unwrap(),
+        )
+    });
+    let tidy = |gap: &str| {
+        let gap = spaces.replace_all(gap, " ");
+        before_punct.replace_all(&gap, "$1").into_owned()
+    };
+
+    let mut output = String::with_capacity(text.len());
+    let mut cursor = 0;
+    for span in verbatim_spans(text) {
+        output.push_str(&tidy(&text[cursor..span.start]));
+        output.push_str(&text[span.clone()]);
+        cursor = span.end;
+    }
+    output.push_str(&tidy(&text[cursor..]));
+    output.trim().to_string()
+}
+
+/// Formal text starts every sentence with a capital, also where a deleted
+/// particle used to: "Lho aku udah bilang." -> "Saya sudah bilang.".
+fn capitalize_sentences(text: &str) -> String {
+    let mut output = text.to_string();
+    for range in truncate::sentence_ranges(text).into_iter().rev() {
+        let Some(first) = text[range.clone()].chars().next() else {
+            continue;
+        };
+        if first.is_lowercase() {
+            let upper: String = first.to_uppercase().collect();
+            output.replace_range(range.start..range.start + first.len_utf8(), &upper);
+        }
+    }
+    output
+}
+
+// ========== REGISTER MODEL ==========
+/// Bidirectional word equivalences between the three registers. Conversion
+/// reuses the style engine: one compiled rule set per target register.
+#[derive(Clone, Debug)]
+pub struct RegisterModel {
+    engines: HashMap<Register, StyleEngine>,
+    deleting: HashMap<Register, Vec<String>>, // ids of rules that delete words
+    /// Pronoun-only rule sets, for mirroring "aku/lo" style mixes.
+    persons: HashMap<(Person, Register), StyleEngine>,
+    pronouns: HashMap<String, (Person, Register)>,
+    /// Surface form -> registers it belongs to, for detection.
+    markers: HashMap<String, [bool; 3]>,
+}
+
+impl RegisterModel {
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let file: EntryFile = serde_json::from_str(json).map_err(|e| e.to_string())?;
+
+        let mut owner: HashMap<String, &str> = HashMap::new();
+        for entry in &file.entries {
+            if REGISTERS.iter().all(|&r| entry.variants(r).iter().all(String::is_empty)) {
+                return Err(format!("register entry '{}' has no words", entry.id));
+            }
+            for &register in &REGISTERS {
+                if entry.variants(register).is_empty() {
+                    return Err(format!("register entry '{}' has no {} form", entry.id, register.as_str()));
+                }
+                for word in entry.variants(register).iter().filter(|w| !w.is_empty()) {
+                    let word = word.to_lowercase();
+                    match owner.get(word.as_str()) {
+                        Some(other) if *other != entry.id => {
+                            return Err(format!(
+                                "'{}' is in both '{}' and '{}'",
+                                word, other, entry.id
+                            ));
+                        }
+                        _ => {
+                            owner.insert(word, &entry.id);
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut engines = HashMap::new();
+        let mut deleting = HashMap::new();
+        for target in REGISTERS {
+            let mut rules = Vec::new();
+            let mut deletes = Vec::new();
+            for entry in file.entries.iter().filter(|e| e.targets.contains(&target)) {
+                let (entry_rules, entry_deletes) = entry.rules_for(target);
+                rules.extend(entry_rules);
+                deletes.extend(entry_deletes);
+            }
+            let engine = StyleEngine::compile(rules).map_err(|e| e.to_string())?;
+            engines.insert(target, engine);
+            deleting.insert(target, deletes);
+        }
+
+        let mut persons = HashMap::new();
+        let mut pronouns = HashMap::new();
+        for person in [Person::First, Person::Second] {
+            let entry = file
+                .entries
+                .iter()
+                .find(|e| e.id == person.entry_id())
+                .ok_or_else(|| format!("register entry '{}' is required", person.entry_id()))?;
+            for target in REGISTERS {
+                let (rules, _) = entry.rules_for(target);
+                let engine = StyleEngine::compile(rules).map_err(|e| e.to_string())?;
+                persons.insert((person, target), engine);
+                for word in entry.variants(target) {
+                    pronouns.insert(word.to_lowercase(), (person, target));
+                }
+            }
+        }
+
+        let mut markers: HashMap<String, [bool; 3]> = HashMap::new();
+        for entry in file.entries.iter().filter(|e| e.detect) {
+            for (i, &register) in REGISTERS.iter().enumerate() {
+                for word in entry.variants(register).iter().filter(|w| !w.is_empty()) {
+                    markers.entry(word.to_lowercase()).or_default()[i] = true;
+                }
+            }
+        }
+        // A word every register uses says nothing about formality
+        markers.retain(|_, levels| !levels.iter().all(|&l| l));
+
+        Ok(Self {
+            engines,
+            deleting,
+            persons,
+            pronouns,
+            markers,
+        })
+    }
+
+    /// Rewrite `text` into `target`, keeping the case of replaced words.
+    pub fn convert(&self, text: &str, target: Register) -> RegisterOutput {
+        let output = self.engines[&target].apply(text);
+        let deleted = output
+            .fired
+            .iter()
+            .any(|f| self.deleting[&target].contains(&f.id));
+        let text = if deleted {
+            tidy_deletions(&output.text)
+        } else {
+            output.text
+        };
+        let text = if target == Register::Formal {
+            capitalize_sentences(&text)
+        } else {
+            text
+        };
+        RegisterOutput {
+            text,
+            fired: output.fired,
+        }
+    }
+
+    /// Rewrite only the pronouns of `person` into `target`.
+    pub fn convert_person(&self, text: &str, person: Person, target: Register) -> RegisterOutput {
+        let output = self.persons[&(person, target)].apply(text);
+        RegisterOutput {
+            text: output.text,
+            fired: output.fired,
+        }
+    }
+
+    /// Every word for `person` in any register.
+    pub fn pronoun_words(&self, person: Person) -> Vec<&str> {
+        let mut words: Vec<&str> = self
+            .pronouns
+            .iter()
+            .filter(|(_, (p, _))| *p == person)
+            .map(|(w, _)| w.as_str())
+            .collect();
+        words.sort_unstable();
+        words
+    }
+
+    /// Person and register of `word` if it is a pronoun.
+    pub fn pronoun(&self, word: &str) -> Option<(Person, Register)> {
+        self.pronouns.get(&word.to_lowercase()).copied()
+    }
+
+    /// (first person, second person) registers of the pronouns in `text`;
+    /// the last one used wins.
+    pub fn detect_pronouns(&self, text: &str) -> (Option<Register>, Option<Register>) {
+        let mut found = (None, None);
+        for word in text.split_whitespace() {
+            let word = word.trim_matches(|c: char| !c.is_alphanumeric()).to_lowercase();
+            match self.pronouns.get(&word) {
+                Some((Person::First, register)) => found.0 = Some(*register),
+                Some((Person::Second, register)) => found.1 = Some(*register),
+                None => {}
+            }
+        }
+        found
+    }
+
+    /// Score how formal `text` is from the register-specific words in it.
+    /// Ties with casual, and texts with no markers, count as casual.
+    pub fn detect(&self, text: &str) -> RegisterScore {
+        let lower = text.to_lowercase();
+        let words: Vec<&str> = lower
+            .split_whitespace()
+            .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '-'))
+            .filter(|w| !w.is_empty())
+            .collect();
+
+        let mut counts = [0usize; 3];
+        let mut hits = 0;
+        let mut i = 0;
+        while i < words.len() {
+            let pair = words.get(i + 1).map(|next| format!("{} {}", words[i], next));
+            let (levels, len) = match pair.as_ref().and_then(|p| self.markers.get(p)) {
+                Some(levels) => (Some(levels), 2),
+                None => (self.markers.get(words[i]), 1),
+            };
+            i += len;
+            let Some(levels) = levels else { continue };
+            hits += 1;
+            for (count, &level) in counts.iter_mut().zip(levels) {
+                *count += level as usize;
+            }
+        }
+
+        let register = [Register::Formal, Register::Gaul, Register::Casual]
+            .into_iter()
+            .max_by_key(|r| counts[REGISTERS.iter().position(|x| x == r).unwrap()])
+            .filter(|_| hits > 0)
+            .unwrap_or(Register::Casual);
+        let total: usize = counts.iter().sum();
+        let formality = if total == 0 {
+            0.5
+        } else {
+            REGISTERS
+                .iter()
+                .zip(counts)
+                .map(|(r, c)| r.formality() * c as f32)
+                .sum::<f32>()
+                / total as f32
+        };
+
+        RegisterScore {
+            register,
+            formality,
+            hits,
+            scores: REGISTERS.iter().copied().zip(counts).collect(),
+        }
+    }
+}
+
+impl Default for RegisterModel {
+    fn default() -> Self {
+        Self::from_json(DEFAULT_ENTRIES).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn formal(text: &str) -> String {
+        RegisterModel::default().convert(text, Register::Formal).text
+    }
+
+    #[test]
+    fn formal_keeps_kok_and_capitalizes_anda() {
+        assert_eq!(
+            formal("Kok kamu gitu sih? Aku mau makan dulu deh."),
+            "Kok Anda begitu? Saya ingin makan dulu."
+        );
+        assert_eq!(formal("Aku udah bilang ke kamu."), "Saya sudah bilang ke Anda.");
+    }
+
+    #[test]
+    fn formal_recapitalizes_after_a_deleted_particle() {
+        assert_eq!(formal("Lho aku udah bilang. Nih kamu coba dulu."), "Saya sudah bilang. Anda coba dulu.");
+        assert_eq!(formal("iya, makasih ya."), "Iya, terima kasih ya.");
+    }
+
+    #[test]
+    fn casual_lowers_anda_mid_sentence() {
+        let model = RegisterModel::default();
+        assert_eq!(
+            model.convert("Anda bisa bertanya kepada Anda sendiri.", Register::Casual).text,
+            "Kamu bisa nanya kepada kamu sendiri."
+        );
+    }
+}
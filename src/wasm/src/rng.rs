@@ -0,0 +1,98 @@
+// src/wasm/src/rng.rs
+use serde::{Deserialize, Serialize};
+
+// ========== HASHING ==========
+/// splitmix64 finalizer; the same inputs always give the same bits.
+pub fn hash(seed: u64, channel: u64, n: u64) -> u64 {
+    let mut z = seed
+        .wrapping_add(channel.wrapping_mul(0x9E37_79B9_7F4A_7C15))
+        .wrapping_add(n.wrapping_mul(0xBF58_476D_1CE4_E5B9));
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Uniform in 0..1.
+pub fn hash01(seed: u64, channel: u64, n: u64) -> f64 {
+    (hash(seed, channel, n) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// ========== SESSION RNG ==========
+/// Counter-based generator behind every random choice a session makes.
+/// Draw `n` depends only on `seed` and `n`, so `{seed, draws}` is the whole
+/// state: a snapshot replays exactly from where it was taken.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct Rng {
+    pub seed: u32,
+    pub draws: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u32) -> Self {
+        Self { seed, draws: 0 }
+    }
+
+    /// Seeded from the platform's entropy source (`crypto.getRandomValues`
+    /// in the browser); seed 0 if that is unavailable.
+    pub fn from_entropy() -> Self {
+        let mut buf = [0u8; 4];
+        let seed = match getrandom::getrandom(&mut buf) {
+            Ok(()) => u32::from_le_bytes(buf),
+            Err(_) => 0,
+        };
+        Self::new(seed)
+    }
+
+    /// Uniform in 0..1.
+    pub fn next_f64(&mut self) -> f64 {
+        let value = hash01(self.seed as u64, 0, self.draws);
+        self.draws = self.draws.wrapping_add(1);
+        value
+    }
+
+    /// Uniform index below `len`; `len` must be positive.
+    pub fn below(&mut self, len: usize) -> usize {
+        ((self.next_f64() * len as f64) as usize).min(len - 1)
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        Some(&items[self.below(items.len())])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draws(rng: &mut Rng, n: usize) -> Vec<f64> {
+        (0..n).map(|_| rng.next_f64()).collect()
+    }
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let a = draws(&mut Rng::new(42), 16);
+        assert_eq!(a, draws(&mut Rng::new(42), 16));
+        assert_ne!(a, draws(&mut Rng::new(43), 16));
+        assert!(a.iter().all(|v| (0.0..1.0).contains(v)));
+    }
+
+    #[test]
+    fn resumes_from_its_serialized_state() {
+        let mut rng = Rng::new(7);
+        draws(&mut rng, 5);
+        let mut restored: Rng = serde_json::from_str(&serde_json::to_string(&rng).unwrap()).unwrap();
+        assert_eq!(restored, Rng { seed: 7, draws: 5 });
+        assert_eq!(draws(&mut restored, 8), draws(&mut rng, 8));
+    }
+
+    #[test]
+    fn below_and_pick_stay_in_range() {
+        let mut rng = Rng::new(1);
+        assert!((0..1000).all(|_| rng.below(3) < 3));
+        assert_eq!(rng.pick::<u8>(&[]), None);
+        assert_eq!(rng.pick(&["only"]), Some(&"only"));
+    }
+}
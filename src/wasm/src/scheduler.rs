@@ -0,0 +1,310 @@
+// src/wasm/src/scheduler.rs
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Cap on waiting requests; the lowest priority one is dropped beyond this.
+const MAX_QUEUE: usize = 16;
+/// Cap on `expression_history`, oldest entries go first.
+pub const MAX_HISTORY: usize = 100;
+
+pub const DEFAULT_FADE_MS: f64 = 300.0;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum IdleMode {
+    Neutral,
+    Mood,
+}
+
+impl IdleMode {
+    pub fn parse(mode: &str) -> Option<Self> {
+        match mode {
+            "neutral" => Some(IdleMode::Neutral),
+            "mood" => Some(IdleMode::Mood),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExpressionRequest {
+    pub id: String,
+    pub priority: u8,
+    pub hold_ms: f64,
+    pub fade_ms: f64,
+    /// Whether a higher-priority request may cut this one short.
+    pub interruptible: bool,
+}
+
+#[derive(Clone, Debug)]
+struct Active {
+    request: ExpressionRequest,
+    started_at: f64,
+    idle: bool,
+}
+
+impl Active {
+    fn ends_at(&self) -> Option<f64> {
+        if self.idle {
+            None
+        } else {
+            Some(self.started_at + self.request.hold_ms)
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ExpressionFrame {
+    pub expression: String,
+    pub previous: Option<String>,
+    pub progress: f32, // fade-in of `expression`, 0.0-1.0
+    pub weights: BTreeMap<String, f32>,
+    pub idle: bool,
+    pub remaining_ms: Option<f64>,
+    pub queued: usize,
+    pub parameters: Option<BTreeMap<String, f32>>,
+}
+
+/// Decides which expression is on screen at any time: requests are held for
+/// their duration, higher priorities may interrupt, and once nothing is left
+/// the face falls back to the idle expression.
+#[derive(Clone, Debug)]
+pub struct ExpressionScheduler {
+    queue: Vec<ExpressionRequest>,
+    active: Option<Active>,
+    previous: Option<String>,
+    pub idle_mode: IdleMode,
+}
+
+impl ExpressionScheduler {
+    pub fn new() -> Self {
+        Self {
+            queue: Vec::new(),
+            active: None,
+            previous: None,
+            idle_mode: IdleMode::Mood,
+        }
+    }
+
+    /// Returns true if the request went on screen right away. Holds that
+    /// ended by `now_ms` are settled first, so a request never waits
+    /// behind an expression that is already over.
+    pub fn enqueue(
+        &mut self,
+        request: ExpressionRequest,
+        now_ms: f64,
+        idle_id: &str,
+        history: &mut Vec<(String, f64)>,
+    ) -> bool {
+        self.expire(now_ms, idle_id, history);
+        let preempts = match &self.active {
+            None => true,
+            Some(active) if active.idle => true,
+            Some(active) => active.request.interruptible && request.priority > active.request.priority,
+        };
+
+        if preempts {
+            self.start(request, now_ms, false, history);
+            return true;
+        }
+
+        // Highest priority first, FIFO within a priority
+        let pos = self
+            .queue
+            .iter()
+            .position(|queued| queued.priority < request.priority)
+            .unwrap_or(self.queue.len());
+        self.queue.insert(pos, request);
+        self.queue.truncate(MAX_QUEUE);
+        false
+    }
+
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Hand expired holds over to the next request, or to `idle_id` once
+    /// the queue runs dry. Each handover happens at the exact end time, so
+    /// a late call still produces the right timeline.
+    fn expire(&mut self, now_ms: f64, idle_id: &str, history: &mut Vec<(String, f64)>) {
+        while let Some(ends_at) = self.active.as_ref().and_then(Active::ends_at) {
+            if ends_at > now_ms {
+                break;
+            }
+            if self.queue.is_empty() {
+                self.start(Self::idle_request(idle_id), ends_at, true, history);
+            } else {
+                let next = self.queue.remove(0);
+                self.start(next, ends_at, false, history);
+            }
+        }
+    }
+
+    /// Advance to `now_ms` and report what should be shown. `idle_id` is the
+    /// expression to rest on once the queue runs dry.
+    pub fn tick(
+        &mut self,
+        now_ms: f64,
+        idle_id: &str,
+        history: &mut Vec<(String, f64)>,
+    ) -> ExpressionFrame {
+        self.expire(now_ms, idle_id, history);
+
+        let idle_changed = self
+            .active
+            .as_ref()
+            .is_none_or(|a| a.idle && a.request.id != idle_id);
+        if idle_changed {
+            self.start(Self::idle_request(idle_id), now_ms, true, history);
+        }
+
+        let active = self.active.as_ref().unwrap();
+        let fade_ms = active.request.fade_ms.max(0.0);
+        let elapsed = (now_ms - active.started_at).max(0.0);
+        let progress = if fade_ms == 0.0 {
+            1.0
+        } else {
+            (elapsed / fade_ms).min(1.0) as f32
+        };
+
+        let mut weights = BTreeMap::new();
+        if let Some(previous) = self.previous.as_ref().filter(|_| progress < 1.0) {
+            weights.insert(previous.clone(), 1.0 - progress);
+        }
+        *weights.entry(active.request.id.clone()).or_insert(0.0) += progress;
+
+        ExpressionFrame {
+            expression: active.request.id.clone(),
+            previous: self.previous.clone(),
+            progress,
+            weights,
+            idle: active.idle,
+            remaining_ms: active.ends_at().map(|end| (end - now_ms).max(0.0)),
+            queued: self.queue.len(),
+            parameters: None,
+        }
+    }
+
+    fn idle_request(idle_id: &str) -> ExpressionRequest {
+        ExpressionRequest {
+            id: idle_id.to_string(),
+            priority: 0,
+            hold_ms: 0.0,
+            fade_ms: DEFAULT_FADE_MS,
+            interruptible: true,
+        }
+    }
+
+    fn start(
+        &mut self,
+        request: ExpressionRequest,
+        at_ms: f64,
+        idle: bool,
+        history: &mut Vec<(String, f64)>,
+    ) {
+        self.previous = self.active.take().map(|a| a.request.id);
+        history.push((request.id.clone(), at_ms));
+        if history.len() > MAX_HISTORY {
+            history.drain(..history.len() - MAX_HISTORY);
+        }
+
+        self.active = Some(Active {
+            request,
+            started_at: at_ms,
+            idle,
+        });
+    }
+}
+
+impl Default for ExpressionScheduler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(id: &str, priority: u8, hold_ms: f64, interruptible: bool) -> ExpressionRequest {
+        ExpressionRequest {
+            id: id.to_string(),
+            priority,
+            hold_ms,
+            fade_ms: 0.0,
+            interruptible,
+        }
+    }
+
+    #[test]
+    fn higher_priority_interrupts_only_interruptible_holds() {
+        let mut scheduler = ExpressionScheduler::new();
+        let mut history = Vec::new();
+        assert!(scheduler.enqueue(request("f02", 1, 1000.0, true), 0.0, "f01", &mut history));
+        assert!(scheduler.enqueue(request("f03", 2, 1000.0, false), 100.0, "f01", &mut history));
+        assert!(!scheduler.enqueue(request("f04", 3, 1000.0, true), 200.0, "f01", &mut history));
+        assert_eq!(scheduler.tick(300.0, "f01", &mut history).expression, "f03");
+    }
+
+    #[test]
+    fn expired_hold_hands_over_at_its_end_time() {
+        let mut scheduler = ExpressionScheduler::new();
+        let mut history = Vec::new();
+        scheduler.enqueue(request("f02", 1, 1000.0, false), 0.0, "f01", &mut history);
+        scheduler.enqueue(request("f03", 1, 500.0, false), 100.0, "f01", &mut history);
+
+        // A late tick still starts f03 at 1000, so it is already over at 1600
+        let frame = scheduler.tick(1200.0, "f01", &mut history);
+        assert_eq!(frame.expression, "f03");
+        assert_eq!(frame.remaining_ms, Some(300.0));
+        assert_eq!(frame.queued, 0);
+    }
+
+    #[test]
+    fn face_returns_to_idle_once_the_queue_is_empty() {
+        let mut scheduler = ExpressionScheduler::new();
+        let mut history = Vec::new();
+        scheduler.enqueue(request("f02", 1, 1000.0, true), 0.0, "f01", &mut history);
+        let frame = scheduler.tick(1500.0, "f01", &mut history);
+        assert_eq!(frame.expression, "f01");
+        assert!(frame.idle);
+        assert_eq!(frame.remaining_ms, None);
+
+        // A new idle expression takes over right away
+        assert_eq!(scheduler.tick(1600.0, "f03", &mut history).expression, "f03");
+    }
+
+    #[test]
+    fn request_after_an_expired_hold_starts_now() {
+        let mut scheduler = ExpressionScheduler::new();
+        let mut history = Vec::new();
+        scheduler.enqueue(request("f02", 1, 1000.0, false), 0.0, "f01", &mut history);
+
+        // No tick since f02 ended; f04 must not wait behind it
+        assert!(scheduler.enqueue(request("f04", 1, 1000.0, false), 1500.0, "f01", &mut history));
+        let frame = scheduler.tick(1600.0, "f01", &mut history);
+        assert_eq!(frame.expression, "f04");
+        assert_eq!(frame.remaining_ms, Some(900.0));
+    }
+
+    #[test]
+    fn history_records_each_start_and_is_capped() {
+        let mut scheduler = ExpressionScheduler::new();
+        let mut history = Vec::new();
+        scheduler.enqueue(request("f02", 1, 1000.0, true), 0.0, "f01", &mut history);
+        scheduler.enqueue(request("f03", 1, 500.0, true), 10.0, "f01", &mut history);
+        scheduler.tick(2000.0, "f01", &mut history);
+        assert_eq!(
+            history,
+            [
+                ("f02".to_string(), 0.0),
+                ("f03".to_string(), 1000.0),
+                ("f01".to_string(), 1500.0)
+            ]
+        );
+
+        for i in 0..2 * MAX_HISTORY {
+            scheduler.enqueue(request("f04", 9, 0.0, true), 3000.0 + i as f64, "f01", &mut history);
+        }
+        assert_eq!(history.len(), MAX_HISTORY);
+    }
+}
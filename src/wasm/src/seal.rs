@@ -0,0 +1,322 @@
+// src/wasm/src/seal.rs
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Envelope format version, independent of the snapshot schema inside it.
+pub const SEAL_VERSION: u32 = 1;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const CHECK_LEN: usize = 16;
+
+// Argon2id defaults (OWASP baseline: 19 MiB, 2 passes, 1 lane)
+const DEFAULT_M_COST: u32 = 19 * 1024;
+const DEFAULT_T_COST: u32 = 2;
+const DEFAULT_P_COST: u32 = 1;
+
+// Refuse envelopes asking for absurd KDF work; they'd freeze the tab.
+const MAX_M_COST: u32 = 256 * 1024;
+const MAX_T_COST: u32 = 10;
+const MAX_P_COST: u32 = 4;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct KdfParams {
+    pub m: u32,
+    pub t: u32,
+    pub p: u32,
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m: DEFAULT_M_COST,
+            t: DEFAULT_T_COST,
+            p: DEFAULT_P_COST,
+        }
+    }
+}
+
+/// What actually lands in localStorage. Everything except `ct` is
+/// authenticated as associated data. An edited salt or kdf changes the
+/// derived key and so reads as a wrong passphrase; any other edit reads
+/// as tampering.
+#[derive(Serialize, Deserialize)]
+struct SealedEnvelope {
+    v: u32,
+    kdf: KdfParams,
+    salt: String,
+    check: String, // key-check value, tells a wrong passphrase apart from tampering
+    nonce: String,
+    ct: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SealError {
+    WrongPassphrase,
+    Tampered,
+    Malformed(String),
+    UnsupportedVersion(u32),
+    Kdf(String),
+    Random(String),
+}
+
+impl SealError {
+    /// Stable error name surfaced to JS as `error.name`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SealError::WrongPassphrase => "WrongPassphraseError",
+            SealError::Tampered => "TamperedError",
+            SealError::Malformed(_) => "MalformedSealError",
+            SealError::UnsupportedVersion(_) => "UnsupportedSealVersionError",
+            SealError::Kdf(_) => "KdfError",
+            SealError::Random(_) => "RandomSourceError",
+        }
+    }
+}
+
+impl fmt::Display for SealError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SealError::WrongPassphrase => write!(f, "wrong passphrase"),
+            SealError::Tampered => write!(f, "sealed state was modified or corrupted"),
+            SealError::Malformed(msg) => write!(f, "malformed sealed state: {}", msg),
+            SealError::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported seal version {} (this build reads up to {})",
+                v, SEAL_VERSION
+            ),
+            SealError::Kdf(msg) => write!(f, "key derivation failed: {}", msg),
+            SealError::Random(msg) => write!(f, "random source unavailable: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SealError {}
+
+// ========== KEY DERIVATION ==========
+struct DerivedKey {
+    key: [u8; KEY_LEN],
+    check: [u8; CHECK_LEN],
+}
+
+fn derive_key(passphrase: &str, salt: &[u8], params: KdfParams) -> Result<DerivedKey, SealError> {
+    let argon_params = Params::new(params.m, params.t, params.p, Some(KEY_LEN + CHECK_LEN))
+        .map_err(|e| SealError::Kdf(e.to_string()))?;
+    let argon = Argon2::new(Algorithm::Argon2id, Version::V0x13, argon_params);
+
+    let mut out = [0u8; KEY_LEN + CHECK_LEN];
+    argon
+        .hash_password_into(passphrase.as_bytes(), salt, &mut out)
+        .map_err(|e| SealError::Kdf(e.to_string()))?;
+
+    let mut derived = DerivedKey {
+        key: [0u8; KEY_LEN],
+        check: [0u8; CHECK_LEN],
+    };
+    derived.key.copy_from_slice(&out[..KEY_LEN]);
+    derived.check.copy_from_slice(&out[KEY_LEN..]);
+    out.fill(0);
+    Ok(derived)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], SealError> {
+    let mut buf = [0u8; N];
+    getrandom::getrandom(&mut buf).map_err(|e| SealError::Random(e.to_string()))?;
+    Ok(buf)
+}
+
+fn associated_data(v: u32, kdf: &KdfParams, salt: &str, check: &str, nonce: &str) -> Vec<u8> {
+    format!(
+        "mira-sealed:{}:{}:{}:{}:{}:{}:{}",
+        v, kdf.m, kdf.t, kdf.p, salt, check, nonce
+    )
+    .into_bytes()
+}
+
+fn decode_field<const N: usize>(name: &str, value: &str) -> Result<[u8; N], SealError> {
+    let bytes = B64
+        .decode(value)
+        .map_err(|e| SealError::Malformed(format!("`{}`: {}", name, e)))?;
+    bytes
+        .try_into()
+        .map_err(|_| SealError::Malformed(format!("`{}` has wrong length", name)))
+}
+
+// ========== SEAL / UNSEAL ==========
+/// Encrypt `plaintext` under a key derived from `passphrase` with a fresh
+/// salt and nonce. Returns a JSON envelope safe to store as a string.
+pub fn seal(plaintext: &str, passphrase: &str, params: KdfParams) -> Result<String, SealError> {
+    let salt = random_bytes::<SALT_LEN>()?;
+    let nonce = random_bytes::<NONCE_LEN>()?;
+    let derived = derive_key(passphrase, &salt, params)?;
+
+    let salt_b64 = B64.encode(salt);
+    let check_b64 = B64.encode(derived.check);
+    let nonce_b64 = B64.encode(nonce);
+    let aad = associated_data(SEAL_VERSION, &params, &salt_b64, &check_b64, &nonce_b64);
+
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&derived.key));
+    let ct = cipher
+        .encrypt(
+            Nonce::from_slice(&nonce),
+            Payload {
+                msg: plaintext.as_bytes(),
+                aad: &aad,
+            },
+        )
+        .map_err(|_| SealError::Malformed("encryption failed".to_string()))?;
+
+    let envelope = SealedEnvelope {
+        v: SEAL_VERSION,
+        kdf: params,
+        salt: salt_b64,
+        check: check_b64,
+        nonce: nonce_b64,
+        ct: B64.encode(ct),
+    };
+    Ok(serde_json::to_string(&envelope).unwrap())
+}
+
+/// Decrypt an envelope produced by `seal`.
+pub fn unseal(sealed: &str, passphrase: &str) -> Result<String, SealError> {
+    let envelope: SealedEnvelope =
+        serde_json::from_str(sealed).map_err(|e| SealError::Malformed(e.to_string()))?;
+
+    if envelope.v == 0 || envelope.v > SEAL_VERSION {
+        return Err(SealError::UnsupportedVersion(envelope.v));
+    }
+    let kdf = envelope.kdf;
+    if kdf.m > MAX_M_COST || kdf.t > MAX_T_COST || kdf.p > MAX_P_COST {
+        return Err(SealError::Malformed(
+            "KDF parameters out of range".to_string(),
+        ));
+    }
+
+    let salt = decode_field::<SALT_LEN>("salt", &envelope.salt)?;
+    let check = decode_field::<CHECK_LEN>("check", &envelope.check)?;
+    let nonce = decode_field::<NONCE_LEN>("nonce", &envelope.nonce)?;
+    let ct = B64
+        .decode(&envelope.ct)
+        .map_err(|e| SealError::Malformed(format!("`ct`: {}", e)))?;
+
+    // The tag is verified first, over the check value this passphrase
+    // derives rather than the stored one. Only then does the stored check
+    // decide which error a failure is.
+    let derived = derive_key(passphrase, &salt, kdf)?;
+    let check_matches = constant_time_eq(&derived.check, &check);
+    let aad = associated_data(
+        envelope.v,
+        &kdf,
+        &envelope.salt,
+        &B64.encode(derived.check),
+        &envelope.nonce,
+    );
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&derived.key));
+    let decrypted = cipher.decrypt(
+        Nonce::from_slice(&nonce),
+        Payload {
+            msg: &ct,
+            aad: &aad,
+        },
+    );
+
+    let plaintext = match decrypted {
+        // Right key, but the stored check was edited
+        Ok(_) if !check_matches => return Err(SealError::Tampered),
+        Ok(plaintext) => plaintext,
+        // Right key, so the ciphertext or nonce was edited
+        Err(_) if check_matches => return Err(SealError::Tampered),
+        // Wrong key. An edited salt or kdf also lands here: without the
+        // right key there is nothing to tell it from a wrong passphrase.
+        Err(_) => return Err(SealError::WrongPassphrase),
+    };
+    String::from_utf8(plaintext).map_err(|_| SealError::Tampered)
+}
+
+/// Re-encrypt under a new passphrase. The old passphrase must be correct;
+/// salt and nonce are always regenerated.
+pub fn rotate(
+    sealed: &str,
+    old_passphrase: &str,
+    new_passphrase: &str,
+    params: KdfParams,
+) -> Result<String, SealError> {
+    let plaintext = unseal(sealed, old_passphrase)?;
+    seal(&plaintext, new_passphrase, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    // Tiny Argon2 cost so the tests stay fast
+    const FAST: KdfParams = KdfParams { m: 64, t: 1, p: 1 };
+
+    fn edit(sealed: &str, field: &str, f: impl FnOnce(&mut Value)) -> String {
+        let mut envelope: Value = serde_json::from_str(sealed).unwrap();
+        f(&mut envelope[field]);
+        envelope.to_string()
+    }
+
+    fn flip_first_byte(value: &mut Value) {
+        let mut bytes = B64.decode(value.as_str().unwrap()).unwrap();
+        bytes[0] ^= 1;
+        *value = Value::String(B64.encode(bytes));
+    }
+
+    #[test]
+    fn round_trip() {
+        let sealed = seal("{\"v\":6}", "rahasia", FAST).unwrap();
+        assert_eq!(unseal(&sealed, "rahasia").unwrap(), "{\"v\":6}");
+    }
+
+    #[test]
+    fn wrong_passphrase_is_reported_as_such() {
+        let sealed = seal("halo", "rahasia", FAST).unwrap();
+        assert_eq!(unseal(&sealed, "salah"), Err(SealError::WrongPassphrase));
+    }
+
+    #[test]
+    fn edited_ciphertext_is_tampered() {
+        let sealed = seal("halo", "rahasia", FAST).unwrap();
+        let edited = edit(&sealed, "ct", flip_first_byte);
+        assert_eq!(unseal(&edited, "rahasia"), Err(SealError::Tampered));
+    }
+
+    #[test]
+    fn edited_header_is_tampered() {
+        let sealed = seal("halo", "rahasia", FAST).unwrap();
+        for field in ["nonce", "check"] {
+            let edited = edit(&sealed, field, flip_first_byte);
+            assert_eq!(unseal(&edited, "rahasia"), Err(SealError::Tampered), "{}", field);
+        }
+        let newer = edit(&sealed, "v", |v| *v = Value::from(SEAL_VERSION + 1));
+        assert_eq!(
+            unseal(&newer, "rahasia"),
+            Err(SealError::UnsupportedVersion(SEAL_VERSION + 1))
+        );
+        let greedy = edit(&sealed, "kdf", |kdf| kdf["m"] = Value::from(MAX_M_COST + 1));
+        assert!(matches!(unseal(&greedy, "rahasia"), Err(SealError::Malformed(_))));
+    }
+
+    #[test]
+    fn rotate_moves_to_the_new_passphrase() {
+        let sealed = seal("halo", "lama", FAST).unwrap();
+        assert_eq!(rotate(&sealed, "salah", "baru", FAST), Err(SealError::WrongPassphrase));
+
+        let rotated = rotate(&sealed, "lama", "baru", FAST).unwrap();
+        assert_eq!(unseal(&rotated, "baru").unwrap(), "halo");
+        assert_eq!(unseal(&rotated, "lama"), Err(SealError::WrongPassphrase));
+    }
+}
@@ -0,0 +1,285 @@
+// src/wasm/src/session.rs
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+use crate::affect::{Affect, AffectDynamics};
+use crate::baseline::SentimentBaseline;
+use crate::bubbles::BubbleOptions;
+use crate::emoji::EmojiRules;
+use crate::expressions::ExpressionRegistry;
+use crate::lexicon::Lexicon;
+use crate::mirror::Mirror;
+use crate::mood::MoodExplanation;
+use crate::persona::PersonaRules;
+use crate::profiles::HumanizeProfiles;
+use crate::register::Register;
+use crate::rng::Rng;
+use crate::motions::MotionSelector;
+use crate::scheduler::ExpressionScheduler;
+use crate::style::StyleEngine;
+use crate::truncate::TruncateOptions;
+use crate::{to_js_object, ExpressionDetection, MiraCore, MOODS};
+
+/// Everything that belongs to a single conversation. The lexicon is not here
+/// on purpose: it is immutable and shared between sessions.
+pub struct SessionState {
+    pub mood_cache: HashMap<String, String>,
+    pub mood_counts: HashMap<String, u32>,
+    pub expression_history: Vec<(String, f64)>, // (expression, timestamp ms)
+    pub baseline: SentimentBaseline,
+    pub last_mood: Option<String>,
+    pub last_explanation: Option<MoodExplanation>, // debug only, not persisted
+    pub scheduler: ExpressionScheduler,             // live timeline, not persisted
+    pub motions: MotionSelector,                    // cooldowns, not persisted
+    pub user_emotion: Option<ExpressionDetection>,  // last user message, not persisted
+    pub reply_emotion: Option<ExpressionDetection>, // last MIRA reply, not persisted
+    pub affect: Affect,
+    pub affect_dynamics: AffectDynamics, // configuration, not persisted
+    pub truncation: TruncateOptions,     // configuration, not persisted
+    pub profiles: HumanizeProfiles,      // configuration, not persisted
+    pub crisis: bool,                    // last user message, not persisted
+    pub rng: Rng,
+    pub register: Register, // configuration, not persisted
+    pub mirror: Mirror,     // how the user talks
+    pub persona: PersonaRules, // configuration, not persisted
+    pub bubbles: BubbleOptions, // configuration, not persisted
+    pub emoji: EmojiRules,      // configuration, not persisted
+    pub recent_emoji: Vec<String>, // newest last, not persisted
+}
+
+impl SessionState {
+    pub fn new() -> Self {
+        Self {
+            mood_cache: HashMap::new(),
+            mood_counts: MOODS.iter().map(|m| (m.to_string(), 0)).collect(),
+            expression_history: Vec::new(),
+            baseline: SentimentBaseline::new(),
+            last_mood: None,
+            last_explanation: None,
+            scheduler: ExpressionScheduler::new(),
+            motions: MotionSelector::new(),
+            user_emotion: None,
+            reply_emotion: None,
+            affect: Affect::new(),
+            affect_dynamics: AffectDynamics::default(),
+            truncation: TruncateOptions::default(),
+            profiles: HumanizeProfiles::default(),
+            crisis: false,
+            rng: Rng::from_entropy(),
+            register: Register::Casual,
+            mirror: Mirror::new(),
+            persona: PersonaRules::default(),
+            bubbles: BubbleOptions::default(),
+            emoji: EmojiRules::default(),
+            recent_emoji: Vec::new(),
+        }
+    }
+
+    pub fn dominant_mood(&self) -> String {
+        self.mood_counts
+            .iter()
+            .max_by_key(|&(_, count)| count)
+            .map(|(mood, _)| mood.clone())
+            .unwrap_or_else(|| "chill".to_string())
+    }
+
+    fn message_count(&self) -> u32 {
+        self.mood_counts.values().sum()
+    }
+}
+
+impl Default for SessionState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Serialize)]
+struct SessionSummary {
+    id: String,
+    dominant_mood: String,
+    message_count: u32,
+    cached_inputs: usize,
+    expressions_recorded: usize,
+}
+
+#[derive(Serialize)]
+struct AggregateStats {
+    session_count: usize,
+    active_session: Option<String>,
+    total_messages: u32,
+    mood_counts: BTreeMap<String, u32>,
+    dominant_mood: String,
+    sessions: Vec<SessionSummary>,
+}
+
+// ========== SESSION MANAGER ==========
+/// Keeps many conversations on top of a single lexicon. `session(id)` and
+/// `active()` return `MiraCore` handles that read and write the managed state
+/// directly, so nothing needs to be synced back.
+#[wasm_bindgen]
+pub struct SessionManager {
+    lexicon: Rc<Lexicon>,
+    registry: Rc<RefCell<ExpressionRegistry>>,
+    style: Rc<RefCell<StyleEngine>>,
+    sessions: BTreeMap<String, Rc<RefCell<SessionState>>>,
+    active: Option<String>,
+}
+
+#[wasm_bindgen]
+impl SessionManager {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            lexicon: Rc::new(Lexicon::new()),
+            registry: Rc::new(RefCell::new(ExpressionRegistry::default())),
+            style: Rc::new(RefCell::new(StyleEngine::default())),
+            sessions: BTreeMap::new(),
+            active: None,
+        }
+    }
+
+    /// Create an empty session. Fails if the ID is already taken. The first
+    /// session created becomes the active one.
+    #[wasm_bindgen]
+    pub fn create_session(&mut self, id: &str) -> Result<MiraCore, JsError> {
+        if self.sessions.contains_key(id) {
+            return Err(JsError::new(&format!("session '{}' already exists", id)));
+        }
+
+        let state = Rc::new(RefCell::new(SessionState::new()));
+        self.sessions.insert(id.to_string(), state.clone());
+        if self.active.is_none() {
+            self.active = Some(id.to_string());
+        }
+
+        Ok(self.handle(state))
+    }
+
+    #[wasm_bindgen]
+    pub fn switch_session(&mut self, id: &str) -> Result<MiraCore, JsError> {
+        let core = self.session(id)?;
+        self.active = Some(id.to_string());
+        Ok(core)
+    }
+
+    #[wasm_bindgen]
+    pub fn session(&self, id: &str) -> Result<MiraCore, JsError> {
+        self.sessions
+            .get(id)
+            .map(|state| self.handle(state.clone()))
+            .ok_or_else(|| JsError::new(&format!("unknown session '{}'", id)))
+    }
+
+    #[wasm_bindgen]
+    pub fn active(&self) -> Option<MiraCore> {
+        self.active.as_deref().and_then(|id| self.session(id).ok())
+    }
+
+    #[wasm_bindgen]
+    pub fn active_session_id(&self) -> Option<String> {
+        self.active.clone()
+    }
+
+    #[wasm_bindgen]
+    pub fn list_sessions(&self) -> Vec<String> {
+        self.sessions.keys().cloned().collect()
+    }
+
+    #[wasm_bindgen]
+    pub fn has_session(&self, id: &str) -> bool {
+        self.sessions.contains_key(id)
+    }
+
+    /// Remove a session. Returns false if it didn't exist. Deleting the active
+    /// session leaves no session active.
+    #[wasm_bindgen]
+    pub fn delete_session(&mut self, id: &str) -> bool {
+        let removed = self.sessions.remove(id).is_some();
+        if removed && self.active.as_deref() == Some(id) {
+            self.active = None;
+        }
+        removed
+    }
+
+    /// Load the Live2D expression registry for every session (see
+    /// `MiraCore::load_expression_registry`).
+    #[wasm_bindgen]
+    pub fn load_expression_registry(
+        &mut self,
+        model3_json: &str,
+        mapping_json: &str,
+    ) -> Result<(), JsError> {
+        let registry = ExpressionRegistry::from_json(model3_json, mapping_json)
+            .map_err(|e| JsError::new(&e.to_string()))?;
+        *self.registry.borrow_mut() = registry;
+        Ok(())
+    }
+
+    /// Load humanization style rules for every session (see
+    /// `MiraCore::load_style_rules`).
+    #[wasm_bindgen]
+    pub fn load_style_rules(&mut self, rules_json: &str) -> Result<(), JsError> {
+        let engine = StyleEngine::from_json(rules_json).map_err(|e| JsError::new(&e.to_string()))?;
+        *self.style.borrow_mut() = engine;
+        Ok(())
+    }
+
+    #[wasm_bindgen]
+    pub fn aggregate_stats(&self) -> JsValue {
+        let mut mood_counts: BTreeMap<String, u32> =
+            MOODS.iter().map(|m| (m.to_string(), 0)).collect();
+        let mut sessions = Vec::new();
+
+        for (id, state) in &self.sessions {
+            let state = state.borrow();
+            for (mood, count) in &state.mood_counts {
+                *mood_counts.entry(mood.clone()).or_insert(0) += count;
+            }
+            sessions.push(SessionSummary {
+                id: id.clone(),
+                dominant_mood: state.dominant_mood(),
+                message_count: state.message_count(),
+                cached_inputs: state.mood_cache.len(),
+                expressions_recorded: state.expression_history.len(),
+            });
+        }
+
+        let dominant_mood = mood_counts
+            .iter()
+            .max_by_key(|&(_, count)| count)
+            .map(|(mood, _)| mood.clone())
+            .unwrap_or_else(|| "chill".to_string());
+
+        let stats = AggregateStats {
+            session_count: self.sessions.len(),
+            active_session: self.active.clone(),
+            total_messages: mood_counts.values().sum(),
+            mood_counts,
+            dominant_mood,
+            sessions,
+        };
+
+        to_js_object(&stats)
+    }
+}
+
+impl SessionManager {
+    fn handle(&self, state: Rc<RefCell<SessionState>>) -> MiraCore {
+        MiraCore::from_parts(
+            self.lexicon.clone(),
+            self.registry.clone(),
+            self.style.clone(),
+            state,
+        )
+    }
+}
+
+impl Default for SessionManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,281 @@
+// src/wasm/src/snapshot.rs
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::affect::Affect;
+use crate::baseline::SentimentBaseline;
+use crate::mirror::Mirror;
+use crate::rng::Rng;
+use crate::MOODS;
+
+/// Current snapshot schema version. Bump this and append a migration to
+/// `MIGRATIONS` whenever the shape of `SessionSnapshot` changes.
+pub const SNAPSHOT_VERSION: u32 = 6;
+
+/// Upper bound for restored collections, so a corrupted or hostile snapshot
+/// can't blow up memory on import.
+const MAX_ENTRIES: usize = 10_000;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SessionSnapshot {
+    pub version: u32,
+    pub mood_counts: HashMap<String, u32>,
+    pub mood_cache: HashMap<String, String>,
+    pub expression_history: Vec<(String, f64)>, // (expression, timestamp ms)
+    pub baseline: SentimentBaseline,
+    pub last_mood: Option<String>,
+    pub affect: Affect,
+    pub rng: Rng, // seed and draw count, for exact replay
+    pub mirror: Mirror,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotError {
+    Malformed(String),
+    UnsupportedVersion(u32),
+    Invalid(String),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::Malformed(msg) => write!(f, "malformed snapshot: {}", msg),
+            SnapshotError::UnsupportedVersion(v) => write!(
+                f,
+                "unsupported snapshot version {} (this build reads up to {})",
+                v, SNAPSHOT_VERSION
+            ),
+            SnapshotError::Invalid(msg) => write!(f, "invalid snapshot: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+// ========== MIGRATIONS ==========
+/// `MIGRATIONS[i]` upgrades a raw snapshot from version `i + 1` to `i + 2`.
+type Migration = fn(&mut Value) -> Result<(), SnapshotError>;
+
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+];
+
+/// v2: per-user sentiment baseline. Older sessions start uncalibrated.
+fn migrate_v1_to_v2(value: &mut Value) -> Result<(), SnapshotError> {
+    value["baseline"] = serde_json::to_value(SentimentBaseline::new()).unwrap();
+    Ok(())
+}
+
+/// v3: last detected mood, used as the prior mood in explanations.
+fn migrate_v2_to_v3(value: &mut Value) -> Result<(), SnapshotError> {
+    value["last_mood"] = Value::Null;
+    Ok(())
+}
+
+/// v4: MIRA's own affect. Older sessions start from the resting state.
+fn migrate_v3_to_v4(value: &mut Value) -> Result<(), SnapshotError> {
+    value["affect"] = serde_json::to_value(Affect::new()).unwrap();
+    Ok(())
+}
+
+/// v5: the session RNG. Older sessions never recorded one and get a fresh
+/// seed.
+fn migrate_v4_to_v5(value: &mut Value) -> Result<(), SnapshotError> {
+    value["rng"] = serde_json::to_value(Rng::from_entropy()).unwrap();
+    Ok(())
+}
+
+/// v6: how the user talks. Older sessions start with nothing learned.
+fn migrate_v5_to_v6(value: &mut Value) -> Result<(), SnapshotError> {
+    value["mirror"] = serde_json::to_value(Mirror::new()).unwrap();
+    Ok(())
+}
+
+/// Run every migration from version `from` up to `SNAPSHOT_VERSION`.
+fn migrate(value: &mut Value, from: u32) -> Result<(), SnapshotError> {
+    for step in from..SNAPSHOT_VERSION {
+        MIGRATIONS[(step - 1) as usize](value)?;
+        value["version"] = Value::from(step + 1);
+    }
+    Ok(())
+}
+
+// ========== ENCODE / DECODE ==========
+impl SessionSnapshot {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap()
+    }
+
+    /// Parse any supported snapshot version, migrate it to the current
+    /// schema and validate the result.
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        let mut value: Value =
+            serde_json::from_str(json).map_err(|e| SnapshotError::Malformed(e.to_string()))?;
+
+        let version = value
+            .get("version")
+            .and_then(Value::as_u64)
+            .ok_or_else(|| SnapshotError::Malformed("missing `version`".to_string()))?;
+        let version =
+            u32::try_from(version).map_err(|_| SnapshotError::UnsupportedVersion(u32::MAX))?;
+
+        if version == 0 || version > SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version));
+        }
+
+        migrate(&mut value, version)?;
+
+        let snapshot: SessionSnapshot =
+            serde_json::from_value(value).map_err(|e| SnapshotError::Malformed(e.to_string()))?;
+        snapshot.validate()?;
+        Ok(snapshot)
+    }
+
+    pub fn validate(&self) -> Result<(), SnapshotError> {
+        if self.mood_cache.len() > MAX_ENTRIES || self.expression_history.len() > MAX_ENTRIES {
+            return Err(SnapshotError::Invalid(format!(
+                "too many entries (limit {})",
+                MAX_ENTRIES
+            )));
+        }
+
+        if let Some(mood) = self
+            .mood_counts
+            .keys()
+            .find(|m| !MOODS.contains(&m.as_str()))
+        {
+            return Err(SnapshotError::Invalid(format!(
+                "unknown mood in counts: {}",
+                mood
+            )));
+        }
+
+        if let Some((input, mood)) = self
+            .mood_cache
+            .iter()
+            .find(|(_, m)| !MOODS.contains(&m.as_str()))
+        {
+            return Err(SnapshotError::Invalid(format!(
+                "unknown mood '{}' cached for '{}'",
+                mood, input
+            )));
+        }
+
+        if let Some(input) = self.mood_cache.keys().find(|k| k.to_lowercase() != **k) {
+            return Err(SnapshotError::Invalid(format!(
+                "mood cache key is not normalized: {}",
+                input
+            )));
+        }
+
+        if self.expression_history.iter().any(|(_, at)| !at.is_finite()) {
+            return Err(SnapshotError::Invalid(
+                "expression history timestamp is not finite".to_string(),
+            ));
+        }
+
+        if let Some(mood) = self.last_mood.as_deref().filter(|m| !MOODS.contains(m)) {
+            return Err(SnapshotError::Invalid(format!("unknown last mood: {}", mood)));
+        }
+
+        if !self.baseline.is_valid() {
+            return Err(SnapshotError::Invalid("baseline is not finite".to_string()));
+        }
+
+        if !self.affect.is_valid() {
+            return Err(SnapshotError::Invalid("affect is out of range".to_string()));
+        }
+
+        if !self.mirror.is_valid() {
+            return Err(SnapshotError::Invalid("mirror scores are out of range".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn v1() -> Value {
+        json!({
+            "version": 1,
+            "mood_counts": {"chill": 2, "playful": 1},
+            "mood_cache": {"halo": "chill"},
+            "expression_history": [["f01", 1000.0]],
+        })
+    }
+
+    #[test]
+    fn v1_snapshot_migrates_to_the_current_version() {
+        let snapshot = SessionSnapshot::from_json(&v1().to_string()).unwrap();
+        assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+        assert_eq!(snapshot.mood_counts["chill"], 2);
+        assert_eq!(snapshot.last_mood, None);
+        assert_eq!(snapshot.affect, Affect::new());
+        assert_eq!(snapshot.mirror, Mirror::new());
+    }
+
+    #[test]
+    fn current_snapshot_round_trips() {
+        let mut snapshot = SessionSnapshot::from_json(&v1().to_string()).unwrap();
+        snapshot.last_mood = Some("playful".to_string());
+        snapshot.mirror.observe(&crate::register::RegisterModel::default(), "kak, gue capek");
+        let json = snapshot.to_json();
+        assert_eq!(SessionSnapshot::from_json(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn future_and_zero_versions_are_rejected() {
+        for version in [0, SNAPSHOT_VERSION + 1] {
+            let mut value = v1();
+            value["version"] = Value::from(version);
+            assert_eq!(
+                SessionSnapshot::from_json(&value.to_string()),
+                Err(SnapshotError::UnsupportedVersion(version))
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_snapshots_are_rejected() {
+        let mut missing_field = v1();
+        missing_field.as_object_mut().unwrap().remove("mood_cache");
+        for json in ["not json", "{}", "{\"version\": \"1\"}", &missing_field.to_string()] {
+            assert!(
+                matches!(SessionSnapshot::from_json(json), Err(SnapshotError::Malformed(_))),
+                "{}",
+                json
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_snapshots_are_rejected() {
+        let edits: [fn(&mut Value); 3] = [
+            |v| v["mood_counts"]["grumpy"] = Value::from(1),
+            |v| v["mood_cache"]["Halo"] = Value::from("chill"),
+            |v| v["last_mood"] = Value::from("grumpy"),
+        ];
+        for edit in edits {
+            let mut value = v1();
+            value["version"] = Value::from(3);
+            value["baseline"] = serde_json::to_value(SentimentBaseline::new()).unwrap();
+            value["last_mood"] = Value::Null;
+            edit(&mut value);
+            assert!(
+                matches!(SessionSnapshot::from_json(&value.to_string()), Err(SnapshotError::Invalid(_))),
+                "{}",
+                value
+            );
+        }
+    }
+}
@@ -0,0 +1,319 @@
+// src/wasm/src/style.rs
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use crate::emoji::PlacedEmoji;
+use crate::profiles::Decorations;
+use crate::register::Register;
+
+/// Stiff phrases swapped for casual ones ("saya mengerti" -> "iya paham")
+/// until `load_style_rules` replaces them.
+const DEFAULT_RULES: &str = include_str!("../data/humanize_rules.json");
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct StyleRule {
+    pub id: String,
+    /// A literal phrase, or a regex when `regex` is set.
+    pub from: String,
+    /// Replacement; regex rules may use `$1` / `${name}`.
+    pub to: String,
+    /// Higher wins when two rules match overlapping text.
+    #[serde(default)]
+    pub priority: i32,
+    #[serde(default)]
+    pub regex: bool,
+    #[serde(default = "default_true")]
+    pub whole_word: bool,
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct RuleFile {
+    rules: Vec<StyleRule>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleError {
+    Malformed(String),
+    DuplicateId(String),
+    InvalidPattern { id: String, message: String },
+}
+
+impl fmt::Display for StyleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleError::Malformed(msg) => write!(f, "malformed style rules: {}", msg),
+            StyleError::DuplicateId(id) => write!(f, "style rule '{}' is declared twice", id),
+            StyleError::InvalidPattern { id, message } => {
+                write!(f, "style rule '{}' has an invalid pattern: {}", id, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for StyleError {}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct FiredRule {
+    pub id: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StyleOutput {
+    pub text: String,
+    pub fired: Vec<FiredRule>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct HumanizeReport {
+    pub text: String,
+    pub fired: Vec<FiredRule>,
+    pub truncated: bool,
+    pub register: Register,
+    pub profile: String,
+    #[serde(flatten)]
+    pub added: Decorations,
+    pub emoji: Vec<PlacedEmoji>,
+    pub emoji_skipped: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+struct CompiledRule {
+    rule: StyleRule,
+    regex: Regex,
+}
+
+/// Follow the case of the text being replaced: "SAYA" -> "AKU",
+/// "Saya" -> "Aku", "saya" -> "aku". A capital only carries over at the
+/// start of a sentence; mid-sentence "Anda" becomes "kamu". A replacement
+/// written with a capital ("Anda") always keeps it.
+fn match_case(matched: &str, replacement: &str, sentence_start: bool) -> String {
+    let letters: Vec<char> = matched.chars().filter(|c| c.is_alphabetic()).collect();
+    if letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()) {
+        return replacement.to_uppercase();
+    }
+
+    let upper = sentence_start && letters.first().is_some_and(|c| c.is_uppercase());
+    let mut chars = replacement.chars();
+    match chars.next() {
+        Some(first) if upper => first.to_uppercase().chain(chars).collect(),
+        Some(first) if first.is_uppercase() => replacement.to_string(),
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Text no rule may rewrite: code (fenced and inline) and quotations.
+pub fn verbatim_spans(text: &str) -> Vec<Range<usize>> {
+    static VERBATIM: OnceLock<Regex> = OnceLock::new();
+    let re = VERBATIM.get_or_init(|| {
+        Regex::new(r#"(?s)```.*?(?:```|\z)|`[^`\n]*`|"[^"\n]*"|“[^”\n]*”"#).unwrap()
+    });
+    re.find_iter(text).map(|m| m.range()).collect()
+}
+
+// ========== STYLE ENGINE ==========
+/// Phrase-rewriting rules compiled once. All rules match against the
+/// original text in one pass, so one rule's output is never rewritten by
+/// another; where matches overlap, the higher priority wins. Code and
+/// quotations are left alone.
+#[derive(Clone, Debug)]
+pub struct StyleEngine {
+    rules: Vec<CompiledRule>,
+}
+
+impl StyleEngine {
+    pub fn from_json(json: &str) -> Result<Self, StyleError> {
+        let file: RuleFile =
+            serde_json::from_str(json).map_err(|e| StyleError::Malformed(e.to_string()))?;
+        Self::compile(file.rules)
+    }
+
+    pub fn compile(rules: Vec<StyleRule>) -> Result<Self, StyleError> {
+        let mut ids = HashSet::new();
+        let mut compiled = Vec::with_capacity(rules.len());
+
+        for rule in rules {
+            if !ids.insert(rule.id.clone()) {
+                return Err(StyleError::DuplicateId(rule.id));
+            }
+            let body = if rule.regex {
+                rule.from.clone()
+            } else {
+                regex::escape(&rule.from).replace(' ', r"\s+")
+            };
+            let pattern = if rule.whole_word {
+                format!(r"\b(?:{})\b", body)
+            } else {
+                body
+            };
+            let regex = RegexBuilder::new(&pattern)
+                .case_insensitive(!rule.case_sensitive)
+                .build()
+                .map_err(|e| StyleError::InvalidPattern {
+                    id: rule.id.clone(),
+                    message: e.to_string(),
+                })?;
+            compiled.push(CompiledRule { rule, regex });
+        }
+
+        // Stable sort keeps file order between equal priorities
+        compiled.sort_by_key(|c| std::cmp::Reverse(c.rule.priority));
+        Ok(Self { rules: compiled })
+    }
+
+    pub fn rules(&self) -> Vec<&StyleRule> {
+        self.rules.iter().map(|c| &c.rule).collect()
+    }
+
+    pub fn apply(&self, text: &str) -> StyleOutput {
+        // (start, end, rule index, replacement), highest priority first
+        let mut accepted: Vec<(usize, usize, usize, String)> = Vec::new();
+        let verbatim = verbatim_spans(text);
+
+        for (index, compiled) in self.rules.iter().enumerate() {
+            for caps in compiled.regex.captures_iter(text) {
+                let m = caps.get(0).unwrap();
+                if m.is_empty() {
+                    continue;
+                }
+                let overlaps = accepted
+                    .iter()
+                    .map(|(start, end, _, _)| *start..*end)
+                    .chain(verbatim.iter().cloned())
+                    .any(|r| m.start() < r.end && r.start < m.end());
+                if overlaps {
+                    continue;
+                }
+
+                let replacement = if compiled.rule.regex {
+                    let mut expanded = String::new();
+                    caps.expand(&compiled.rule.to, &mut expanded);
+                    expanded
+                } else {
+                    compiled.rule.to.clone()
+                };
+                let replacement = if compiled.rule.case_sensitive {
+                    replacement
+                } else {
+                    let before = text[..m.start()].trim_end_matches([' ', '\t']);
+                    let sentence_start = before.is_empty()
+                        || before.ends_with(['.', '!', '?', '…', '\n', '"', '“', '(', '*', '-']);
+                    match_case(m.as_str(), &replacement, sentence_start)
+                };
+                accepted.push((m.start(), m.end(), index, replacement));
+            }
+        }
+
+        accepted.sort_by_key(|(start, _, _, _)| *start);
+        let mut output = String::with_capacity(text.len());
+        let mut cursor = 0;
+        let mut counts = vec![0usize; self.rules.len()];
+        for (start, end, index, replacement) in &accepted {
+            output.push_str(&text[cursor..*start]);
+            output.push_str(replacement);
+            cursor = *end;
+            counts[*index] += 1;
+        }
+        output.push_str(&text[cursor..]);
+
+        let fired = self
+            .rules
+            .iter()
+            .zip(counts)
+            .filter(|(_, count)| *count > 0)
+            .map(|(compiled, count)| FiredRule {
+                id: compiled.rule.id.clone(),
+                count,
+            })
+            .collect();
+
+        StyleOutput {
+            text: output,
+            fired,
+        }
+    }
+}
+
+impl Default for StyleEngine {
+    fn default() -> Self {
+        Self::from_json(DEFAULT_RULES).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(rules: &str) -> StyleEngine {
+        StyleEngine::from_json(&format!(r#"{{"rules": [{}]}}"#, rules)).unwrap()
+    }
+
+    const SAYA: &str = r#"{"id": "saya", "from": "saya", "to": "aku"}"#;
+
+    #[test]
+    fn whole_word_rules_leave_longer_words_alone() {
+        let output = engine(SAYA).apply("saya sayang kamu, sayangku");
+        assert_eq!(output.text, "aku sayang kamu, sayangku");
+    }
+
+    #[test]
+    fn case_follows_the_sentence() {
+        let engine = StyleEngine::default();
+        assert_eq!(engine.apply("Saya merasa capek.").text, "Aku rasa capek.");
+        assert_eq!(engine.apply("Jujur, Saya merasa capek.").text, "Jujur, aku rasa capek.");
+        assert_eq!(engine.apply("SAYA MERASA capek").text, "AKU RASA capek");
+    }
+
+    #[test]
+    fn higher_priority_wins_an_overlap() {
+        let engine = engine(&format!(
+            r#"{}, {{"id": "saya-bisa", "from": "saya bisa", "to": "aku bisa banget", "priority": 5}}"#,
+            SAYA
+        ));
+        let output = engine.apply("saya bisa, saya mau");
+        assert_eq!(output.text, "aku bisa banget, aku mau");
+        // One pass: "aku" written by one rule is never rewritten by another
+        assert_eq!(
+            output.fired,
+            [
+                FiredRule { id: "saya-bisa".to_string(), count: 1 },
+                FiredRule { id: "saya".to_string(), count: 1 },
+            ]
+        );
+    }
+
+    #[test]
+    fn quotes_and_code_are_verbatim() {
+        let output = engine(SAYA).apply("saya bilang \"saya capek\", lalu `saya` di kode");
+        assert_eq!(output.text, "aku bilang \"saya capek\", lalu `saya` di kode");
+        assert_eq!(output.fired, [FiredRule { id: "saya".to_string(), count: 1 }]);
+    }
+
+    #[test]
+    fn fired_reports_counts_and_skips_idle_rules() {
+        let output = StyleEngine::default().apply("Silakan coba. Silakan lagi, mohon maaf.");
+        assert_eq!(output.text, "Coba coba. Coba lagi, tolong maaf.");
+        let fired: Vec<(&str, usize)> = output.fired.iter().map(|f| (f.id.as_str(), f.count)).collect();
+        assert_eq!(fired, [("silakan", 2), ("mohon", 1)]);
+    }
+
+    #[test]
+    fn duplicate_ids_and_bad_patterns_are_rejected() {
+        let duplicate = StyleEngine::from_json(&format!(r#"{{"rules": [{}, {}]}}"#, SAYA, SAYA));
+        assert_eq!(duplicate.unwrap_err(), StyleError::DuplicateId("saya".to_string()));
+
+        let bad = r#"{"rules": [{"id": "x", "from": "(", "to": "", "regex": true}]}"#;
+        assert!(matches!(StyleEngine::from_json(bad), Err(StyleError::InvalidPattern { .. })));
+    }
+}
@@ -0,0 +1,448 @@
+// src/wasm/src/truncate.rs
+use regex::Regex;
+use serde::Serialize;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+/// Words that end in a period without ending the sentence.
+const ABBREVIATIONS: [&str; 12] = [
+    "dll", "dsb", "dst", "dkk", "sdh", "tsb", "no", "hlm", "dr", "prof", "mr", "mrs",
+];
+
+const TERMINATORS: [char; 4] = ['.', '!', '?', '…'];
+/// Closers that belong to the sentence before them: quotes, brackets,
+/// markdown emphasis.
+const CLOSERS: [char; 9] = ['"', '\'', '”', '’', ')', ']', '*', '_', '~'];
+
+/// Spans a sentence boundary can't fall inside: URLs, inline code and
+/// markdown links.
+pub fn protected_spans(text: &str) -> Vec<Range<usize>> {
+    static PROTECTED: OnceLock<Regex> = OnceLock::new();
+    let re = PROTECTED.get_or_init(|| {
+        Regex::new(r"`[^`\n]*`|\[[^\]\n]*\]\([^)\s]*\)|(?:https?://|www\.)\S*[^\s.,!?…)]").unwrap()
+    });
+    re.find_iter(text).map(|m| m.range()).collect()
+}
+
+/// Rough token count for budgets: about four characters per token, counted
+/// per word so short words still cost one.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|w| w.chars().count().div_ceil(4))
+        .sum()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UnitKind {
+    Sentence,
+    ListItem, // also headings: one line, never split
+    Code,
+}
+
+/// A piece of the text that is kept or dropped as a whole.
+#[derive(Clone, Debug, PartialEq)]
+struct Unit {
+    kind: UnitKind,
+    range: Range<usize>,
+}
+
+// ========== SEGMENTATION ==========
+fn is_list_or_heading(line: &str) -> bool {
+    static LIST: OnceLock<Regex> = OnceLock::new();
+    let re = LIST.get_or_init(|| Regex::new(r"^\s*(?:[-*+]|\d+[.)]|#{1,6})\s+\S").unwrap());
+    re.is_match(line)
+}
+
+/// Split `text` into blocks (code fences, list items, headings) and
+/// sentences of the paragraphs between them.
+fn units(text: &str) -> Vec<Unit> {
+    let mut units = Vec::new();
+    let mut paragraph: Option<Range<usize>> = None;
+    let mut fence: Option<(usize, &str)> = None;
+    let mut offset = 0;
+
+    let flush = |paragraph: &mut Option<Range<usize>>, units: &mut Vec<Unit>| {
+        if let Some(range) = paragraph.take() {
+            units.extend(sentences(text, range));
+        }
+    };
+
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        let end = start + line.trim_end().len();
+        offset += line.len();
+        let trimmed = line.trim_start();
+
+        if let Some((fence_start, marker)) = fence {
+            if trimmed.starts_with(marker) {
+                units.push(Unit {
+                    kind: UnitKind::Code,
+                    range: fence_start..end,
+                });
+                fence = None;
+            }
+            continue;
+        }
+
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            flush(&mut paragraph, &mut units);
+            fence = Some((start, &trimmed[..3]));
+            continue;
+        }
+        if trimmed.trim().is_empty() {
+            flush(&mut paragraph, &mut units);
+            continue;
+        }
+        if is_list_or_heading(line) {
+            flush(&mut paragraph, &mut units);
+            units.push(Unit {
+                kind: UnitKind::ListItem,
+                range: start + (line.len() - trimmed.len())..end,
+            });
+            continue;
+        }
+
+        // An indented line right after a list item continues it
+        let continues_item = paragraph.is_none()
+            && line.starts_with([' ', '\t'])
+            && units.last().is_some_and(|u| u.kind == UnitKind::ListItem && u.range.end + 1 >= start);
+        if continues_item {
+            units.last_mut().unwrap().range.end = end;
+            continue;
+        }
+
+        match &mut paragraph {
+            Some(range) => range.end = end,
+            None => paragraph = Some(start + (line.len() - trimmed.len())..end),
+        }
+    }
+
+    flush(&mut paragraph, &mut units);
+    if let Some((fence_start, _)) = fence {
+        // Unclosed fence: the rest of the text is code
+        units.push(Unit {
+            kind: UnitKind::Code,
+            range: fence_start..text.trim_end().len(),
+        });
+    }
+    units
+}
+
+/// Sentences of one paragraph, each keeping its own punctuation and any
+/// closing quote or trailing emoji.
+fn sentences(text: &str, paragraph: Range<usize>) -> Vec<Unit> {
+    let segment = &text[paragraph.clone()];
+    let protected = protected_spans(segment);
+    let chars: Vec<(usize, char)> = segment.char_indices().collect();
+    let byte_at = |i: usize| chars.get(i).map_or(segment.len(), |(b, _)| *b);
+
+    let mut result = Vec::new();
+    let mut sentence_start = 0;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let (byte, c) = chars[i];
+        if !TERMINATORS.contains(&c) || protected.iter().any(|r| r.contains(&byte)) {
+            i += 1;
+            continue;
+        }
+
+        // Decimal point: "3.5"
+        let digit = |j: Option<usize>| j.and_then(|j| chars.get(j)).is_some_and(|(_, c)| c.is_ascii_digit());
+        if c == '.' && digit(i.checked_sub(1)) && digit(Some(i + 1)) {
+            i += 1;
+            continue;
+        }
+
+        let run_start = i;
+        while i < chars.len() && TERMINATORS.contains(&chars[i].1) {
+            i += 1;
+        }
+        let run: String = chars[run_start..i].iter().map(|(_, c)| c).collect();
+        while i < chars.len() && CLOSERS.contains(&chars[i].1) {
+            i += 1;
+        }
+        let mut end = i;
+
+        // "file.txt", "v2.0" and similar: no space after, no boundary
+        if i < chars.len() && !chars[i].1.is_whitespace() {
+            continue;
+        }
+
+        let word_before: String = chars[sentence_start..run_start]
+            .iter()
+            .rev()
+            .map(|(_, c)| *c)
+            .take_while(|c| c.is_alphabetic())
+            .collect::<Vec<_>>()
+            .into_iter()
+            .rev()
+            .collect();
+        if run == "." && ABBREVIATIONS.contains(&word_before.to_lowercase().as_str()) {
+            continue;
+        }
+
+        let mut next = i;
+        while next < chars.len() && chars[next].1.is_whitespace() {
+            next += 1;
+        }
+        // "hmm... aku rasa" is one sentence trailing off, not two
+        let ellipsis = run == "..." || run == "…";
+        if ellipsis && next < chars.len() && chars[next].1.is_lowercase() {
+            continue;
+        }
+
+        // An emoji or kaomoji right after the punctuation belongs to it
+        let token_end = (next..chars.len())
+            .find(|&j| chars[j].1.is_whitespace())
+            .unwrap_or(chars.len());
+        let symbol_only = next < token_end
+            && chars[next..token_end].iter().all(|(_, c)| !c.is_alphanumeric());
+        if symbol_only {
+            end = token_end;
+            next = token_end;
+            while next < chars.len() && chars[next].1.is_whitespace() {
+                next += 1;
+            }
+        }
+
+        result.push(Unit {
+            kind: UnitKind::Sentence,
+            range: paragraph.start + byte_at(sentence_start)..paragraph.start + byte_at(end),
+        });
+        sentence_start = next;
+        i = next;
+    }
+
+    let tail = &segment[byte_at(sentence_start)..];
+    if !tail.trim().is_empty() {
+        result.push(Unit {
+            kind: UnitKind::Sentence,
+            range: paragraph.start + byte_at(sentence_start)..paragraph.start + segment.trim_end().len(),
+        });
+    }
+    result
+}
+
+/// Byte ranges of the prose sentences in `text`, skipping list items,
+/// headings and code.
+pub fn sentence_ranges(text: &str) -> Vec<Range<usize>> {
+    units(text)
+        .into_iter()
+        .filter(|u| u.kind == UnitKind::Sentence)
+        .map(|u| u.range)
+        .collect()
+}
+
+/// Every unit of `text` in order: sentences, list items (and headings)
+/// and code blocks, with their byte ranges.
+pub fn segments(text: &str) -> Vec<(UnitKind, Range<usize>)> {
+    units(text).into_iter().map(|u| (u.kind, u.range)).collect()
+}
+
+/// End of the last word of `sentence`, before its closing punctuation,
+/// quotes or emoji; None if it has no word.
+pub fn last_word_end(sentence: &str) -> Option<usize> {
+    sentence
+        .char_indices()
+        .rev()
+        .find(|(_, c)| c.is_alphanumeric())
+        .map(|(i, c)| i + c.len_utf8())
+}
+
+// ========== TRUNCATION ==========
+/// Budgets for `truncate`; `None` means unlimited. Sentences count every
+/// kept unit: a sentence, a list item, a heading or a code block.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct TruncateOptions {
+    pub max_sentences: Option<usize>,
+    pub max_chars: Option<usize>,
+    pub max_tokens: Option<usize>,
+}
+
+impl TruncateOptions {
+    /// Zero means no limit, for the JS-facing setters.
+    pub fn from_limits(max_sentences: u32, max_chars: u32, max_tokens: u32) -> Self {
+        let limit = |n: u32| (n > 0).then_some(n as usize);
+        Self {
+            max_sentences: limit(max_sentences),
+            max_chars: limit(max_chars),
+            max_tokens: limit(max_tokens),
+        }
+    }
+
+    fn fits(&self, text: &str, sentences: usize) -> bool {
+        self.max_sentences.is_none_or(|max| sentences <= max)
+            && self.max_chars.is_none_or(|max| text.chars().count() <= max)
+            && self.max_tokens.is_none_or(|max| estimate_tokens(text) <= max)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct Truncation {
+    pub text: String,
+    pub truncated: bool,
+    pub units_kept: usize,
+    pub units_total: usize,
+}
+
+/// Keep the longest prefix of whole units that fits every budget, with the
+/// original text (punctuation, spacing, markdown) untouched. If not even
+/// the first sentence fits, it is cut at a word boundary outside links and
+/// code, and ends in "…"; a first list item or code block is kept whole.
+pub fn truncate(text: &str, options: &TruncateOptions) -> Truncation {
+    let units = units(text);
+    let total = units.len();
+
+    let kept = units
+        .iter()
+        .enumerate()
+        .take_while(|(i, unit)| options.fits(&text[..unit.range.end], i + 1))
+        .count();
+
+    if kept > 0 || total == 0 {
+        let end = units.get(kept.wrapping_sub(1)).map_or(0, |u| u.range.end);
+        let text = if kept == total { text.trim() } else { text[..end].trim() };
+        return Truncation {
+            text: text.to_string(),
+            truncated: kept < total,
+            units_kept: kept,
+            units_total: total,
+        };
+    }
+
+    let first = &units[0];
+    let text = if first.kind == UnitKind::Sentence {
+        cut_sentence(&text[first.range.clone()], options)
+    } else {
+        text[first.range.clone()].trim().to_string()
+    };
+    Truncation {
+        text,
+        truncated: true,
+        units_kept: 1,
+        units_total: total,
+    }
+}
+
+fn cut_sentence(sentence: &str, options: &TruncateOptions) -> String {
+    let protected = protected_spans(sentence);
+    let cuts = sentence
+        .char_indices()
+        .filter(|(b, c)| c.is_whitespace() && !protected.iter().any(|r| r.contains(b)))
+        .map(|(b, _)| b);
+
+    let mut best = None;
+    for cut in cuts {
+        let candidate = format!("{}…", sentence[..cut].trim_end());
+        if options.fits(&candidate, 1) {
+            best = Some(candidate);
+        } else {
+            break;
+        }
+    }
+    // Nothing fits: the first word is the least we can say
+    best.unwrap_or_else(|| {
+        let first_word = sentence.split_whitespace().next().unwrap_or("");
+        format!("{}…", first_word)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn url_span_stops_before_trailing_punctuation() {
+        let text = "Cek https://mira.id/faq. Atau (www.mira.id/faq), ya!";
+        let urls: Vec<&str> = protected_spans(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(urls, ["https://mira.id/faq", "www.mira.id/faq"]);
+    }
+
+    fn sentences_of(text: &str) -> Vec<&str> {
+        sentence_ranges(text).into_iter().map(|r| &text[r]).collect()
+    }
+
+    #[test]
+    fn decimal_point_is_not_a_boundary() {
+        assert_eq!(
+            sentences_of("Nilainya 3.5 dari 5. Lumayan kok."),
+            ["Nilainya 3.5 dari 5.", "Lumayan kok."]
+        );
+    }
+
+    #[test]
+    fn ellipsis_before_lowercase_trails_off() {
+        assert_eq!(
+            sentences_of("Hmm... aku rasa bisa. Tapi… Nanti dulu."),
+            ["Hmm... aku rasa bisa.", "Tapi…", "Nanti dulu."]
+        );
+    }
+
+    #[test]
+    fn question_and_exclamation_end_sentences() {
+        assert_eq!(
+            sentences_of("Kamu udah makan? Belum?! Ayo makan."),
+            ["Kamu udah makan?", "Belum?!", "Ayo makan."]
+        );
+    }
+
+    #[test]
+    fn emoji_after_punctuation_stays_with_the_sentence() {
+        assert_eq!(
+            sentences_of("Seru banget! 😆 Besok lagi ya."),
+            ["Seru banget! 😆", "Besok lagi ya."]
+        );
+    }
+
+    #[test]
+    fn list_items_and_code_are_their_own_units() {
+        let text = "Caranya gini.\n- buka menu\n- pilih setting\n```\nlet x = 1. y\n```\nUdah deh.";
+        let kinds: Vec<UnitKind> = segments(text).into_iter().map(|(kind, _)| kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                UnitKind::Sentence,
+                UnitKind::ListItem,
+                UnitKind::ListItem,
+                UnitKind::Code,
+                UnitKind::Sentence
+            ]
+        );
+        assert_eq!(sentences_of(text), ["Caranya gini.", "Udah deh."]);
+    }
+
+    #[test]
+    fn sentence_budget_keeps_whole_units() {
+        let options = TruncateOptions::from_limits(2, 0, 0);
+        let cut = truncate("Satu. Dua.\n- tiga\nEmpat.", &options);
+        assert_eq!(cut.text, "Satu. Dua.");
+        assert!(cut.truncated);
+        assert_eq!((cut.units_kept, cut.units_total), (2, 4));
+    }
+
+    #[test]
+    fn char_budget_drops_sentences_then_cuts_a_word_boundary() {
+        let text = "Aku ngerti kok. Kamu capek banget ya hari ini.";
+        let cut = truncate(text, &TruncateOptions::from_limits(0, 20, 0));
+        assert_eq!(cut.text, "Aku ngerti kok.");
+
+        let cut = truncate(text, &TruncateOptions::from_limits(0, 8, 0));
+        assert_eq!(cut.text, "Aku…");
+        assert!(cut.text.chars().count() <= 8);
+    }
+
+    #[test]
+    fn token_budget_counts_words() {
+        assert_eq!(estimate_tokens("aku ngerti banget"), 5);
+        let cut = truncate("Aku ngerti. Kamu capek ya.", &TruncateOptions::from_limits(0, 0, 3));
+        assert_eq!(cut.text, "Aku ngerti.");
+    }
+
+    #[test]
+    fn sentence_ends_right_after_a_url() {
+        let text = "Cek https://mira.id/faq.html. Terus kabarin ya.";
+        let sentences: Vec<&str> = sentence_ranges(text).into_iter().map(|r| &text[r]).collect();
+        assert_eq!(sentences, ["Cek https://mira.id/faq.html.", "Terus kabarin ya."]);
+    }
+}